@@ -84,6 +84,74 @@ mod trie {
         );
     }
 
+    pub fn build_with_capacity(_: &mut Criterion) {
+        let items = 10_000;
+
+        super::c().bench_function(
+            &format!(
+                "[{}] Trie::build() with_capacity({}) items",
+                super::git_hash(),
+                items
+            ),
+            move |b| {
+                b.iter_batched(
+                    || &TRIE_EDICT,
+                    |_trie| {
+                        let mut builder = TrieBuilder::with_capacity(items);
+
+                        let repo_root = env::var("CARGO_MANIFEST_DIR")
+                            .expect("CARGO_MANIFEST_DIR environment variable must be set.");
+                        let edict2_path = format!("{}/benches/edict.furigana", repo_root);
+
+                        let mut n_words = 0;
+                        for result in BufReader::new(File::open(edict2_path).unwrap()).lines() {
+                            let l = result.unwrap();
+                            builder.push(l);
+                            n_words += 1;
+                            if n_words >= items {
+                                break;
+                            }
+                        }
+                        black_box(builder.build())
+                    },
+                    BatchSize::SmallInput,
+                )
+            },
+        );
+    }
+
+    pub fn build_from_sorted_unchecked(_: &mut Criterion) {
+        let items = 10_000;
+
+        super::c().bench_function(
+            &format!(
+                "[{}] TrieBuilder::build_from_sorted_unchecked() {} items",
+                super::git_hash(),
+                items
+            ),
+            move |b| {
+                b.iter_batched(
+                    || {
+                        let repo_root = env::var("CARGO_MANIFEST_DIR")
+                            .expect("CARGO_MANIFEST_DIR environment variable must be set.");
+                        let edict2_path = format!("{}/benches/edict.furigana", repo_root);
+                        let mut keys: Vec<Vec<u8>> =
+                            BufReader::new(File::open(edict2_path).unwrap())
+                                .lines()
+                                .take(items)
+                                .map(|l| l.unwrap().into_bytes())
+                                .collect();
+                        keys.sort();
+                        keys.dedup();
+                        keys
+                    },
+                    |keys| black_box(TrieBuilder::build_from_sorted_unchecked(keys)),
+                    BatchSize::SmallInput,
+                )
+            },
+        );
+    }
+
     pub fn exact_match(_: &mut Criterion) {
         let times = 100;
 
@@ -113,6 +181,60 @@ mod trie {
         );
     }
 
+    pub fn exact_match_many(_: &mut Criterion) {
+        let times = 1000;
+        let queries: Vec<String> = {
+            let repo_root = env::var("CARGO_MANIFEST_DIR")
+                .expect("CARGO_MANIFEST_DIR environment variable must be set.");
+            let edict2_path = format!("{}/benches/edict.furigana", repo_root);
+            BufReader::new(File::open(edict2_path).unwrap())
+                .lines()
+                .step_by(100)
+                .take(times)
+                .map(|l| l.unwrap())
+                .collect()
+        };
+
+        super::c().bench_function(
+            &format!(
+                "[{}] Trie::exact_match() {} queries",
+                super::git_hash(),
+                times
+            ),
+            {
+                let queries = queries.clone();
+                move |b| {
+                    b.iter_batched(
+                        || &TRIE_EDICT,
+                        |trie| {
+                            for query in &queries {
+                                assert!(trie.exact_match(query));
+                            }
+                        },
+                        BatchSize::SmallInput,
+                    )
+                }
+            },
+        );
+
+        super::c().bench_function(
+            &format!(
+                "[{}] Trie::exact_match_many() {} queries",
+                super::git_hash(),
+                times
+            ),
+            move |b| {
+                b.iter_batched(
+                    || &TRIE_EDICT,
+                    |trie| {
+                        assert!(trie.exact_match_many(&queries).all(|found| found));
+                    },
+                    BatchSize::SmallInput,
+                )
+            },
+        );
+    }
+
     pub fn predictive_search(_: &mut Criterion) {
         let times = 100;
 
@@ -271,7 +393,10 @@ mod trie {
 criterion_group!(
     benches,
     trie::build,
+    trie::build_with_capacity,
+    trie::build_from_sorted_unchecked,
     trie::exact_match,
+    trie::exact_match_many,
     trie::predictive_search,
     trie::predictive_search_big_output,
     trie::predictive_search_limited_big_output,
@@ -38,11 +38,11 @@
 //!
 //! This means the above code restores the time complexity of _O(m log n)_ for
 //! the loop.
-use crate::{
-    map::Trie,
-    try_collect::{TryCollect, TryFromIterator},
-};
+use crate::map::Trie;
+use crate::try_collect::{TryCollect, TryFromIterator};
 use louds_rs::LoudsNodeNum;
+use std::collections::{BTreeMap, HashSet};
+use std::sync::Arc;
 
 #[derive(Debug, Clone)]
 /// An incremental search of the trie.
@@ -79,12 +79,23 @@ pub enum Answer {
 }
 
 impl Answer {
-    /// Is query answer a prefix?
+    /// Is query answer a prefix? True for [Answer::Prefix] and
+    /// [Answer::PrefixAndMatch].
+    ///
+    /// ```
+    /// use trie_rs::{inc_search::Answer, map::Trie};
+    ///
+    /// let trie = Trie::from_iter([("a", 0), ("app", 1)]);
+    /// let mut search = trie.inc_search();
+    /// assert!(search.query(&b'a').unwrap().is_prefix());
+    /// assert!(!search.query_until("pp").unwrap().is_prefix());
+    /// ```
     pub fn is_prefix(&self) -> bool {
         matches!(self, Answer::Prefix | Answer::PrefixAndMatch)
     }
 
-    /// Is query answer an exact match?
+    /// Is query answer an exact match? True for [Answer::Match] and
+    /// [Answer::PrefixAndMatch].
     pub fn is_match(&self) -> bool {
         matches!(self, Answer::Match | Answer::PrefixAndMatch)
     }
@@ -125,44 +136,116 @@ impl<'a, Label: Ord, Value> IncSearch<'a, Label, Value> {
     /// assert_eq!(inc_search2.query_until("llo"), Ok(Answer::Match));
     ///
     /// ```
+    ///
+    /// # Panics
+    /// In debug builds, if `position` is not a node number of `trie`. A
+    /// position from another trie that happens to be in range is not caught.
     pub fn resume(trie: &'a Trie<Label, Value>, position: Position) -> Self {
+        debug_assert!(
+            position.0 >= 1 && position.0 <= trie.last_node_num().0,
+            "position {:?} is not in this trie",
+            position
+        );
         Self {
             trie,
             node: position,
         }
     }
 
-    /// Query but do not change the node we're looking at on the trie.
+    /// Return the position the search is on, e.g., to cache a common prefix
+    /// and start later searches there with [Trie::inc_search_from].
+    pub fn position(&self) -> Position {
+        self.node
+    }
+
+    /// Return the answer [IncSearch::query] would give for `chr` without
+    /// moving the search, e.g., to preview candidate keystrokes.
+    ///
+    /// ```
+    /// use trie_rs::{inc_search::Answer, map::Trie};
+    ///
+    /// let trie = Trie::from_iter([("a", 0), ("app", 1), ("b", 2)]);
+    /// let mut search = trie.inc_search();
+    /// assert_eq!(search.query(&b'a'), Some(Answer::PrefixAndMatch));
+    /// assert_eq!(search.peek(&b'p'), Some(Answer::Prefix));
+    /// assert_eq!(search.peek(&b'b'), None);
+    /// assert_eq!(search.prefix::<String, _>(), "a");
+    /// ```
     pub fn peek(&self, chr: &Label) -> Option<Answer> {
-        let children_node_nums: Vec<_> = self.trie.children_node_nums(self.node).collect();
-        let res = self
-            .trie
-            .bin_search_by_children_labels(chr, &children_node_nums[..]);
-        match res {
-            Ok(j) => {
-                let node = children_node_nums[j];
-                let is_prefix = self.trie.has_children_node_nums(node);
-                let is_match = self.trie.value(node).is_some();
-                Answer::new(is_prefix, is_match)
-            }
-            Err(_) => None,
-        }
+        self.step(chr).map(|(_, answer)| answer)
     }
 
     /// Query the trie and go to node if there is a match.
     pub fn query(&mut self, chr: &Label) -> Option<Answer> {
+        let (node, answer) = self.step(chr)?;
+        self.node = node;
+        Some(answer)
+    }
+
+    /// Return the child of the current node labeled `chr` and its answer.
+    fn step(&self, chr: &Label) -> Option<(LoudsNodeNum, Answer)> {
         let children_node_nums: Vec<_> = self.trie.children_node_nums(self.node).collect();
-        let res = self
+        let j = self
             .trie
-            .bin_search_by_children_labels(chr, &children_node_nums[..]);
-        match res {
-            Ok(j) => {
-                self.node = children_node_nums[j];
-                let is_prefix = self.trie.has_children_node_nums(self.node);
-                let is_match = self.trie.value(self.node).is_some();
-                Answer::new(is_prefix, is_match)
+            .bin_search_by_children_labels(chr, &children_node_nums[..])
+            .ok()?;
+        let node = children_node_nums[j];
+        let is_prefix = self.trie.has_children_node_nums(node);
+        let is_match = self.trie.is_terminal(node);
+        Answer::new(is_prefix, is_match).map(|answer| (node, answer))
+    }
+
+    /// Return a search positioned at each child of the current position in
+    /// label order, e.g., to walk completions as a tree on demand. Each fork
+    /// is independent of this search and of the others.
+    ///
+    /// ```
+    /// use trie_rs::map::Trie;
+    ///
+    /// let trie = Trie::from_iter([("ab", 0), ("ac", 1), ("b", 2)]);
+    /// let mut search = trie.inc_search();
+    /// search.query(&b'a');
+    /// let forks: Vec<String> = search.fork_children().map(|fork| fork.prefix()).collect();
+    /// assert_eq!(forks, ["ab", "ac"]);
+    /// ```
+    pub fn fork_children(&self) -> impl Iterator<Item = IncSearch<'a, Label, Value>> + 'a {
+        let trie = self.trie;
+        trie.children_node_nums(self.node)
+            .map(move |node| IncSearch::resume(trie, node))
+    }
+
+    /// Advance the search by `chr` and return the value of the entry that
+    /// ends here, if any.
+    ///
+    /// This is [IncSearch::query] for scanning: `feed(chr)` is `Some` exactly
+    /// when `query(chr)` is `Some(answer)` with `answer.is_match()`. Like
+    /// `query`, if `chr` does not continue any entry the search stays where it
+    /// was and `None` is returned; use [IncSearch::peek] to tell that apart
+    /// from a prefix that is not an entry.
+    ///
+    /// ```
+    /// use trie_rs::map::Trie;
+    ///
+    /// let trie = Trie::from_iter([("a", 0), ("app", 1), ("apple", 2)]);
+    /// let mut search = trie.inc_search();
+    /// let found: Vec<Option<&u8>> = "apple".bytes().map(|c| search.feed(&c)).collect();
+    /// assert_eq!(found, [Some(&0), None, Some(&1), None, Some(&2)]);
+    /// ```
+    pub fn feed(&mut self, chr: &Label) -> Option<&'a Value> {
+        self.query(chr)
+            .filter(Answer::is_match)
+            .and_then(|_| self.value())
+    }
+
+    /// Undo the last label queried by going to the parent of the current
+    /// position. Return false if the search is already at the root.
+    pub fn backspace(&mut self) -> bool {
+        match self.trie.parent(self.node) {
+            Some(parent) => {
+                self.node = parent;
+                true
             }
-            Err(_) => None,
+            None => false,
         }
     }
 
@@ -181,6 +264,39 @@ impl<'a, Label: Ord, Value> IncSearch<'a, Label, Value> {
         result.ok_or(i)
     }
 
+    /// Query the trie with the longest run of `query` that matches and return
+    /// the answer there with the number of labels consumed, e.g., to advance
+    /// an input cursor past a token.
+    ///
+    /// Unlike [IncSearch::query_until], running into a label that does not
+    /// match is not an error once at least one label has matched. Return
+    /// `Err(0)` if `query` is empty or its first label does not match.
+    ///
+    /// ```
+    /// use trie_rs::{inc_search::Answer, map::Trie};
+    ///
+    /// let trie = Trie::from_iter([("app", 0), ("apple", 1)]);
+    /// let mut search = trie.inc_search();
+    /// assert_eq!(search.query_until_counted("apps"), Ok((Answer::PrefixAndMatch, 3)));
+    /// assert_eq!(search.prefix::<String, _>(), "app");
+    /// assert_eq!(search.query_until_counted("s"), Err(0));
+    /// ```
+    pub fn query_until_counted(
+        &mut self,
+        query: impl AsRef<[Label]>,
+    ) -> Result<(Answer, usize), usize> {
+        let mut result = None;
+        let mut i = 0;
+        for chr in query.as_ref().iter() {
+            match self.query(chr) {
+                Some(answer) => result = Some(answer),
+                None => break,
+            }
+            i += 1;
+        }
+        result.map(|answer| (answer, i)).ok_or(0)
+    }
+
     /// Return the value at current node. There should be one for any node where
     /// `answer.is_match()` is true.
     pub fn value(&self) -> Option<&'a Value> {
@@ -212,19 +328,52 @@ impl<'a, Label: Ord, Value> IncSearch<'a, Label, Value> {
         Ok(count)
     }
 
-    /// Return the current prefix for this search.
-    pub fn prefix<C, M>(&self) -> C
+    /// Return the labels [IncSearch::goto_longest_prefix] would follow from
+    /// here without moving, or `None` if there are none, e.g., to complete
+    /// up to the next choice.
+    ///
+    /// ```
+    /// use trie_rs::map::Trie;
+    ///
+    /// let trie = Trie::from_iter([("a", 0), ("apple", 1), ("apply", 2)]);
+    /// let mut search = trie.inc_search();
+    /// search.query(&b'a');
+    /// assert_eq!(search.longest_prefix::<String, _>(), Some("ppl".to_string()));
+    /// assert_eq!(search.prefix::<String, _>(), "a");
+    /// ```
+    pub fn longest_prefix<C, M>(&self) -> Option<C>
     where
         C: TryFromIterator<Label, M>,
         Label: Clone,
     {
-        let mut v: Vec<Label> = self
+        let mut end = IncSearch::resume(self.trie, self.node);
+        let count = end.goto_longest_prefix().unwrap_or_else(|count| count);
+        if count == 0 {
+            return None;
+        }
+        let mut labels: Vec<&Label> = self
             .trie
-            .child_to_ancestors(self.node)
-            .map(|node| self.trie.label(node).clone())
+            .child_to_ancestors(end.node)
+            .take(count)
+            .map(|node| self.trie.label(node))
             .collect();
-        v.reverse();
-        v.into_iter().try_collect().expect("Could not collect")
+        labels.reverse();
+        Some(
+            labels
+                .into_iter()
+                .cloned()
+                .try_collect()
+                .expect("Could not collect"),
+        )
+    }
+
+    /// Return the current prefix for this search.
+    pub fn prefix<C, M>(&self) -> C
+    where
+        C: TryFromIterator<Label, M>,
+        Label: Clone,
+    {
+        self.trie.node_key(self.node)
     }
 
     /// Returne the length of the current prefix for this search.
@@ -261,6 +410,419 @@ impl<'a, Label: Ord, Value> IncSearch<'a, Label, Value> {
     }
 }
 
+#[derive(Debug, Clone)]
+/// An incremental search that owns a shared reference to its trie.
+///
+/// Unlike [IncSearch] it does not borrow the trie, so it can be moved into a
+/// spawned thread or task. It is `Send` and `Sync` when `Label` and `Value`
+/// are.
+///
+/// ```
+/// use std::sync::Arc;
+/// use trie_rs::{inc_search::{Answer, OwnedIncSearch}, map::Trie};
+///
+/// let trie = Arc::new(Trie::from_iter([("a", 0), ("app", 1)]));
+/// let mut search = OwnedIncSearch::new(Arc::clone(&trie));
+/// let handle = std::thread::spawn(move || {
+///     assert_eq!(search.query(&b'a'), Some(Answer::PrefixAndMatch));
+///     assert_eq!(search.query_until("pp"), Ok(Answer::Match));
+///     search.value().copied()
+/// });
+/// assert_eq!(handle.join().unwrap(), Some(1));
+/// ```
+pub struct OwnedIncSearch<Label, Value> {
+    trie: Arc<Trie<Label, Value>>,
+    node: LoudsNodeNum,
+}
+
+impl<Label: Ord, Value> OwnedIncSearch<Label, Value> {
+    /// Create a new incremental search for a shared trie.
+    pub fn new(trie: Arc<Trie<Label, Value>>) -> Self {
+        Self {
+            trie,
+            node: LoudsNodeNum(1),
+        }
+    }
+
+    /// Resume an incremental search at a particular point.
+    pub fn resume(trie: Arc<Trie<Label, Value>>, position: Position) -> Self {
+        Self {
+            trie,
+            node: position,
+        }
+    }
+
+    /// Return the position the search is on.
+    pub fn position(&self) -> Position {
+        self.node
+    }
+
+    /// Return the trie being searched.
+    pub fn trie(&self) -> &Arc<Trie<Label, Value>> {
+        &self.trie
+    }
+
+    fn search(&self) -> IncSearch<'_, Label, Value> {
+        IncSearch::resume(&self.trie, self.node)
+    }
+
+    fn step<R>(&mut self, f: impl FnOnce(&mut IncSearch<'_, Label, Value>) -> R) -> R {
+        let mut search = IncSearch::resume(&self.trie, self.node);
+        let result = f(&mut search);
+        self.node = search.node;
+        result
+    }
+
+    /// Return a search positioned at each child of the current position. See
+    /// [IncSearch::fork_children].
+    pub fn fork_children(&self) -> impl Iterator<Item = OwnedIncSearch<Label, Value>> + '_ {
+        self.trie
+            .children_node_nums(self.node)
+            .map(move |node| OwnedIncSearch::resume(Arc::clone(&self.trie), node))
+    }
+
+    /// Query but do not change the node we're looking at on the trie. See
+    /// [IncSearch::peek].
+    pub fn peek(&self, chr: &Label) -> Option<Answer> {
+        self.search().peek(chr)
+    }
+
+    /// Query the trie and go to node if there is a match. See
+    /// [IncSearch::query].
+    pub fn query(&mut self, chr: &Label) -> Option<Answer> {
+        self.step(|search| search.query(chr))
+    }
+
+    /// Query the trie with a sequence. See [IncSearch::query_until].
+    pub fn query_until(&mut self, query: impl AsRef<[Label]>) -> Result<Answer, usize> {
+        self.step(|search| search.query_until(query))
+    }
+
+    /// Query the trie with the longest run of a sequence that matches. See
+    /// [IncSearch::query_until_counted].
+    pub fn query_until_counted(
+        &mut self,
+        query: impl AsRef<[Label]>,
+    ) -> Result<(Answer, usize), usize> {
+        self.step(|search| search.query_until_counted(query))
+    }
+
+    /// Undo the last label queried. See [IncSearch::backspace].
+    pub fn backspace(&mut self) -> bool {
+        self.step(|search| search.backspace())
+    }
+
+    /// Return the value at current node.
+    pub fn value(&self) -> Option<&Value> {
+        self.trie.value(self.node)
+    }
+
+    /// Return the current prefix for this search.
+    pub fn prefix<C, M>(&self) -> C
+    where
+        C: TryFromIterator<Label, M>,
+        Label: Clone,
+    {
+        self.search().prefix()
+    }
+
+    /// Return the labels that complete this search up to the next choice.
+    /// See [IncSearch::longest_prefix].
+    pub fn longest_prefix<C, M>(&self) -> Option<C>
+    where
+        C: TryFromIterator<Label, M>,
+        Label: Clone,
+    {
+        self.search().longest_prefix()
+    }
+
+    /// Return the length of the current prefix for this search.
+    pub fn prefix_len(&self) -> usize {
+        self.search().prefix_len()
+    }
+
+    /// Reset the query.
+    pub fn reset(&mut self) {
+        self.node = LoudsNodeNum(1);
+    }
+}
+
+#[derive(Debug, Clone)]
+/// A [Trie::common_prefix_search] over a query that arrives one label at a
+/// time, e.g., from a network stream, that reports each entry as soon as its
+/// last label is fed. Entries are reported shortest first.
+///
+/// Once a label does not continue any entry, the stream has diverged from the
+/// trie: that label and every one after it are ignored and nothing more is
+/// reported until [CommonPrefixMatcher::reset].
+///
+/// ```
+/// use trie_rs::map::Trie;
+///
+/// let trie = Trie::from_iter([("a", 0), ("app", 1), ("apple", 2), ("b", 3)]);
+/// let mut matcher = trie.common_prefix_matcher();
+/// let found: Vec<Option<(String, &u8)>> = "apples".bytes().map(|c| matcher.feed(&c)).collect();
+/// assert_eq!(found, [
+///     Some(("a".to_string(), &0)),
+///     None,
+///     Some(("app".to_string(), &1)),
+///     None,
+///     Some(("apple".to_string(), &2)),
+///     None,
+/// ]);
+/// assert!(matcher.is_diverged());
+/// assert_eq!(matcher.consumed(), 5);
+/// ```
+pub struct CommonPrefixMatcher<'a, Label, Value> {
+    search: IncSearch<'a, Label, Value>,
+    diverged: bool,
+}
+
+impl<'a, Label: Ord, Value> CommonPrefixMatcher<'a, Label, Value> {
+    /// Create a new matcher for a trie.
+    pub fn new(trie: &'a Trie<Label, Value>) -> Self {
+        Self {
+            search: IncSearch::new(trie),
+            diverged: false,
+        }
+    }
+
+    /// Feed the next label of the query. Return the entry it completes and
+    /// its value, if any.
+    pub fn feed<C, M>(&mut self, chr: &Label) -> Option<(C, &'a Value)>
+    where
+        C: TryFromIterator<Label, M>,
+        Label: Clone,
+    {
+        if self.diverged {
+            return None;
+        }
+        match self.search.query(chr) {
+            Some(answer) if answer.is_match() => Some((self.search.prefix(), self.search.value()?)),
+            Some(_) => None,
+            None => {
+                self.diverged = true;
+                None
+            }
+        }
+    }
+
+    /// Return true if a label fed has not continued any entry.
+    pub fn is_diverged(&self) -> bool {
+        self.diverged
+    }
+
+    /// Return true if no label fed from here on can complete an entry,
+    /// because the stream has diverged or the last entry reported has no
+    /// longer one to follow.
+    pub fn is_done(&self) -> bool {
+        self.diverged || !self.search.trie.has_children_node_nums(self.search.node)
+    }
+
+    /// Return the number of labels fed before the stream diverged, or all of
+    /// them if it has not.
+    pub fn consumed(&self) -> usize {
+        self.search.prefix_len()
+    }
+
+    /// Start matching a new query.
+    pub fn reset(&mut self) {
+        self.search.reset();
+        self.diverged = false;
+    }
+}
+
+/// The result of a [FuzzyIncSearch] query.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct FuzzyAnswer {
+    /// The smallest edit distance from the query to an entry, if any entry is
+    /// within the budget.
+    pub distance: Option<usize>,
+    /// Number of nodes whose key is within the budget of the query.
+    pub states: usize,
+}
+
+impl FuzzyAnswer {
+    /// Is some key prefix within the budget, so that completions remain?
+    pub fn is_prefix(&self) -> bool {
+        self.states > 0
+    }
+
+    /// Is some entry within the budget of the query?
+    pub fn is_match(&self) -> bool {
+        self.distance.is_some()
+    }
+}
+
+#[derive(Debug, Clone)]
+/// An incremental search that tolerates up to `max_distance` edits
+/// (Levenshtein distance), e.g., for typo-tolerant search as you type.
+///
+/// The search keeps the set of nodes whose key is within `max_distance` edits
+/// of the query so far, with each one's distance. Each query advances every
+/// state at once, so a label costs time proportional to the states and their
+/// children rather than to the trie.
+///
+/// # State growth
+///
+/// With budget _k_, query length _m_, and alphabet size _σ_, the states are
+/// the keys within _k_ edits of the query, which in the worst case number
+/// _O((mσ)<sup>k</sup>)_, never more than the trie's nodes. While _m ≤ k_,
+/// every node up to depth _k - m_ is a state. Keep _k_ small, typically 1
+/// or 2.
+///
+/// ```
+/// use trie_rs::map::Trie;
+/// use trie_rs::inc_search::FuzzyIncSearch;
+///
+/// let trie = Trie::from_iter([("apple", 0), ("apply", 1), ("banana", 2)]);
+/// let mut search = FuzzyIncSearch::new(&trie, 1);
+/// for chr in "aple".bytes() {
+///     search.query(&chr);
+/// }
+/// assert_eq!(search.answer().distance, Some(1));
+/// let completions: Vec<(String, &u8, usize)> = search.completions();
+/// assert_eq!(completions, [("apple".to_string(), &0, 1)]);
+///
+/// search.reset();
+/// search.query_until("apl");
+/// let completions: Vec<(String, &u8, usize)> = search.completions();
+/// assert_eq!(completions, [("apple".to_string(), &0, 1), ("apply".to_string(), &1, 1)]);
+/// ```
+pub struct FuzzyIncSearch<'a, Label, Value> {
+    trie: &'a Trie<Label, Value>,
+    max_distance: usize,
+    /// Node numbers within `max_distance` of the query and their distance.
+    states: BTreeMap<u64, usize>,
+}
+
+impl<'a, Label: Ord, Value> FuzzyIncSearch<'a, Label, Value> {
+    /// Create a new fuzzy incremental search allowing `max_distance` edits.
+    pub fn new(trie: &'a Trie<Label, Value>, max_distance: usize) -> Self {
+        let mut search = Self {
+            trie,
+            max_distance,
+            states: BTreeMap::new(),
+        };
+        search.reset();
+        search
+    }
+
+    /// Extend the query by `chr`.
+    ///
+    /// Unlike [IncSearch::query], the search always moves. Once no state is
+    /// left, nothing further can match; use [FuzzyIncSearch::reset] to start
+    /// over.
+    pub fn query(&mut self, chr: &Label) -> FuzzyAnswer {
+        let mut states = BTreeMap::new();
+        for (&node, &distance) in &self.states {
+            // `chr` is deleted from the query.
+            if distance < self.max_distance {
+                insert_min(&mut states, node, distance + 1);
+            }
+            // `chr` matches or substitutes a child's label.
+            for child in self.trie.children_node_nums(LoudsNodeNum(node)) {
+                let distance = distance + usize::from(*self.trie.label(child) != *chr);
+                if distance <= self.max_distance {
+                    insert_min(&mut states, child.0, distance);
+                }
+            }
+        }
+        self.states = states;
+        self.insert_labels();
+        self.answer()
+    }
+
+    /// Extend the query by each label of `query`.
+    pub fn query_until(&mut self, query: impl AsRef<[Label]>) -> FuzzyAnswer {
+        for chr in query.as_ref() {
+            self.query(chr);
+        }
+        self.answer()
+    }
+
+    /// Add the states reached by inserting labels into the query, i.e., every
+    /// child of a state within the budget.
+    fn insert_labels(&mut self) {
+        // Nodes are numbered breadth-first, so a child comes after its parent.
+        let mut next = self.states.keys().next().copied();
+        while let Some(node) = next {
+            let distance = self.states[&node];
+            if distance < self.max_distance {
+                for child in self.trie.children_node_nums(LoudsNodeNum(node)) {
+                    insert_min(&mut self.states, child.0, distance + 1);
+                }
+            }
+            next = self.states.range(node + 1..).next().map(|(node, _)| *node);
+        }
+    }
+
+    /// Return the answer for the query so far.
+    pub fn answer(&self) -> FuzzyAnswer {
+        let distance = self
+            .states
+            .iter()
+            .filter(|(node, _)| self.trie.is_terminal(LoudsNodeNum(**node)))
+            .map(|(_, distance)| *distance)
+            .min();
+        FuzzyAnswer {
+            distance,
+            states: self.states.len(),
+        }
+    }
+
+    /// Return every entry that starts with a prefix within the budget of the
+    /// query in lexicographic order, with the smallest distance of such a
+    /// prefix.
+    pub fn completions<C, M>(&self) -> Vec<(C, &'a Value, usize)>
+    where
+        C: TryFromIterator<Label, M>,
+        Label: Clone,
+    {
+        // Nodes on the path to some state.
+        let mut on_path = HashSet::new();
+        for &node in self.states.keys() {
+            for ancestor in self.trie.child_to_ancestors(LoudsNodeNum(node)) {
+                if !on_path.insert(ancestor.0) {
+                    break;
+                }
+            }
+        }
+        let mut results = vec![];
+        let mut stack = vec![(LoudsNodeNum(1), None)];
+        while let Some((node_num, inherited)) = stack.pop() {
+            let distance = match (inherited, self.states.get(&node_num.0)) {
+                (Some(a), Some(b)) => Some(usize::min(a, *b)),
+                (a, b) => a.or(b.copied()),
+            };
+            if let (Some(distance), Some(value)) = (distance, self.trie.value(node_num)) {
+                results.push((self.trie.node_key(node_num), value, distance));
+            }
+            let n = stack.len();
+            stack.extend(
+                self.trie
+                    .children_node_nums(node_num)
+                    .filter(|child| distance.is_some() || on_path.contains(&child.0))
+                    .map(|child| (child, distance)),
+            );
+            stack[n..].reverse();
+        }
+        results
+    }
+
+    /// Reset the query.
+    pub fn reset(&mut self) {
+        self.states.clear();
+        self.states.insert(1, 0);
+        self.insert_labels();
+    }
+}
+
+fn insert_min(states: &mut BTreeMap<u64, usize>, node: u64, distance: usize) {
+    let entry = states.entry(node).or_insert(distance);
+    *entry = (*entry).min(distance);
+}
+
 #[cfg(test)]
 mod search_tests {
     use super::*;
@@ -338,6 +900,226 @@ mod search_tests {
         assert_eq!(Some(&2), search.value());
     }
 
+    #[test]
+    fn common_prefix_matcher() {
+        let trie = build_trie();
+        let mut matcher = CommonPrefixMatcher::new(&trie);
+        for query in ["applications", "apple", "app", "apz", "better", "zoo", ""] {
+            matcher.reset();
+            let found: Vec<(String, &u8)> =
+                query.bytes().filter_map(|chr| matcher.feed(&chr)).collect();
+            let expected: Vec<(String, &u8)> = trie.common_prefix_search(query).collect();
+            assert_eq!(found, expected, "query {:?}", query);
+        }
+
+        matcher.reset();
+        assert!(!matcher.is_done());
+        assert_eq!(
+            matcher.feed::<String, _>(&b'a'),
+            Some(("a".to_string(), &0))
+        );
+        assert_eq!(matcher.feed::<String, _>(&b'z'), None);
+        assert!(matcher.is_diverged() && matcher.is_done());
+        // "app" is no longer reported once the stream has diverged.
+        assert_eq!(matcher.feed::<String, _>(&b'p'), None);
+        assert_eq!(matcher.feed::<String, _>(&b'p'), None);
+        assert_eq!(matcher.consumed(), 1);
+
+        matcher.reset();
+        let found: Vec<(String, &u8)> = "better"
+            .bytes()
+            .filter_map(|chr| matcher.feed(&chr))
+            .collect();
+        assert_eq!(found, [("better".to_string(), &3)]);
+        assert!(matcher.is_done() && !matcher.is_diverged());
+    }
+
+    #[test]
+    fn inc_search_from_position() {
+        let trie = build_trie();
+        let mut search = trie.inc_search();
+        search.query_until("app").unwrap();
+        let app = search.position();
+        for (suffix, expected) in [("", Some(&1)), ("le", Some(&2)), ("lication", Some(&4))] {
+            let mut search = trie.inc_search_from(app);
+            assert!(search.query_until(suffix).is_ok() || suffix.is_empty());
+            assert_eq!(search.value(), expected);
+        }
+        let mut search = trie.inc_search_from(app);
+        assert_eq!(search.query_until("x"), Err(0));
+        assert_eq!(search.prefix::<String, _>(), "app");
+        assert_eq!(trie.inc_search().position(), LoudsNodeNum(1));
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "not in this trie")]
+    fn inc_search_from_invalid_position() {
+        let trie = build_trie();
+        trie.inc_search_from(LoudsNodeNum(1000));
+    }
+
+    #[test]
+    fn inc_search_query_until_counted() {
+        let trie = build_trie();
+        let mut search = trie.inc_search();
+        assert_eq!(Err(0), search.query_until_counted("zoo"));
+        assert_eq!(Err(0), search.query_until_counted(""));
+        assert_eq!("", search.prefix::<String, _>());
+        assert_eq!(Ok((Answer::Prefix, 1)), search.query_until_counted("blue"));
+        assert_eq!("b", search.prefix::<String, _>());
+        search.reset();
+        assert_eq!(
+            Ok((Answer::Match, 5)),
+            search.query_until_counted("apple pie")
+        );
+        assert_eq!("apple", search.prefix::<String, _>());
+        search.reset();
+        assert_eq!(
+            Ok((Answer::PrefixAndMatch, 3)),
+            search.query_until_counted("app")
+        );
+    }
+
+    fn levenshtein(a: &[u8], b: &[u8]) -> usize {
+        let mut row: Vec<usize> = (0..=b.len()).collect();
+        for (i, x) in a.iter().enumerate() {
+            let mut prev = row[0];
+            row[0] = i + 1;
+            for (j, y) in b.iter().enumerate() {
+                let next = (prev + usize::from(x != y))
+                    .min(row[j] + 1)
+                    .min(row[j + 1] + 1);
+                prev = row[j + 1];
+                row[j + 1] = next;
+            }
+        }
+        row[b.len()]
+    }
+
+    #[test]
+    fn fuzzy_inc_search_matches_brute_force() {
+        let trie = build_trie();
+        let keys: Vec<(Vec<u8>, u8)> = trie.iter().map(|(k, v)| (k, *v)).collect();
+        for max_distance in 0..=2 {
+            for query in ["", "a", "ap", "aple", "appl", "bettr", "xyz", "applicatoin"] {
+                let query = query.as_bytes();
+                let mut search = FuzzyIncSearch::new(&trie, max_distance);
+                let answer = search.query_until(query);
+                let expected = keys
+                    .iter()
+                    .map(|(key, _)| levenshtein(key, query))
+                    .filter(|d| *d <= max_distance)
+                    .min();
+                assert_eq!(answer.distance, expected, "{:?} {}", query, max_distance);
+                assert_eq!(answer.is_match(), expected.is_some());
+
+                let completions: Vec<(Vec<u8>, u8, usize)> = search
+                    .completions()
+                    .into_iter()
+                    .map(|(k, v, d)| (k, *v, d))
+                    .collect();
+                let expected: Vec<(Vec<u8>, u8, usize)> = keys
+                    .iter()
+                    .filter_map(|(key, value)| {
+                        let d = (0..=key.len())
+                            .map(|n| levenshtein(&key[..n], query))
+                            .min()
+                            .unwrap();
+                        (d <= max_distance).then(|| (key.clone(), *value, d))
+                    })
+                    .collect();
+                assert_eq!(completions, expected, "{:?} {}", query, max_distance);
+                assert_eq!(answer.is_prefix(), !expected.is_empty());
+            }
+        }
+    }
+
+    #[test]
+    fn fuzzy_inc_search_reset() {
+        let trie = build_trie();
+        let mut search = FuzzyIncSearch::new(&trie, 1);
+        assert!(!search.query_until("xyz").is_prefix());
+        assert_eq!(search.completions::<String, _>().len(), 0);
+        search.reset();
+        assert_eq!(search.query_until("bettor").distance, Some(1));
+    }
+
+    #[test]
+    fn answer_predicates() {
+        assert!(Answer::Prefix.is_prefix());
+        assert!(!Answer::Prefix.is_match());
+        assert!(!Answer::Match.is_prefix());
+        assert!(Answer::Match.is_match());
+        assert!(Answer::PrefixAndMatch.is_prefix());
+        assert!(Answer::PrefixAndMatch.is_match());
+    }
+
+    #[test]
+    fn fork_children() {
+        let trie = build_trie();
+        let mut search = trie.inc_search();
+        assert_eq!(search.query_until("app"), Ok(Answer::PrefixAndMatch));
+        let mut forks: Vec<_> = search.fork_children().collect();
+        assert_eq!(forks.len(), 1);
+        assert_eq!(forks[0].prefix::<String, _>(), "appl");
+
+        let mut forks2: Vec<_> = forks[0].fork_children().collect();
+        let prefixes: Vec<String> = forks2.iter().map(|fork| fork.prefix()).collect();
+        assert_eq!(prefixes, ["apple", "appli"]);
+        assert_eq!(forks2[0].value(), Some(&2));
+
+        // Moving a fork leaves its parent and siblings where they were.
+        assert_eq!(forks2[1].query_until("cation"), Ok(Answer::Match));
+        assert_eq!(forks2[1].value(), Some(&4));
+        assert_eq!(forks2[0].prefix::<String, _>(), "apple");
+        assert!(forks[0].backspace());
+        assert_eq!(forks[0].prefix::<String, _>(), "app");
+        assert_eq!(search.prefix::<String, _>(), "app");
+
+        let leaf = trie.inc_search();
+        let mut leaf = leaf.fork_children().next().unwrap();
+        assert_eq!(leaf.query_until("pple"), Ok(Answer::Match));
+        assert_eq!(leaf.fork_children().count(), 0);
+    }
+
+    mod longest_prefix_tests {
+        use crate::inc_search::IncSearch;
+
+        macro_rules! parameterized_tests {
+            ($($name:ident: $value:expr,)*) => {
+            $(
+                #[test]
+                fn $name() {
+                    let (query, expected): (&str, Option<&str>) = $value;
+                    let trie = super::build_trie();
+                    let mut search = trie.inc_search();
+                    let _ = search.query_until(query);
+                    let result: Option<String> = search.longest_prefix();
+                    assert_eq!(result.as_deref(), expected);
+                    assert_eq!(search.prefix::<String, _>(), query);
+                    let mut moved: IncSearch<'_, u8, u8> = search.clone();
+                    let _ = moved.goto_longest_prefix();
+                    let completed = query.to_string() + expected.unwrap_or("");
+                    assert_eq!(moved.prefix::<String, _>(), completed);
+                }
+            )*
+            }
+        }
+
+        parameterized_tests! {
+            t1: ("", None),
+            t2: ("a", Some("pp")),
+            t3: ("ap", Some("p")),
+            t4: ("app", Some("l")),
+            t5: ("appl", None),
+            t6: ("appli", Some("cation")),
+            t7: ("b", Some("etter")),
+            t8: ("better", None),
+            t9: ("アップ", Some("ル🍎")),
+        }
+    }
+
     #[test]
     fn inc_search_goto_longest_prefix() {
         let trie = build_trie();
@@ -362,6 +1144,79 @@ mod search_tests {
         assert_eq!(Some(&2), search.value());
     }
 
+    #[test]
+    fn inc_search_feed() {
+        let trie = build_trie();
+        let mut search = trie.inc_search();
+        assert_eq!(Some(&0), search.feed(&b'a'));
+        assert_eq!(None, search.feed(&b'p'));
+        assert_eq!(Some(&1), search.feed(&b'p'));
+        // No entry continues with 'z'; the search stays put.
+        assert_eq!(None, search.feed(&b'z'));
+        assert_eq!("app", search.prefix::<String, _>());
+        assert_eq!(None, search.feed(&b'l'));
+        assert_eq!(Some(&2), search.feed(&b'e'));
+    }
+
+    #[test]
+    fn inc_search_backspace() {
+        let trie = build_trie();
+        let mut search = trie.inc_search();
+        assert!(!search.backspace());
+        assert_eq!(Ok(Answer::Match), search.query_until("apple"));
+        assert!(search.backspace());
+        assert_eq!("appl", search.prefix::<String, _>());
+        assert_eq!(Some(Answer::Prefix), search.query(&b'i'));
+        assert!(search.backspace());
+        assert!(search.backspace());
+        assert_eq!(Some(&1), search.value());
+        assert!(search.backspace());
+        assert!(search.backspace());
+        assert!(search.backspace());
+        assert_eq!("", search.prefix::<String, _>());
+        assert!(!search.backspace());
+    }
+
+    #[test]
+    fn peek_matches_query() {
+        let trie = build_trie();
+        let mut search = trie.inc_search();
+        for prefix in ["", "a", "app", "appl", "apple", "b"] {
+            search.reset();
+            if !prefix.is_empty() {
+                assert!(search.query_until(prefix).is_ok());
+            }
+            let position = LoudsNodeNum::from(search.clone());
+            for chr in 0..=u8::MAX {
+                let answer = search.peek(&chr);
+                assert_eq!(LoudsNodeNum::from(search.clone()), position);
+                let mut moved = search.clone();
+                assert_eq!(answer, moved.query(&chr));
+            }
+        }
+    }
+
+    #[test]
+    fn owned_inc_search() {
+        fn assert_send_sync<T: Send + Sync>(_: &T) {}
+        let trie = Arc::new(build_trie());
+        let mut search = OwnedIncSearch::new(Arc::clone(&trie));
+        assert_send_sync(&search);
+        assert_eq!(None, search.query(&b'z'));
+        assert_eq!(Some(Answer::PrefixAndMatch), search.peek(&b'a'));
+        assert_eq!("", search.prefix::<String, _>());
+        assert_eq!(Ok(Answer::Match), search.query_until("apple"));
+        assert_eq!(Some(&2), search.value());
+        assert_eq!(5, search.prefix_len());
+        assert!(search.backspace());
+        assert_eq!("appl", search.prefix::<String, _>());
+        let resumed = OwnedIncSearch::resume(Arc::clone(search.trie()), search.position());
+        assert_eq!("appl", resumed.prefix::<String, _>());
+        search.reset();
+        assert_eq!(0, search.prefix_len());
+        assert_eq!(Ok(Answer::PrefixAndMatch), search.query_until("app"));
+    }
+
     // #[test]
     // fn inc_serach_value_mut() {
     //     let trie = build_trie();
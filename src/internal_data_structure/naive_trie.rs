@@ -94,4 +94,6 @@ pub struct NaiveTrieIntermOrLeaf<Label, Value> {
     children: Vec<NaiveTrie<Label, Value>>,
     pub(crate) label: Label,
     pub(crate) value: Option<Value>,
+    /// Sequence number of the push that set `value`.
+    pub(crate) order: u64,
 }
@@ -7,15 +7,49 @@ impl<'trie, Label: Ord, Value> NaiveTrie<Label, Value> {
         NaiveTrie::Root(NaiveTrieRoot { children: vec![] })
     }
 
+    pub fn make_root_with_capacity(capacity: usize) -> Self {
+        NaiveTrie::Root(NaiveTrieRoot {
+            children: Vec::with_capacity(capacity),
+        })
+    }
+
     pub fn make_interm_or_leaf(label: Label, terminal: Option<Value>) -> Self {
         NaiveTrie::IntermOrLeaf(NaiveTrieIntermOrLeaf {
             children: vec![],
             label,
             value: terminal,
+            order: 0,
         })
     }
 
-    pub fn push<Arr: Iterator<Item = Label>>(&'trie mut self, word: Arr, value: Value) {
+    /// Return the value previously stored for `word` if any.
+    pub fn push<Arr: Iterator<Item = Label>>(
+        &'trie mut self,
+        word: Arr,
+        value: Value,
+    ) -> Option<Value> {
+        self.push_ordered(word, value, 0)
+    }
+
+    /// Push `word` with the sequence number `order`. Return the value
+    /// previously stored for `word` if any.
+    pub fn push_ordered<Arr: Iterator<Item = Label>>(
+        &'trie mut self,
+        word: Arr,
+        value: Value,
+        order: u64,
+    ) -> Option<Value> {
+        let node = self.node_mut(word);
+        node.order = order;
+        node.value.replace(value)
+    }
+
+    /// Return the node for `word`, adding any missing nodes along the way.
+    /// Panics if `word` is empty.
+    pub fn node_mut<Arr: Iterator<Item = Label>>(
+        &'trie mut self,
+        word: Arr,
+    ) -> &'trie mut NaiveTrieIntermOrLeaf<Label, Value> {
         let mut trie = self;
         for chr in word {
             let res = trie
@@ -37,6 +71,11 @@ impl<'trie, Label: Ord, Value> NaiveTrie<Label, Value> {
                             &mut node.children[j]
                         }
                         NaiveTrie::IntermOrLeaf(node) => {
+                            // Most nodes have a single child. Growing an empty
+                            // `Vec` would reserve room for four.
+                            if node.children.is_empty() {
+                                node.children.reserve_exact(1);
+                            }
                             node.children.insert(j, child_trie);
                             &mut node.children[j]
                         }
@@ -46,7 +85,56 @@ impl<'trie, Label: Ord, Value> NaiveTrie<Label, Value> {
             };
         }
         match trie {
-            NaiveTrie::IntermOrLeaf(node) => node.value = Some(value),
+            NaiveTrie::IntermOrLeaf(node) => node,
+            NaiveTrie::Root(_) => panic!("An empty entry cannot be pushed"),
+            _ => panic!("Unexpected type"),
+        }
+    }
+
+    /// Return the node for `word` like [NaiveTrie::node_mut], but try the
+    /// last child first at each level, which is where the next of a sorted
+    /// sequence of words belongs when no other words are present.
+    ///
+    /// Also return whether `word` sorts after every word already present.
+    pub fn node_mut_sorted<Arr: Iterator<Item = Label>>(
+        &'trie mut self,
+        word: Arr,
+    ) -> (&'trie mut NaiveTrieIntermOrLeaf<Label, Value>, bool) {
+        let mut in_order = true;
+        let mut trie = self;
+        for chr in word {
+            let children = trie.children_mut();
+            let last = children.last().map(|last| last.label().cmp(&chr));
+            let j = match last {
+                Some(std::cmp::Ordering::Equal) => children.len() - 1,
+                Some(std::cmp::Ordering::Less) | None => {
+                    // See node_mut() for why a first child gets one slot.
+                    if children.capacity() == 0 {
+                        children.reserve_exact(1);
+                    }
+                    children.push(Self::make_interm_or_leaf(chr, None));
+                    children.len() - 1
+                }
+                Some(std::cmp::Ordering::Greater) => {
+                    in_order = false;
+                    match children.binary_search_by(|child| child.label().cmp(&chr)) {
+                        Ok(j) => j,
+                        Err(j) => {
+                            children.insert(j, Self::make_interm_or_leaf(chr, None));
+                            j
+                        }
+                    }
+                }
+            };
+            trie = &mut children[j];
+        }
+        match trie {
+            NaiveTrie::IntermOrLeaf(node) => {
+                // A longer word was already present.
+                in_order &= node.children.is_empty();
+                (node, in_order)
+            }
+            NaiveTrie::Root(_) => panic!("An empty entry cannot be pushed"),
             _ => panic!("Unexpected type"),
         }
     }
@@ -59,6 +147,131 @@ impl<'trie, Label: Ord, Value> NaiveTrie<Label, Value> {
         }
     }
 
+    fn children_mut(&mut self) -> &mut Vec<Self> {
+        match self {
+            NaiveTrie::Root(node) => &mut node.children,
+            NaiveTrie::IntermOrLeaf(node) => &mut node.children,
+            _ => panic!("Unexpected type"),
+        }
+    }
+
+    /// Return the index of the child labeled `label`.
+    fn child_index(&self, label: &Label) -> Option<usize> {
+        self.children()
+            .binary_search_by(|child| child.label().cmp(label))
+            .ok()
+    }
+
+    /// Return the node `word` leads to.
+    pub fn get(&self, word: &[Label]) -> Option<&Self> {
+        let mut trie = self;
+        for chr in word {
+            trie = &trie.children()[trie.child_index(chr)?];
+        }
+        Some(trie)
+    }
+
+    /// Return the node `word` leads to.
+    pub fn get_mut(&mut self, word: &[Label]) -> Option<&mut Self> {
+        let mut trie = self;
+        for chr in word {
+            let j = trie.child_index(chr)?;
+            trie = &mut trie.children_mut()[j];
+        }
+        Some(trie)
+    }
+
+    /// Remove `word` and return its value. Nodes left without any entry below
+    /// them are removed too.
+    pub fn remove(&mut self, word: &[Label]) -> Option<Value> {
+        // The deepest node on the path that must be kept whatever is below it,
+        // and the index of its child on the path.
+        let mut cut = (0, 0);
+        let mut trie: &Self = self;
+        for (depth, chr) in word.iter().enumerate() {
+            if depth == 0 || trie.value().is_some() || trie.children().len() > 1 {
+                cut = (depth, 0);
+            }
+            let j = trie.child_index(chr)?;
+            if cut.0 == depth {
+                cut.1 = j;
+            }
+            trie = &trie.children()[j];
+        }
+        let node = match self.get_mut(word)? {
+            NaiveTrie::IntermOrLeaf(node) => node,
+            _ => return None,
+        };
+        let value = node.value.take()?;
+        if node.children.is_empty() {
+            let parent = self
+                .get_mut(&word[..cut.0])
+                .expect("Path was already walked");
+            parent.children_mut().remove(cut.1);
+        }
+        Some(value)
+    }
+
+    /// Merge `other` into this trie. Where both have a value for an entry,
+    /// keep ours if `keep_ours`, otherwise take theirs as though its entries
+    /// were pushed after this trie's. Return the number of entries both have.
+    pub fn append(&mut self, other: Self, keep_ours: bool) -> usize {
+        let mut duplicates = 0;
+        let mut stack = vec![(self, other)];
+        while let Some((trie, other)) = stack.pop() {
+            let (theirs, value, order) = match other {
+                NaiveTrie::Root(node) => (node.children, None, 0),
+                NaiveTrie::IntermOrLeaf(node) => (node.children, node.value, node.order),
+                NaiveTrie::PhantomSibling => panic!("Unexpected type"),
+            };
+            if let (NaiveTrie::IntermOrLeaf(node), Some(value)) = (&mut *trie, value) {
+                if node.value.is_some() {
+                    duplicates += 1;
+                }
+                if node.value.is_none() || !keep_ours {
+                    node.value = Some(value);
+                    node.order = order;
+                }
+            }
+            let children = trie.children_mut();
+            let mut ours = std::mem::take(children).into_iter().peekable();
+            // Children of `other` with the same label as a child of ours, by
+            // the index of ours.
+            let mut matches = vec![];
+            for their in theirs {
+                while let Some(our) = ours.next_if(|our| our.label().cmp(their.label()).is_lt()) {
+                    children.push(our);
+                }
+                match ours.next_if(|our| our.label() == their.label()) {
+                    Some(our) => {
+                        matches.push((children.len(), their));
+                        children.push(our);
+                    }
+                    None => children.push(their),
+                }
+            }
+            children.extend(ours);
+            let mut matches = matches.into_iter().peekable();
+            for (i, child) in children.iter_mut().enumerate() {
+                if let Some((_, their)) = matches.next_if(|(j, _)| *j == i) {
+                    stack.push((child, their));
+                }
+            }
+        }
+        duplicates
+    }
+
+    /// Add `offset` to the sequence number of every node.
+    pub fn offset_orders(&mut self, offset: u64) {
+        let mut stack = vec![self];
+        while let Some(trie) = stack.pop() {
+            if let NaiveTrie::IntermOrLeaf(node) = &mut *trie {
+                node.order += offset;
+            }
+            stack.extend(trie.children_mut().iter_mut());
+        }
+    }
+
     pub fn drain_children(&mut self) -> Drain<'_, Self> {
         match self {
             NaiveTrie::Root(node) => node.children.drain(0..),
@@ -67,9 +280,35 @@ impl<'trie, Label: Ord, Value> NaiveTrie<Label, Value> {
         }
     }
 
+    /// Consume the trie and return its entries in lexicographic order.
+    pub fn into_entries(self) -> Vec<(Vec<Label>, Value)>
+    where
+        Label: Clone,
+    {
+        let mut entries = vec![];
+        let mut key = vec![];
+        let mut stack = vec![(0, self)];
+        while let Some((depth, trie)) = stack.pop() {
+            match trie {
+                NaiveTrie::Root(node) => {
+                    stack.extend(node.children.into_iter().rev().map(|c| (depth, c)));
+                }
+                NaiveTrie::IntermOrLeaf(node) => {
+                    key.truncate(depth);
+                    key.push(node.label);
+                    if let Some(value) = node.value {
+                        entries.push((key.clone(), value));
+                    }
+                    stack.extend(node.children.into_iter().rev().map(|c| (depth + 1, c)));
+                }
+                NaiveTrie::PhantomSibling => {}
+            }
+        }
+        entries
+    }
+
     /// # Panics
     /// If self is not IntermOrLeaf.
-    #[allow(dead_code)]
     pub fn value(&self) -> Option<&Value> {
         match self {
             NaiveTrie::IntermOrLeaf(node) => node.value.as_ref(),
@@ -0,0 +1,138 @@
+use crate::map::Trie;
+use crate::try_collect::{TryCollect, TryFromIterator};
+use louds_rs::LoudsNodeNum;
+use std::marker::PhantomData;
+
+#[derive(Debug)]
+/// Iterates through all the matches of a query in lexicographic order while
+/// holding at most `max_frontier` nodes.
+///
+/// Rather than a frontier of every unvisited sibling, only the path to the
+/// current node is kept: one `(next sibling, last sibling)` pair per level.
+/// Subtrees deeper than `max_frontier` labels past the query are skipped and
+/// reported by [BoundedSearchIter::is_truncated].
+pub struct BoundedSearchIter<'a, Label, Value, C, M> {
+    trie: &'a Trie<Label, Value>,
+    prefix: Vec<Label>,
+    first: Option<&'a Value>,
+    /// (next sibling to visit, last sibling) per level.
+    stack: Vec<(u64, u64)>,
+    max_frontier: usize,
+    /// Entries fewer than this many labels past the query are not yielded.
+    min_depth: usize,
+    truncated: bool,
+    col: PhantomData<(C, M)>,
+}
+
+impl<'a, Label: Clone, Value, C, M> Clone for BoundedSearchIter<'a, Label, Value, C, M> {
+    fn clone(&self) -> Self {
+        Self {
+            trie: self.trie,
+            prefix: self.prefix.clone(),
+            first: self.first,
+            stack: self.stack.clone(),
+            max_frontier: self.max_frontier,
+            min_depth: self.min_depth,
+            truncated: self.truncated,
+            col: PhantomData,
+        }
+    }
+}
+
+impl<'a, Label: Ord + Clone, Value, C, M> BoundedSearchIter<'a, Label, Value, C, M> {
+    pub(crate) fn new(
+        trie: &'a Trie<Label, Value>,
+        query: impl AsRef<[Label]>,
+        max_frontier: usize,
+    ) -> Self {
+        let mut iter = Self {
+            trie,
+            prefix: query.as_ref().to_vec(),
+            first: None,
+            stack: Vec::new(),
+            max_frontier,
+            min_depth: 0,
+            truncated: false,
+            col: PhantomData,
+        };
+        if let Some(node) = trie.prefix_node(query) {
+            iter.first = trie.value(node);
+            iter.push_children(node);
+        }
+        iter
+    }
+
+    /// Only yield entries at least `min_depth` labels longer than the query.
+    pub(crate) fn min_depth(mut self, min_depth: usize) -> Self {
+        self.min_depth = min_depth;
+        if min_depth > 0 {
+            self.first = None;
+        }
+        self
+    }
+
+    /// Return true if some subtree was skipped because it was too deep.
+    ///
+    /// Only subtrees the iterator has reached are known, so check this after
+    /// iterating.
+    pub fn is_truncated(&self) -> bool {
+        self.truncated
+    }
+
+    fn push_children(&mut self, node: LoudsNodeNum) {
+        let mut children = self.trie.children_node_nums(node);
+        if let Some(first) = children.next() {
+            if self.stack.len() < self.max_frontier {
+                let last = children.next_back().unwrap_or(first);
+                self.stack.push((first.0, last.0));
+            } else {
+                self.truncated = true;
+            }
+        }
+    }
+}
+
+impl<'a, Label: Ord + Clone, Value, C, M> Iterator for BoundedSearchIter<'a, Label, Value, C, M>
+where
+    C: TryFromIterator<Label, M>,
+{
+    type Item = (C, &'a Value);
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(value) = self.first.take() {
+            return Some((
+                self.prefix
+                    .iter()
+                    .cloned()
+                    .try_collect()
+                    .expect("Could not collect"),
+                value,
+            ));
+        }
+        while let Some((next, last)) = self.stack.last_mut() {
+            if *next > *last {
+                self.stack.pop();
+                continue;
+            }
+            let node = LoudsNodeNum(*next);
+            *next += 1;
+            // Each level's current node is one before its next sibling.
+            let entry = self
+                .trie
+                .value(node)
+                .filter(|_| self.stack.len() >= self.min_depth)
+                .map(|value| {
+                    let key = self.prefix.iter().cloned().chain(
+                        self.stack
+                            .iter()
+                            .map(|(next, _)| self.trie.label(LoudsNodeNum(next - 1)).clone()),
+                    );
+                    (key.try_collect().expect("Could not collect"), value)
+                });
+            self.push_children(node);
+            if entry.is_some() {
+                return entry;
+            }
+        }
+        None
+    }
+}
@@ -0,0 +1,81 @@
+use crate::internal_data_structure::naive_trie::NaiveTrie;
+use crate::try_collect::{TryCollect, TryFromIterator};
+use std::marker::PhantomData;
+
+#[derive(Debug)]
+/// Iterates through all the matches of a query in a [crate::map::DynTrie] in
+/// lexicographic order.
+pub struct DynSearchIter<'a, Label, Value, C, M> {
+    prefix: Vec<Label>,
+    first: Option<&'a Value>,
+    stack: Vec<(usize, &'a NaiveTrie<Label, Value>)>,
+    buffer: Vec<&'a Label>,
+    col: PhantomData<(C, M)>,
+}
+
+impl<'a, Label: Clone, Value, C, M> Clone for DynSearchIter<'a, Label, Value, C, M> {
+    fn clone(&self) -> Self {
+        Self {
+            prefix: self.prefix.clone(),
+            first: self.first,
+            stack: self.stack.clone(),
+            buffer: self.buffer.clone(),
+            col: PhantomData,
+        }
+    }
+}
+
+impl<'a, Label: Ord + Clone, Value, C, M> DynSearchIter<'a, Label, Value, C, M> {
+    pub(crate) fn new(naive_trie: &'a NaiveTrie<Label, Value>, query: &[Label]) -> Self {
+        let mut iter = Self {
+            prefix: query.to_vec(),
+            first: None,
+            stack: Vec::new(),
+            buffer: Vec::new(),
+            col: PhantomData,
+        };
+        if let Some(node) = naive_trie.get(query) {
+            if let NaiveTrie::IntermOrLeaf(n) = node {
+                iter.first = n.value.as_ref();
+            }
+            iter.stack
+                .extend(node.children().iter().rev().map(|child| (0, child)));
+        }
+        iter
+    }
+}
+
+impl<'a, Label: Ord + Clone, Value, C, M> Iterator for DynSearchIter<'a, Label, Value, C, M>
+where
+    C: TryFromIterator<Label, M>,
+{
+    type Item = (C, &'a Value);
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(value) = self.first.take() {
+            let key = self
+                .prefix
+                .iter()
+                .cloned()
+                .try_collect()
+                .expect("Could not collect");
+            return Some((key, value));
+        }
+        while let Some((depth, node)) = self.stack.pop() {
+            self.buffer.truncate(depth);
+            self.buffer.push(node.label());
+            self.stack
+                .extend(node.children().iter().rev().map(|child| (depth + 1, child)));
+            if let Some(value) = node.value() {
+                let key = self
+                    .prefix
+                    .iter()
+                    .chain(self.buffer.iter().copied())
+                    .cloned()
+                    .try_collect()
+                    .expect("Could not collect");
+                return Some((key, value));
+            }
+        }
+        None
+    }
+}
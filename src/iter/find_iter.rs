@@ -0,0 +1,109 @@
+use crate::map::Trie;
+use louds_rs::LoudsNodeNum;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// An entry found in a text by [Trie::find_in].
+pub struct Match<'a, Value> {
+    /// Index of the first label of the entry in the text.
+    pub start: usize,
+    /// Index one past the last label of the entry in the text.
+    pub end: usize,
+    /// The entry's value.
+    pub value: &'a Value,
+}
+
+#[derive(Debug, Clone)]
+/// Iterates through the entries found in a text. See [Trie::find_in].
+pub struct FindIter<'a, Label, Value> {
+    trie: &'a Trie<Label, Value>,
+    text: &'a [Label],
+    non_overlapping: bool,
+    start: usize,
+    end: usize,
+    node: LoudsNodeNum,
+}
+
+impl<'a, Label: Ord, Value> FindIter<'a, Label, Value> {
+    #[inline]
+    pub(crate) fn new(
+        trie: &'a Trie<Label, Value>,
+        text: &'a [Label],
+        non_overlapping: bool,
+    ) -> Self {
+        Self {
+            trie,
+            text,
+            non_overlapping,
+            start: 0,
+            end: 0,
+            node: LoudsNodeNum(1),
+        }
+    }
+
+    /// Follow the next label of the text. Return false at a dead end.
+    fn step(&mut self) -> bool {
+        let chr = match self.text.get(self.end) {
+            Some(chr) => chr,
+            None => return false,
+        };
+        let children_node_nums: Vec<_> = self.trie.children_node_nums(self.node).collect();
+        match self
+            .trie
+            .bin_search_by_children_labels(chr, &children_node_nums[..])
+        {
+            Ok(j) => {
+                self.node = children_node_nums[j];
+                self.end += 1;
+                true
+            }
+            Err(_) => false,
+        }
+    }
+
+    /// Restart the walk from the root at `start`.
+    fn restart(&mut self, start: usize) {
+        self.start = start;
+        self.end = start;
+        self.node = LoudsNodeNum(1);
+    }
+}
+
+impl<'a, Label: Ord, Value> Iterator for FindIter<'a, Label, Value> {
+    type Item = Match<'a, Value>;
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.start < self.text.len() {
+            if self.non_overlapping {
+                let mut longest = None;
+                while self.step() {
+                    if let Some(value) = self.trie.value(self.node) {
+                        longest = Some((self.end, value));
+                    }
+                }
+                match longest {
+                    Some((end, value)) => {
+                        let found = Match {
+                            start: self.start,
+                            end,
+                            value,
+                        };
+                        self.restart(end);
+                        return Some(found);
+                    }
+                    None => self.restart(self.start + 1),
+                }
+            } else {
+                while self.step() {
+                    if let Some(value) = self.trie.value(self.node) {
+                        return Some(Match {
+                            start: self.start,
+                            end: self.end,
+                            value,
+                        });
+                    }
+                }
+                self.restart(self.start + 1);
+            }
+        }
+        None
+    }
+}
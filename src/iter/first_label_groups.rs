@@ -0,0 +1,67 @@
+use crate::iter::SearchIter;
+use crate::map::Trie;
+use crate::try_collect::TryFromIterator;
+use louds_rs::LoudsNodeNum;
+use std::marker::PhantomData;
+
+#[derive(Debug)]
+/// Iterates through the entries of a trie grouped by their first label. See
+/// [Trie::group_by_first].
+///
+/// Groups are made on demand; a group that is never iterated costs nothing.
+pub struct FirstLabelGroups<'a, Label, Value, C, M> {
+    trie: &'a Trie<Label, Value>,
+    /// Node numbers of the root's children not yet grouped, `next..end`.
+    next: u64,
+    end: u64,
+    col: PhantomData<(C, M)>,
+}
+
+impl<'a, Label, Value, C, M> Clone for FirstLabelGroups<'a, Label, Value, C, M> {
+    fn clone(&self) -> Self {
+        Self {
+            trie: self.trie,
+            next: self.next,
+            end: self.end,
+            col: PhantomData,
+        }
+    }
+}
+
+impl<'a, Label: Ord, Value, C, M> FirstLabelGroups<'a, Label, Value, C, M> {
+    #[inline]
+    pub(crate) fn new(trie: &'a Trie<Label, Value>) -> Self {
+        // The root's children are nodes 2 up to its last child.
+        let mut children = trie.children_node_nums(LoudsNodeNum(1));
+        let end = children.next_back().map_or(2, |last| last.0 + 1);
+        Self {
+            trie,
+            next: 2,
+            end,
+            col: PhantomData,
+        }
+    }
+}
+
+impl<'a, Label: Ord + Clone, Value, C, M> Iterator for FirstLabelGroups<'a, Label, Value, C, M>
+where
+    C: TryFromIterator<Label, M> + Clone,
+{
+    type Item = (&'a Label, SearchIter<'a, Label, Value, C, M>);
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.next >= self.end {
+            return None;
+        }
+        let label = self.trie.label(LoudsNodeNum(self.next));
+        self.next += 1;
+        Some((
+            label,
+            SearchIter::new(self.trie, std::slice::from_ref(label)),
+        ))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = (self.end - self.next) as usize;
+        (len, Some(len))
+    }
+}
@@ -1,10 +1,28 @@
 //! Trie iterators
+mod bounded_search_iter;
+mod dyn_search_iter;
+mod find_iter;
+mod first_label_groups;
 mod keys;
+mod node_iter;
+mod ordered_search_iter;
 mod postfix_iter;
 mod prefix_iter;
+mod search_cursor;
 mod search_iter;
+mod try_collect_iter;
+mod values;
 
+pub use bounded_search_iter::BoundedSearchIter;
+pub use dyn_search_iter::DynSearchIter;
+pub use find_iter::{FindIter, Match};
+pub use first_label_groups::FirstLabelGroups;
 pub use keys::{Keys, KeysExt};
+pub use node_iter::{BfNodes, DfNodes, NodeRef};
+pub use ordered_search_iter::{OrderedSearchIter, SearchOrder};
 pub use postfix_iter::PostfixIter;
-pub use prefix_iter::PrefixIter;
-pub use search_iter::SearchIter;
+pub use prefix_iter::{AnnotatedKeys, AnnotatedPrefixIter, PrefixIter};
+pub use search_cursor::SearchCursor;
+pub use search_iter::{SearchIter, WithDepth};
+pub use try_collect_iter::{TryCollectIter, TryKeys};
+pub use values::{Values, ValuesExt};
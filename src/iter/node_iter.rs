@@ -0,0 +1,163 @@
+use crate::map::Trie;
+use louds_rs::LoudsNodeNum;
+
+#[derive(Debug, PartialEq, Eq)]
+/// A node of a built trie as yielded by [BfNodes] and [DfNodes].
+pub struct NodeRef<'a, Label, Value> {
+    label: &'a Label,
+    value: Option<&'a Value>,
+    depth: usize,
+}
+
+impl<'a, Label, Value> Clone for NodeRef<'a, Label, Value> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<'a, Label, Value> Copy for NodeRef<'a, Label, Value> {}
+
+impl<'a, Label, Value> NodeRef<'a, Label, Value> {
+    /// Return the label of the edge leading to this node.
+    pub fn label(&self) -> &'a Label {
+        self.label
+    }
+
+    /// Return the value if this node terminates an entry.
+    pub fn value(&self) -> Option<&'a Value> {
+        self.value
+    }
+
+    /// Return true if this node terminates an entry.
+    pub fn is_terminal(&self) -> bool {
+        self.value.is_some()
+    }
+
+    /// Return the number of labels from the root to this node. The root's
+    /// children have a depth of one.
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+}
+
+#[derive(Debug)]
+/// Iterates through every node but the root in breadth-first order.
+///
+/// Siblings are visited in lexicographic order.
+pub struct BfNodes<'a, Label, Value> {
+    trie: &'a Trie<Label, Value>,
+    next: u64,
+    depth: usize,
+    /// The last node of the current level.
+    level_end: u64,
+    /// The last node seen so far of the next level.
+    next_level_end: u64,
+}
+
+impl<'a, Label, Value> Clone for BfNodes<'a, Label, Value> {
+    fn clone(&self) -> Self {
+        Self {
+            trie: self.trie,
+            next: self.next,
+            depth: self.depth,
+            level_end: self.level_end,
+            next_level_end: self.next_level_end,
+        }
+    }
+}
+
+impl<'a, Label: Ord, Value> BfNodes<'a, Label, Value> {
+    pub(crate) fn new(trie: &'a Trie<Label, Value>) -> Self {
+        let root = LoudsNodeNum(1);
+        Self {
+            trie,
+            next: 2,
+            depth: 0,
+            level_end: root.0,
+            next_level_end: last_child(trie, root).unwrap_or(root.0),
+        }
+    }
+}
+
+fn last_child<Label: Ord, Value>(trie: &Trie<Label, Value>, node_num: LoudsNodeNum) -> Option<u64> {
+    trie.children_node_nums(node_num)
+        .next_back()
+        .map(|child| child.0)
+}
+
+impl<'a, Label: Ord, Value> Iterator for BfNodes<'a, Label, Value> {
+    type Item = NodeRef<'a, Label, Value>;
+    fn next(&mut self) -> Option<Self::Item> {
+        // Node numbers are assigned breadth-first.
+        if self.next > self.trie.last_node_num().0 {
+            return None;
+        }
+        let node_num = LoudsNodeNum(self.next);
+        self.next += 1;
+        if node_num.0 > self.level_end {
+            self.depth += 1;
+            self.level_end = self.next_level_end;
+        }
+        if let Some(child) = last_child(self.trie, node_num) {
+            self.next_level_end = self.next_level_end.max(child);
+        }
+        Some(NodeRef {
+            label: self.trie.label(node_num),
+            value: self.trie.value(node_num),
+            depth: self.depth,
+        })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = (self.trie.last_node_num().0 + 1).saturating_sub(self.next) as usize;
+        (remaining, Some(remaining))
+    }
+}
+
+impl<'a, Label: Ord, Value> ExactSizeIterator for BfNodes<'a, Label, Value> {}
+
+#[derive(Debug)]
+/// Iterates through every node but the root in depth-first order, i.e.,
+/// lexicographic order of their keys.
+pub struct DfNodes<'a, Label, Value> {
+    trie: &'a Trie<Label, Value>,
+    stack: Vec<(LoudsNodeNum, usize)>,
+}
+
+impl<'a, Label, Value> Clone for DfNodes<'a, Label, Value> {
+    fn clone(&self) -> Self {
+        Self {
+            trie: self.trie,
+            stack: self.stack.clone(),
+        }
+    }
+}
+
+impl<'a, Label: Ord, Value> DfNodes<'a, Label, Value> {
+    pub(crate) fn new(trie: &'a Trie<Label, Value>) -> Self {
+        let stack = trie
+            .children_node_nums(LoudsNodeNum(1))
+            .rev()
+            .map(|child| (child, 1))
+            .collect();
+        Self { trie, stack }
+    }
+}
+
+impl<'a, Label: Ord, Value> Iterator for DfNodes<'a, Label, Value> {
+    type Item = NodeRef<'a, Label, Value>;
+    fn next(&mut self) -> Option<Self::Item> {
+        let (node_num, depth) = self.stack.pop()?;
+        self.stack.extend(
+            self.trie
+                .children_node_nums(node_num)
+                .rev()
+                .map(|child| (child, depth + 1)),
+        );
+        Some(NodeRef {
+            label: self.trie.label(node_num),
+            value: self.trie.value(node_num),
+            depth,
+        })
+    }
+}
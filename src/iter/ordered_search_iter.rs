@@ -0,0 +1,76 @@
+use crate::iter::SearchIter;
+use crate::map::Trie;
+use crate::try_collect::TryFromIterator;
+use louds_rs::LoudsNodeNum;
+use std::collections::VecDeque;
+use std::marker::PhantomData;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+/// The order of entries from [Trie::predictive_search_ordered].
+pub enum SearchOrder {
+    /// Ascending lexicographic order, like [Trie::predictive_search].
+    #[default]
+    Lexicographic,
+    /// Shortest keys first; keys of the same length are in lexicographic
+    /// order.
+    ByLength,
+}
+
+#[derive(Debug)]
+/// Iterates through all the matches of a query in a [SearchOrder]. See
+/// [Trie::predictive_search_ordered].
+pub struct OrderedSearchIter<'a, Label, Value, C, M>(Inner<'a, Label, Value, C, M>);
+
+#[derive(Debug)]
+enum Inner<'a, Label, Value, C, M> {
+    Lexicographic(SearchIter<'a, Label, Value, C, M>),
+    /// A breadth-first walk, which visits shallower nodes first and each level
+    /// in lexicographic order.
+    ByLength {
+        trie: &'a Trie<Label, Value>,
+        queue: VecDeque<LoudsNodeNum>,
+        col: PhantomData<(C, M)>,
+    },
+}
+
+impl<'a, Label: Ord + Clone, Value, C, M> OrderedSearchIter<'a, Label, Value, C, M>
+where
+    C: TryFromIterator<Label, M> + Clone,
+{
+    pub(crate) fn new(
+        trie: &'a Trie<Label, Value>,
+        query: impl AsRef<[Label]>,
+        order: SearchOrder,
+    ) -> Self {
+        Self(match order {
+            SearchOrder::Lexicographic => Inner::Lexicographic(SearchIter::new(trie, query)),
+            SearchOrder::ByLength => Inner::ByLength {
+                trie,
+                queue: trie.prefix_node(query).into_iter().collect(),
+                col: PhantomData,
+            },
+        })
+    }
+}
+
+impl<'a, Label: Ord + Clone, Value, C, M> Iterator for OrderedSearchIter<'a, Label, Value, C, M>
+where
+    C: TryFromIterator<Label, M> + Clone,
+{
+    type Item = (C, &'a Value);
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        match &mut self.0 {
+            Inner::Lexicographic(iter) => iter.next(),
+            Inner::ByLength { trie, queue, .. } => {
+                while let Some(node_num) = queue.pop_front() {
+                    queue.extend(trie.children_node_nums(node_num));
+                    if let Some(value) = trie.value(node_num) {
+                        return Some((trie.node_key(node_num), value));
+                    }
+                }
+                None
+            }
+        }
+    }
+}
@@ -1,10 +1,14 @@
+#[cfg(doc)]
+use crate::iter::SearchIter;
 use crate::map::Trie;
 use crate::try_collect::{TryCollect, TryFromIterator};
 use louds_rs::LoudsNodeNum;
 use std::marker::PhantomData;
 
-#[derive(Debug, Clone)]
+#[derive(Debug)]
 /// Iterates through all the postfixes of a matching query.
+///
+/// Cloning does not require `Value: Clone`; see [SearchIter].
 pub struct PostfixIter<'a, Label, Value, C, M> {
     trie: &'a Trie<Label, Value>,
     queue: Vec<(usize, LoudsNodeNum)>,
@@ -13,6 +17,18 @@ pub struct PostfixIter<'a, Label, Value, C, M> {
     col: PhantomData<(C, M)>,
 }
 
+impl<'a, Label, Value, C, M> Clone for PostfixIter<'a, Label, Value, C, M> {
+    fn clone(&self) -> Self {
+        Self {
+            trie: self.trie,
+            queue: self.queue.clone(),
+            buffer: self.buffer.clone(),
+            value: self.value,
+            col: PhantomData,
+        }
+    }
+}
+
 impl<'a, Label: Ord, Value, C, M> PostfixIter<'a, Label, Value, C, M>
 where
     C: TryFromIterator<Label, M>,
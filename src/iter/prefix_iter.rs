@@ -1,10 +1,14 @@
+#[cfg(doc)]
+use crate::iter::SearchIter;
 use crate::map::Trie;
 use crate::try_collect::{TryCollect, TryFromIterator};
 use louds_rs::LoudsNodeNum;
 use std::marker::PhantomData;
 
-#[derive(Debug, Clone)]
+#[derive(Debug)]
 /// Iterates through all the common prefixes of a given query.
+///
+/// Cloning does not require `Value: Clone`; see [SearchIter].
 pub struct PrefixIter<'a, Label, Value, C, M> {
     trie: &'a Trie<Label, Value>,
     query: Vec<Label>,
@@ -15,6 +19,20 @@ pub struct PrefixIter<'a, Label, Value, C, M> {
     col: PhantomData<(C, M)>,
 }
 
+impl<'a, Label: Clone, Value, C, M> Clone for PrefixIter<'a, Label, Value, C, M> {
+    fn clone(&self) -> Self {
+        Self {
+            trie: self.trie,
+            query: self.query.clone(),
+            index: self.index,
+            node: self.node,
+            buffer: self.buffer.clone(),
+            consume: self.consume,
+            col: PhantomData,
+        }
+    }
+}
+
 impl<'a, Label: Ord + Clone, Value, C, M> PrefixIter<'a, Label, Value, C, M> {
     #[inline]
     pub(crate) fn new(trie: &'a Trie<Label, Value>, query: impl AsRef<[Label]>) -> Self {
@@ -70,3 +88,44 @@ where
         }
     }
 }
+
+/// Iterates through the common prefixes of a query in a set [crate::Trie]
+/// along with whether each one continues into longer entries.
+///
+/// Made by [crate::Trie::common_prefix_search_annotated].
+pub type AnnotatedKeys<'a, Label, C, M> =
+    std::iter::Map<AnnotatedPrefixIter<'a, Label, (), C, M>, fn((C, &'a (), bool)) -> (C, bool)>;
+
+#[derive(Debug)]
+/// Iterates through all the common prefixes of a given query along with
+/// whether each one continues into longer entries.
+///
+/// Made by [Trie::common_prefix_search_annotated].
+pub struct AnnotatedPrefixIter<'a, Label, Value, C, M>(PrefixIter<'a, Label, Value, C, M>);
+
+impl<'a, Label: Clone, Value, C, M> Clone for AnnotatedPrefixIter<'a, Label, Value, C, M> {
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
+    }
+}
+
+impl<'a, Label: Ord + Clone, Value, C, M> AnnotatedPrefixIter<'a, Label, Value, C, M> {
+    #[inline]
+    pub(crate) fn new(trie: &'a Trie<Label, Value>, query: impl AsRef<[Label]>) -> Self {
+        Self(PrefixIter::new(trie, query))
+    }
+}
+
+impl<'a, Label: Ord + Clone, Value, C, M> Iterator for AnnotatedPrefixIter<'a, Label, Value, C, M>
+where
+    C: TryFromIterator<Label, M>,
+{
+    /// The key, its value, and true if the key is a prefix of other entries.
+    type Item = (C, &'a Value, bool);
+    fn next(&mut self) -> Option<Self::Item> {
+        let (key, value) = self.0.next()?;
+        // The inner iterator rests on the node of the entry it just yielded.
+        let is_further_prefix = self.0.trie.children_node_nums(self.0.node).next().is_some();
+        Some((key, value, is_further_prefix))
+    }
+}
@@ -0,0 +1,68 @@
+use crate::map::Trie;
+use louds_rs::LoudsNodeNum;
+
+#[derive(Debug)]
+/// Steps through all the matches of a query, lending each key as a view into
+/// one reusable buffer instead of allocating it.
+///
+/// Keys cannot be borrowed from the trie itself: a LOUDS trie stores labels
+/// in breadth-first order, so no key's labels are contiguous in memory. This
+/// is the next best thing. It is not an [Iterator] because each key is only
+/// valid until the next call to [SearchCursor::next_entry].
+///
+/// Made by [Trie::predictive_search_cursor].
+pub struct SearchCursor<'a, Label, Value> {
+    trie: &'a Trie<Label, Value>,
+    /// Nodes to visit and their depth below the query.
+    stack: Vec<(usize, LoudsNodeNum)>,
+    query_len: usize,
+    buffer: Vec<Label>,
+}
+
+impl<'a, Label: Clone, Value> Clone for SearchCursor<'a, Label, Value> {
+    fn clone(&self) -> Self {
+        Self {
+            trie: self.trie,
+            stack: self.stack.clone(),
+            query_len: self.query_len,
+            buffer: self.buffer.clone(),
+        }
+    }
+}
+
+impl<'a, Label: Ord + Clone, Value> SearchCursor<'a, Label, Value> {
+    pub(crate) fn new(trie: &'a Trie<Label, Value>, query: impl AsRef<[Label]>) -> Self {
+        let query = query.as_ref();
+        let stack = match trie.prefix_node(query) {
+            Some(node) => vec![(0, node)],
+            None => vec![],
+        };
+        Self {
+            trie,
+            stack,
+            query_len: query.len(),
+            buffer: query.to_vec(),
+        }
+    }
+
+    /// Advance to the next match and return its key and value, or `None` when
+    /// there are no more.
+    pub fn next_entry(&mut self) -> Option<(&[Label], &'a Value)> {
+        while let Some((depth, node)) = self.stack.pop() {
+            self.stack.extend(
+                self.trie
+                    .children_node_nums(node)
+                    .rev()
+                    .map(|child| (depth + 1, child)),
+            );
+            if depth > 0 {
+                self.buffer.truncate(self.query_len + depth - 1);
+                self.buffer.push(self.trie.label(node).clone());
+            }
+            if let Some(value) = self.trie.value(node) {
+                return Some((&self.buffer, value));
+            }
+        }
+        None
+    }
+}
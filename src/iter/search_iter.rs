@@ -4,8 +4,12 @@ use crate::try_collect::{Collect, TryCollect, TryFromIterator};
 use louds_rs::LoudsNodeNum;
 use std::marker::PhantomData;
 
-#[derive(Debug, Clone)]
-/// Iterates through all the matches of a query.
+#[derive(Debug)]
+/// Iterates through all the matches of a query in lexicographic order.
+///
+/// Cloning this iterator copies its traversal state, not the trie, so it does
+/// not require `Value: Clone`; both clones continue independently from the
+/// same position.
 pub struct SearchIter<'a, Label, Value, C, M> {
     prefix: Vec<Label>,
     first: Option<(C, &'a Value)>,
@@ -13,6 +17,17 @@ pub struct SearchIter<'a, Label, Value, C, M> {
     col: PhantomData<(C, M)>,
 }
 
+impl<'a, Label: Clone, Value, C: Clone, M> Clone for SearchIter<'a, Label, Value, C, M> {
+    fn clone(&self) -> Self {
+        Self {
+            prefix: self.prefix.clone(),
+            first: self.first.clone(),
+            postfix_iter: self.postfix_iter.clone(),
+            col: PhantomData,
+        }
+    }
+}
+
 impl<'a, Label: Ord + Clone, Value, C, M> SearchIter<'a, Label, Value, C, M>
 where
     C: TryFromIterator<Label, M> + Clone,
@@ -50,6 +65,24 @@ where
         }
     }
 
+    /// Yield the number of labels in each key with it, e.g., to indent a
+    /// tree of completions, without measuring the collected keys.
+    ///
+    /// ```rust
+    /// use trie_rs::map::Trie;
+    ///
+    /// let trie = Trie::from_iter([("a", 0), ("app", 1), ("アップル", 2)]);
+    /// let results: Vec<(String, &u8, usize)> = trie.predictive_search("").with_depth().collect();
+    /// assert_eq!(results, [
+    ///     ("a".to_string(), &0, 1),
+    ///     ("app".to_string(), &1, 3),
+    ///     ("アップル".to_string(), &2, 12),
+    /// ]);
+    /// ```
+    pub fn with_depth(self) -> WithDepth<'a, Label, Value, C, M> {
+        WithDepth(self)
+    }
+
     fn empty(trie: &'a Trie<Label, Value>) -> Self {
         SearchIter {
             prefix: Vec::new(),
@@ -68,21 +101,48 @@ where
     type Item = (C, &'a Value);
     #[inline]
     fn next(&mut self) -> Option<Self::Item> {
+        self.next_with_depth().map(|(entry, v, _)| (entry, v))
+    }
+}
+
+impl<'a, Label: Ord + Clone, Value, C, M> SearchIter<'a, Label, Value, C, M>
+where
+    C: TryFromIterator<Label, M> + Clone,
+{
+    #[inline]
+    fn next_with_depth(&mut self) -> Option<(C, &'a Value, usize)> {
         match self.first.take() {
             // None => None,
             None => self.postfix_iter.next().map(|(postfix, v)| {
+                let depth = self.prefix.len() + postfix.len();
                 let entry = C::try_from_iter(self.prefix.clone().into_iter().chain(postfix))
                     .expect("Could not collect postfix");
                 // let mut entry = self.prefix.clone();
                 // let ext: C = postfix.into_iter().try_collect().expect("Could not collect postfix");
                 // entry.extend([ext]);
-                (entry, v)
+                (entry, v, depth)
             }),
-            x => x,
+            Some((entry, v)) => Some((entry, v, self.prefix.len())),
         }
     }
 }
 
+#[derive(Debug, Clone)]
+/// Yields the entries of a [SearchIter] with the number of labels in each
+/// key. See [SearchIter::with_depth].
+pub struct WithDepth<'a, Label, Value, C, M>(SearchIter<'a, Label, Value, C, M>);
+
+impl<'a, Label: Ord + Clone, Value, C, M> Iterator for WithDepth<'a, Label, Value, C, M>
+where
+    C: TryFromIterator<Label, M> + Clone,
+{
+    type Item = (C, &'a Value, usize);
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next_with_depth()
+    }
+}
+
 // impl<'a, Label: Ord + Clone, Value, C> Iterator for SearchIter<'a, Label, Value, C, Collect>
 // where C: TryFromIterator<Label, Collect> + Extend<Label> + Clone,
 // Vec<Label>: TryFromIterator<Label, Collect>
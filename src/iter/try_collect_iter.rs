@@ -0,0 +1,66 @@
+use crate::try_collect::{TryCollect, TryFromIterator};
+use std::marker::PhantomData;
+
+#[derive(Debug)]
+/// Collects the `Vec<Label>` keys of a [crate::iter] iterator into `C`,
+/// yielding an error rather than panicking when that fails.
+///
+/// Made by the `try_*` searches such as
+/// [crate::map::Trie::try_predictive_search].
+pub struct TryCollectIter<I, C, M> {
+    iter: I,
+    col: PhantomData<(C, M)>,
+}
+
+impl<I: Clone, C, M> Clone for TryCollectIter<I, C, M> {
+    fn clone(&self) -> Self {
+        Self {
+            iter: self.iter.clone(),
+            col: PhantomData,
+        }
+    }
+}
+
+impl<I, C, M> TryCollectIter<I, C, M> {
+    pub(crate) fn new(iter: I) -> Self {
+        Self {
+            iter,
+            col: PhantomData,
+        }
+    }
+}
+
+impl<I, Label, V, C, M> Iterator for TryCollectIter<I, C, M>
+where
+    I: Iterator<Item = (Vec<Label>, V)>,
+    C: TryFromIterator<Label, M>,
+{
+    type Item = Result<(C, V), C::Error>;
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter
+            .next()
+            .map(|(key, value)| key.into_iter().try_collect().map(|key| (key, value)))
+    }
+}
+
+#[derive(Debug, Clone)]
+/// Retains keys and strips off `Value`s from a fallible [crate::iter]
+/// iterator.
+pub struct TryKeys<I>(I);
+
+impl<I> TryKeys<I> {
+    /// Creates a new `TryKeys` iterator.
+    pub fn new(iter: I) -> Self {
+        Self(iter)
+    }
+}
+
+impl<I, C, V, E> Iterator for TryKeys<I>
+where
+    I: Iterator<Item = Result<(C, V), E>>,
+{
+    type Item = Result<C, E>;
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next().map(|x| x.map(|x| x.0))
+    }
+}
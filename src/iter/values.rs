@@ -0,0 +1,58 @@
+#[derive(Debug, Clone)]
+/// Retains values and strips off keys from a [crate::iter] iterator.
+pub struct Values<I>(I);
+
+impl<I> Values<I> {
+    ///Creates a new `Values` iterator.
+    pub fn new(iter: I) -> Self {
+        Self(iter)
+    }
+}
+
+// Like `Keys`, this is generic for V, which in a `map::Trie<K,V>` iterator is
+// `&V`.
+impl<I, C, V> Iterator for Values<I>
+where
+    I: Iterator<Item = (C, V)>,
+{
+    type Item = V;
+    fn next(&mut self) -> Option<V> {
+        self.0.next().map(|x| x.1)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.0.size_hint()
+    }
+}
+
+/// Strip an iterator items `(K, V)` to only have `V`.
+///
+/// ```rust
+/// use trie_rs::iter::ValuesExt;
+/// use trie_rs::map::Trie;
+///
+/// let trie = Trie::from_iter([("a", 0), ("app", 1), ("apple", 2)]);
+/// let sum: u8 = trie.predictive_search::<String, _>("app").values().sum();
+/// assert_eq!(sum, 3);
+/// ```
+pub trait ValuesExt: Iterator {
+    /// Retain values and strip keys from a [crate::iter] iterator.
+    fn values(self) -> Values<Self>
+    where
+        Self: Sized,
+    {
+        Values::new(self)
+    }
+
+    /// Retain both keys and values. This is the identity and exists to make
+    /// the intent clear next to [KeysExt::keys](super::KeysExt::keys) and
+    /// [ValuesExt::values].
+    fn entries(self) -> Self
+    where
+        Self: Sized,
+    {
+        self
+    }
+}
+
+impl<T> ValuesExt for T where T: Iterator + ?Sized {}
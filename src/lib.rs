@@ -6,6 +6,11 @@ pub mod inc_search;
 mod internal_data_structure;
 pub mod iter;
 pub mod map;
+#[cfg(feature = "unicode-normalization")]
+pub mod normalize;
+pub mod phonetic;
+#[cfg(feature = "testing")]
+pub mod test_support;
 mod trie;
 pub mod try_collect;
 pub use trie::{Trie, TrieBuilder};
@@ -1,9 +1,45 @@
 //! A trie that maps sequence of `Label`s to a `Value`.
 use crate::internal_data_structure::naive_trie::NaiveTrie;
-use louds_rs::Louds;
+use fid_rs::Fid;
+use louds_rs::{Louds, LoudsNodeNum};
 
+pub use binary::{FixedBytes, LabelEncoding};
+pub use check::InvariantError;
+pub use pattern::Glob;
+#[cfg(feature = "rand")]
+pub use sample::Sampler;
+pub use stats::Stats;
+pub use trie_builder::{DuplicateKeyError, DuplicatePolicy};
+#[cfg(feature = "bytemuck")]
+pub use view::TrieView;
+pub use visit::{Descent, Visitor};
+
+mod binary;
+mod chars;
+mod check;
+mod counts;
+mod csv;
+mod dawg;
+mod dyn_trie;
+mod fuzzy;
+#[cfg(feature = "unicode-segmentation")]
+mod graphemes;
+#[cfg(feature = "serde_json")]
+mod json;
+#[cfg(feature = "rayon")]
+mod par;
+mod pattern;
+#[cfg(feature = "rand")]
+mod sample;
+mod stats;
+mod suffix_trie;
 mod trie;
 mod trie_builder;
+mod try_search;
+mod utf8;
+#[cfg(feature = "bytemuck")]
+mod view;
+mod visit;
 
 #[cfg(feature = "mem_dbg")]
 use mem_dbg::MemDbg;
@@ -12,25 +48,133 @@ use mem_dbg::MemDbg;
 #[cfg_attr(feature = "mem_dbg", derive(mem_dbg::MemDbg, mem_dbg::MemSize))]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 /// A trie for sequences of the type `Label`; each sequence has an associated `Value`.
+///
+/// # Limits
+///
+/// A trie has one node per label stored plus the root, so it can hold as many
+/// nodes as a `Vec<Label>` can hold labels, i.e., up to `usize::MAX` on any
+/// target. With the "counts" crate feature it holds at most `u32::MAX`
+/// entries.
 pub struct Trie<Label, Value> {
     louds: Louds,
 
-    /// (LoudsNodeNum - 2) -> TrieLabel
-    trie_labels: Vec<TrieLabel<Label, Value>>,
+    /// (LoudsNodeNum - 2) -> Label
+    labels: Vec<Label>,
+
+    /// (LoudsNodeNum - 1) -> whether the node terminates an entry
+    terminals: Fid,
+
+    /// (number of terminals up to and including a node - 1) -> Value
+    ///
+    /// Values are stored apart from labels so that nodes which are not
+    /// terminal take no room for a value.
+    values: Vec<Value>,
+
+    /// (LoudsNodeNum - 1) -> number of terminals in the node's subtree
+    #[cfg(feature = "counts")]
+    counts: Vec<u32>,
+
+    /// (LoudsNodeNum - 1) -> sum of the weights of the entries in the node's
+    /// subtree, if built with [TrieBuilder::build_weighted]
+    #[cfg(feature = "rand")]
+    weights: Option<Vec<f64>>,
+
+    /// (number of terminals up to and including a node - 1) -> sequence
+    /// number of the push that set its value, if kept. See
+    /// [TrieBuilder::with_insertion_order].
+    insertion_order: Option<Vec<u64>>,
 }
 
 #[derive(Debug, Clone)]
 #[cfg_attr(feature = "mem_dbg", derive(mem_dbg::MemDbg, mem_dbg::MemSize))]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 /// A trie builder for [Trie].
+///
+/// # `push` or `insert`?
+///
+/// Both add an entry the same way. [TrieBuilder::push] takes a borrowed key
+/// such as `&str` or `&[Label]` and clones its labels, so it requires
+/// `Label: Clone`. [TrieBuilder::insert] takes the labels by value and
+/// requires nothing of them. [TrieBuilder::push_ref] also clones a borrowed
+/// value.
+///
+/// Labels need not be `Clone` to build and query a trie: `exact_match`,
+/// `is_prefix`, `has_completion`, `prefix_count`, `aggregate_prefix`,
+/// `visit`, and incremental search all work without it. Searches that return
+/// keys, such as `predictive_search`, must clone labels to build them.
 pub struct TrieBuilder<Label, Value> {
     naive_trie: NaiveTrie<Label, Value>,
+    /// Expected number of entries.
+    capacity: usize,
+    /// Sequence number of the next push if insertion order is kept.
+    insertion_order: Option<u64>,
+    /// What to do when an entry is pushed again.
+    on_duplicate: DuplicatePolicy,
+    /// Number of duplicate entries pushed under [DuplicatePolicy::Error].
+    duplicates: usize,
 }
 
 #[derive(Debug, Clone)]
 #[cfg_attr(feature = "mem_dbg", derive(mem_dbg::MemDbg, mem_dbg::MemSize))]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
-struct TrieLabel<Label, Value> {
-    label: Label,
-    value: Option<Value>,
+/// A trie that stores its keys reversed to answer "ends with" queries. Build
+/// one with [TrieBuilder::build_reversed].
+///
+/// The wrapped [Trie] holds the reversed keys; querying it directly gives
+/// reversed results.
+pub struct SuffixTrie<Label, Value>(pub Trie<Label, Value>);
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "mem_dbg", derive(mem_dbg::MemDbg, mem_dbg::MemSize))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// A trie whose equal subtrees are merged into one, i.e., a directed acyclic
+/// word graph (DAWG). Build one with [TrieBuilder::build_minimized].
+///
+/// Keys that end with the same labels and equal values share those nodes, so
+/// a dictionary with common suffixes, e.g., inflected words, takes far fewer
+/// nodes than a [Trie]. Queries answer as a [Trie]'s would.
+pub struct Dawg<Label, Value> {
+    /// node -> start of its edges in `edges`, plus the end of the last.
+    offsets: Vec<u32>,
+    /// The edges of every node sorted by label, to the node they lead to.
+    edges: Vec<(Label, u32)>,
+    /// node -> value if the node ends an entry
+    values: Vec<Option<Value>>,
+    /// The node of the empty key, which is made last.
+    root: u32,
+    /// Number of entries.
+    len: usize,
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "mem_dbg", derive(mem_dbg::MemDbg, mem_dbg::MemSize))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// A trie that supports inserting and removing entries at any time.
+///
+/// It is a tree of sorted child vectors, so it uses more memory and queries
+/// are slower than a [Trie], but an edit costs _O(m log k)_ where _m_ is the
+/// key length and _k_ the branching rather than a rebuild. Call
+/// [DynTrie::freeze] to convert it into a [Trie] once edits are done.
+///
+/// ```rust
+/// use trie_rs::map::DynTrie;
+///
+/// let mut trie = DynTrie::new();
+/// trie.push("app", 1);
+/// trie.push("apple", 2);
+/// assert_eq!(trie.remove("app"), Some(1));
+/// let trie = trie.freeze();
+/// assert_eq!(trie.exact_match("apple"), Some(&2));
+/// assert_eq!(trie.exact_match("app"), None);
+/// ```
+pub struct DynTrie<Label, Value> {
+    naive_trie: NaiveTrie<Label, Value>,
+    len: usize,
 }
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// Identifies an entry of a [Trie], e.g., to find entries first and mutate
+/// their values one at a time after. See [Trie::predictive_search_indices].
+///
+/// An id is only meaningful for the trie that returned it.
+pub struct TerminalId(LoudsNodeNum);
@@ -0,0 +1,664 @@
+//! A versioned binary format that is written and read as a stream.
+//!
+//! All numbers are little-endian and every section starts on an 8-byte
+//! boundary:
+//!
+//! | Section | Contents |
+//! |---------|----------|
+//! | header | [MAGIC], then `u32`s for the [VERSION], label size, value size, and flags |
+//! | nodes | `u64` number of nodes including the root |
+//! | LOUDS bits | `2 * nodes + 1` bits packed into `u64` words, lowest bit first |
+//! | terminal bits | `nodes` bits packed into `u64` words |
+//! | labels | one label per node but the root in breadth-first order, as varints if flag bit 1 is set |
+//! | values | one value per terminal node in breadth-first order |
+//! | insertion order | one `u64` per value if flag bit 0 is set |
+//!
+//! A varint is the label's little-endian bytes read as an unsigned integer
+//! and written in LEB128: seven bits per byte, lowest first, with the high bit
+//! set on every byte but the last. See [LabelEncoding].
+use super::trie_builder::assemble_parts;
+use super::Trie;
+use fid_rs::Fid;
+use louds_rs::{Louds, LoudsNodeNum};
+use std::io::{self, Read, Write};
+
+/// Magic bytes that start the binary format.
+pub(super) const MAGIC: [u8; 8] = *b"trie-rs\0";
+
+/// Version of the binary format.
+pub(super) const VERSION: u32 = 1;
+
+/// Flag bit set when the insertion order is stored.
+const INSERTION_ORDER: u32 = 1;
+
+/// Flag bit set when the labels are varints.
+pub(super) const VARINT_LABELS: u32 = 2;
+
+/// How [Trie::write_to_with] writes labels. Values are always fixed-width.
+///
+/// [Trie::write_to] uses the default, [LabelEncoding::Varint].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum LabelEncoding {
+    /// Little-endian varints, which are smaller for multi-byte labels with
+    /// small values, e.g., `char`s of mostly ASCII text.
+    #[default]
+    Varint,
+    /// [FixedBytes::SIZE] little-endian bytes each, which
+    /// `TrieView` requires to read labels in place.
+    Fixed,
+}
+
+/// A type that is written as a fixed number of little-endian bytes in the
+/// binary format of [Trie::write_to].
+pub trait FixedBytes: Sized {
+    /// Number of bytes written.
+    const SIZE: usize;
+
+    /// Write `self` into `bytes`, which is [FixedBytes::SIZE] long.
+    fn write_bytes(&self, bytes: &mut [u8]);
+
+    /// Read a value from `bytes`, which is [FixedBytes::SIZE] long, or
+    /// `None` if they are not a valid value.
+    fn read_bytes(bytes: &[u8]) -> Option<Self>;
+}
+
+macro_rules! fixed_bytes {
+    ($($t:ty),*) => {
+        $(
+            impl FixedBytes for $t {
+                const SIZE: usize = std::mem::size_of::<$t>();
+
+                fn write_bytes(&self, bytes: &mut [u8]) {
+                    bytes.copy_from_slice(&self.to_le_bytes());
+                }
+
+                fn read_bytes(bytes: &[u8]) -> Option<Self> {
+                    bytes.try_into().ok().map(<$t>::from_le_bytes)
+                }
+            }
+        )*
+    }
+}
+
+fixed_bytes!(u8, u16, u32, u64, u128, i8, i16, i32, i64, i128, f32, f64);
+
+impl FixedBytes for char {
+    const SIZE: usize = 4;
+
+    fn write_bytes(&self, bytes: &mut [u8]) {
+        (*self as u32).write_bytes(bytes);
+    }
+
+    fn read_bytes(bytes: &[u8]) -> Option<Self> {
+        u32::read_bytes(bytes).and_then(char::from_u32)
+    }
+}
+
+impl FixedBytes for () {
+    const SIZE: usize = 0;
+
+    fn write_bytes(&self, _bytes: &mut [u8]) {}
+
+    fn read_bytes(_bytes: &[u8]) -> Option<Self> {
+        Some(())
+    }
+}
+
+impl<Label: Ord + FixedBytes, Value: FixedBytes> Trie<Label, Value> {
+    /// Write this trie to `w` in a versioned binary format without
+    /// buffering it whole. Many small writes are made, so wrap an unbuffered
+    /// writer like a `File` in a `BufWriter`.
+    ///
+    /// Labels are written as [LabelEncoding::default], varints. Use
+    /// [Trie::write_to_with] and [LabelEncoding::Fixed] for a buffer to be
+    /// read by `TrieView`.
+    ///
+    /// ```rust
+    /// use trie_rs::map::Trie;
+    ///
+    /// let trie = Trie::from_iter([("a", 0u32), ("app", 1), ("apple", 2)]);
+    /// let mut bytes = Vec::new();
+    /// trie.write_to(&mut bytes).unwrap();
+    /// let read = Trie::<u8, u32>::read_from(&mut bytes.as_slice()).unwrap();
+    /// assert_eq!(read, trie);
+    /// ```
+    pub fn write_to(&self, w: &mut impl Write) -> io::Result<()> {
+        self.write_to_with(w, LabelEncoding::default())
+    }
+
+    /// Write this trie to `w` like [Trie::write_to] with labels in
+    /// `encoding`. [Trie::read_from] reads either encoding.
+    ///
+    /// ```rust
+    /// use trie_rs::map::{LabelEncoding, Trie};
+    ///
+    /// let trie = Trie::<char, u32>::from_iter([("apple".chars().collect::<Vec<_>>(), 0)]);
+    /// let mut bytes = Vec::new();
+    /// trie.write_to_with(&mut bytes, LabelEncoding::Varint).unwrap();
+    /// let read = Trie::<char, u32>::read_from(&mut bytes.as_slice()).unwrap();
+    /// assert_eq!(read, trie);
+    /// ```
+    pub fn write_to_with(&self, w: &mut impl Write, encoding: LabelEncoding) -> io::Result<()> {
+        let mut w = Counted { inner: w, len: 0 };
+        let mut flags = 0;
+        if self.insertion_order.is_some() {
+            flags |= INSERTION_ORDER;
+        }
+        if encoding == LabelEncoding::Varint {
+            flags |= VARINT_LABELS;
+        }
+        w.write_all(&MAGIC)?;
+        for n in [VERSION, Label::SIZE as u32, Value::SIZE as u32, flags] {
+            w.write_all(&n.to_le_bytes())?;
+        }
+        let nodes = self.last_node_num().0;
+        w.write_all(&nodes.to_le_bytes())?;
+
+        let mut bits = BitWriter::new(&mut w);
+        bits.push(true)?;
+        bits.push(false)?;
+        for node_num in (1..=nodes).map(LoudsNodeNum) {
+            for _ in self.children_node_nums(node_num) {
+                bits.push(true)?;
+            }
+            bits.push(false)?;
+        }
+        bits.finish()?;
+        let mut bits = BitWriter::new(&mut w);
+        for node_num in (1..=nodes).map(LoudsNodeNum) {
+            bits.push(self.is_terminal(node_num))?;
+        }
+        bits.finish()?;
+
+        match encoding {
+            LabelEncoding::Varint => write_all_varint(&mut w, &self.labels)?,
+            LabelEncoding::Fixed => write_all_fixed(&mut w, &self.labels)?,
+        }
+        write_all_fixed(&mut w, &self.values)?;
+        if let Some(orders) = &self.insertion_order {
+            write_all_fixed(&mut w, orders)?;
+        }
+        Ok(())
+    }
+
+    /// Read a trie written by [Trie::write_to] or [Trie::write_to_with] from
+    /// `r`. Many small reads are made, so wrap an unbuffered reader like a
+    /// `File` in a `BufReader`.
+    ///
+    /// The bit vectors are held packed while reading. Since louds-rs and
+    /// fid-rs only build from a `&[bool]`, each is unpacked in turn at the
+    /// end, which briefly takes one byte per bit of that vector alone.
+    ///
+    /// Returns an error of kind [io::ErrorKind::InvalidData] if the data is
+    /// not in this format, is of another version, or does not hold `Label`s
+    /// and `Value`s.
+    pub fn read_from(r: &mut impl Read) -> io::Result<Self> {
+        let mut r = Counted { inner: r, len: 0 };
+        let mut magic = [0; 8];
+        r.read_exact(&mut magic)?;
+        if magic != MAGIC {
+            return Err(invalid_data("not a trie-rs binary"));
+        }
+        let version = read_u32(&mut r)?;
+        if version != VERSION {
+            return Err(invalid_data("unsupported version"));
+        }
+        let label_size = read_u32(&mut r)?;
+        let value_size = read_u32(&mut r)?;
+        if label_size as usize != Label::SIZE || value_size as usize != Value::SIZE {
+            return Err(invalid_data("label or value size does not match"));
+        }
+        let flags = read_u32(&mut r)?;
+        let mut nodes = [0; 8];
+        r.read_exact(&mut nodes)?;
+        let nodes = u64::from_le_bytes(nodes);
+        if nodes == 0 || nodes > u64::MAX / 4 {
+            return Err(invalid_data("invalid number of nodes"));
+        }
+        if usize::try_from(nodes).is_err() {
+            return Err(invalid_data("too many nodes for this platform"));
+        }
+
+        let louds_bits = Bits::read(&mut r, 2 * nodes + 1)?;
+        if !louds_bits.is_louds() {
+            return Err(invalid_data("malformed LOUDS bits"));
+        }
+        let terminals = Bits::read(&mut r, nodes)?;
+        if terminals.get(0) {
+            return Err(invalid_data("root is terminal"));
+        }
+        let labels = if flags & VARINT_LABELS != 0 {
+            read_all_varint(&mut r, nodes - 1)?
+        } else {
+            read_all_fixed(&mut r, nodes - 1)?
+        };
+        let count = terminals.count_ones();
+        let values = read_all_fixed(&mut r, count)?;
+        let insertion_order = if flags & INSERTION_ORDER != 0 {
+            Some(read_all_fixed(&mut r, count)?)
+        } else {
+            None
+        };
+        let louds = Louds::from(&louds_bits.unpack()[..]);
+        drop(louds_bits);
+        let terminals = Fid::from(&terminals.unpack()[..]);
+        let trie = assemble_parts(louds, labels, terminals, values, insertion_order);
+        trie.check()
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        Ok(trie)
+    }
+}
+
+/// A bit vector as read, packed into `u64` words lowest bit first.
+struct Bits {
+    words: Vec<u64>,
+    len: u64,
+}
+
+impl Bits {
+    /// Read `len` bits, clearing any padding bits of the last word.
+    fn read<R: Read>(r: &mut Counted<R>, len: u64) -> io::Result<Self> {
+        let mut words = Vec::new();
+        let mut word = [0; 8];
+        while (words.len() as u64) * 64 < len {
+            r.read_exact(&mut word)?;
+            words.push(u64::from_le_bytes(word));
+        }
+        if len % 64 != 0 {
+            if let Some(last) = words.last_mut() {
+                *last &= (1 << (len % 64)) - 1;
+            }
+        }
+        Ok(Self { words, len })
+    }
+
+    fn get(&self, i: u64) -> bool {
+        self.words[(i / 64) as usize] >> (i % 64) & 1 == 1
+    }
+
+    fn count_ones(&self) -> u64 {
+        self.words.iter().map(|word| word.count_ones() as u64).sum()
+    }
+
+    /// Return true if this is a valid LOUDS bit string, which louds-rs would
+    /// otherwise panic on.
+    fn is_louds(&self) -> bool {
+        if self.len < 2 || !self.get(0) || self.get(1) {
+            return false;
+        }
+        // Ones minus zeros so far, which must never fall below -1. A word
+        // with too few zeros to reach that is taken whole.
+        let mut balance: i64 = 0;
+        for (k, &word) in self.words.iter().enumerate() {
+            let n = (self.len - 64 * k as u64).min(64) as i64;
+            let ones = word.count_ones() as i64;
+            if balance - (n - ones) >= -1 {
+                balance += 2 * ones - n;
+                continue;
+            }
+            for i in 0..n {
+                balance += if word >> i & 1 == 1 { 1 } else { -1 };
+                if balance < -1 {
+                    return false;
+                }
+            }
+        }
+        balance == -1
+    }
+
+    /// Return one `bool` per bit, which louds-rs and fid-rs build from.
+    fn unpack(&self) -> Vec<bool> {
+        (0..self.len).map(|i| self.get(i)).collect()
+    }
+}
+
+/// Tracks the number of bytes passed through to pad sections.
+struct Counted<T> {
+    inner: T,
+    len: u64,
+}
+
+impl<T: Write> Write for Counted<T> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let n = self.inner.write(buf)?;
+        self.len += n as u64;
+        Ok(n)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+impl<T: Read> Read for Counted<T> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let n = self.inner.read(buf)?;
+        self.len += n as u64;
+        Ok(n)
+    }
+}
+
+impl<T: Write> Counted<T> {
+    /// Write zeros up to the next 8-byte boundary.
+    fn pad(&mut self) -> io::Result<()> {
+        let padding = (8 - self.len % 8) % 8;
+        self.write_all(&[0; 8][..padding as usize])
+    }
+}
+
+impl<T: Read> Counted<T> {
+    /// Skip to the next 8-byte boundary.
+    fn skip_padding(&mut self) -> io::Result<()> {
+        let padding = (8 - self.len % 8) % 8;
+        self.read_exact(&mut [0; 8][..padding as usize])
+    }
+}
+
+/// Packs bits into little-endian `u64` words.
+struct BitWriter<'a, W: Write> {
+    w: &'a mut Counted<W>,
+    word: u64,
+    len: u32,
+}
+
+impl<'a, W: Write> BitWriter<'a, W> {
+    fn new(w: &'a mut Counted<W>) -> Self {
+        Self { w, word: 0, len: 0 }
+    }
+
+    fn push(&mut self, bit: bool) -> io::Result<()> {
+        self.word |= (bit as u64) << self.len;
+        self.len += 1;
+        if self.len == 64 {
+            self.w.write_all(&self.word.to_le_bytes())?;
+            self.word = 0;
+            self.len = 0;
+        }
+        Ok(())
+    }
+
+    fn finish(self) -> io::Result<()> {
+        if self.len > 0 {
+            self.w.write_all(&self.word.to_le_bytes())?;
+        }
+        Ok(())
+    }
+}
+
+fn write_all_fixed<T: FixedBytes, W: Write>(w: &mut Counted<W>, items: &[T]) -> io::Result<()> {
+    let mut buf = vec![0; T::SIZE];
+    for item in items {
+        item.write_bytes(&mut buf);
+        w.write_all(&buf)?;
+    }
+    w.pad()
+}
+
+fn read_all_fixed<T: FixedBytes, R: Read>(r: &mut Counted<R>, count: u64) -> io::Result<Vec<T>> {
+    let mut buf = vec![0; T::SIZE];
+    let mut items = Vec::new();
+    for _ in 0..count {
+        r.read_exact(&mut buf)?;
+        items.push(T::read_bytes(&buf).ok_or_else(|| invalid_data("invalid label or value"))?);
+    }
+    r.skip_padding()?;
+    Ok(items)
+}
+
+fn write_all_varint<T: FixedBytes, W: Write>(w: &mut Counted<W>, items: &[T]) -> io::Result<()> {
+    let mut buf = vec![0; T::SIZE];
+    let mut varint = Vec::new();
+    for item in items {
+        item.write_bytes(&mut buf);
+        // Number of significant bits.
+        let bits = match buf.iter().rposition(|&byte| byte != 0) {
+            Some(i) => 8 * (i + 1) - buf[i].leading_zeros() as usize,
+            None => 0,
+        };
+        varint.clear();
+        let mut bit = 0;
+        loop {
+            let mut group = 0u8;
+            for k in 0..7 {
+                if bit + k < bits && buf[(bit + k) / 8] >> ((bit + k) % 8) & 1 == 1 {
+                    group |= 1 << k;
+                }
+            }
+            bit += 7;
+            if bit >= bits {
+                varint.push(group);
+                break;
+            }
+            varint.push(group | 0x80);
+        }
+        w.write_all(&varint)?;
+    }
+    w.pad()
+}
+
+fn read_all_varint<T: FixedBytes, R: Read>(r: &mut Counted<R>, count: u64) -> io::Result<Vec<T>> {
+    let mut buf = vec![0; T::SIZE];
+    let mut items = Vec::new();
+    for _ in 0..count {
+        buf.fill(0);
+        let mut bit = 0;
+        loop {
+            let mut byte = [0];
+            r.read_exact(&mut byte)?;
+            for k in 0..7 {
+                if byte[0] >> k & 1 == 1 {
+                    let i = (bit + k) / 8;
+                    if i >= buf.len() {
+                        return Err(invalid_data("varint label is too large"));
+                    }
+                    buf[i] |= 1 << ((bit + k) % 8);
+                }
+            }
+            bit += 7;
+            if byte[0] & 0x80 == 0 {
+                break;
+            }
+            if bit >= 8 * buf.len() + 7 {
+                return Err(invalid_data("varint label is too long"));
+            }
+        }
+        items.push(T::read_bytes(&buf).ok_or_else(|| invalid_data("invalid label or value"))?);
+    }
+    r.skip_padding()?;
+    Ok(items)
+}
+
+fn read_u32<R: Read>(r: &mut R) -> io::Result<u32> {
+    let mut bytes = [0; 4];
+    r.read_exact(&mut bytes)?;
+    Ok(u32::from_le_bytes(bytes))
+}
+
+pub(super) fn invalid_data(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+#[cfg(test)]
+mod binary_tests {
+    use crate::map::{LabelEncoding, Trie, TrieBuilder};
+    use std::io::ErrorKind;
+
+    fn build_trie() -> Trie<u8, u32> {
+        let mut builder = TrieBuilder::new();
+        builder.push("a", 0);
+        builder.push("app", 1);
+        builder.push("apple", 2);
+        builder.push("better", 3);
+        builder.push("application", 4);
+        builder.push("アップル🍎", 5);
+        builder.build()
+    }
+
+    fn to_bytes<Label: Ord + super::FixedBytes, Value: super::FixedBytes>(
+        trie: &Trie<Label, Value>,
+    ) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        trie.write_to(&mut bytes).unwrap();
+        bytes
+    }
+
+    #[test]
+    fn round_trip() {
+        let trie = build_trie();
+        let bytes = to_bytes(&trie);
+        assert_eq!(bytes.len() % 8, 0);
+        let read = Trie::<u8, u32>::read_from(&mut bytes.as_slice()).unwrap();
+        assert_eq!(read, trie);
+        assert_eq!(read.predictive_search::<String, _>("app").count(), 3);
+    }
+
+    #[test]
+    fn round_trip_char_and_unit() {
+        let trie = Trie::<char, ()>::from_iter([("アップル".chars().collect::<Vec<_>>(), ())]);
+        let read = Trie::<char, ()>::read_from(&mut to_bytes(&trie).as_slice()).unwrap();
+        assert_eq!(read, trie);
+    }
+
+    #[test]
+    fn round_trip_label_encodings() {
+        let trie = Trie::<char, u32>::from_iter([
+            ("apple".chars().collect::<Vec<_>>(), 0),
+            ("アップル🍎".chars().collect(), 1),
+            (vec![char::MAX], 2),
+        ]);
+        let mut sizes = vec![];
+        for encoding in [LabelEncoding::Varint, LabelEncoding::Fixed] {
+            let mut bytes = Vec::new();
+            trie.write_to_with(&mut bytes, encoding).unwrap();
+            assert_eq!(bytes.len() % 8, 0);
+            let read = Trie::<char, u32>::read_from(&mut bytes.as_slice()).unwrap();
+            assert_eq!(read, trie);
+            sizes.push(bytes.len());
+        }
+        assert!(sizes[0] < sizes[1]);
+
+        let mut bytes = Vec::new();
+        build_trie()
+            .write_to_with(&mut bytes, LabelEncoding::Varint)
+            .unwrap();
+        let read = Trie::<u8, u32>::read_from(&mut bytes.as_slice()).unwrap();
+        assert_eq!(read, build_trie());
+    }
+
+    #[test]
+    fn varint_label_out_of_range() {
+        let trie = Trie::<u8, ()>::from_iter([("\x7f", ())]);
+        let mut bytes = Vec::new();
+        trie.write_to_with(&mut bytes, LabelEncoding::Varint)
+            .unwrap();
+        // After the header, node count, and one word each of bits.
+        assert_eq!(&bytes[48..50], [0x7f, 0]);
+        // 0x80 0x02 is 256, which is not a u8.
+        bytes[48] = 0x80;
+        bytes[49] = 0x02;
+        let err = Trie::<u8, ()>::read_from(&mut bytes.as_slice()).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn round_trip_empty() {
+        let trie: Trie<u8, u32> = TrieBuilder::new().build();
+        let read = Trie::<u8, u32>::read_from(&mut to_bytes(&trie).as_slice()).unwrap();
+        assert_eq!(read, trie);
+    }
+
+    #[test]
+    fn round_trip_insertion_order() {
+        let mut builder = TrieBuilder::new().with_insertion_order();
+        builder.push("b", 0u8);
+        builder.push("a", 1);
+        let trie = builder.build();
+        let read = Trie::<u8, u8>::read_from(&mut to_bytes(&trie).as_slice()).unwrap();
+        assert_eq!(read.insertion_index("b"), Some(0));
+        assert_eq!(read.insertion_index("a"), Some(1));
+    }
+
+    #[test]
+    fn invalid_data() {
+        let bytes = to_bytes(&build_trie());
+        let read = |bytes: &[u8]| {
+            Trie::<u8, u32>::read_from(&mut &bytes[..])
+                .unwrap_err()
+                .kind()
+        };
+        let mut bad_magic = bytes.clone();
+        bad_magic[0] = b'T';
+        assert_eq!(read(&bad_magic), ErrorKind::InvalidData);
+        let mut bad_version = bytes.clone();
+        bad_version[8] = 2;
+        assert_eq!(read(&bad_version), ErrorKind::InvalidData);
+        let mut bad_louds = bytes.clone();
+        bad_louds[32] = 0;
+        assert_eq!(read(&bad_louds), ErrorKind::InvalidData);
+        assert_eq!(read(&bytes[..bytes.len() - 8]), ErrorKind::UnexpectedEof);
+        let wrong_value = Trie::<u8, u8>::read_from(&mut bytes.as_slice()).unwrap_err();
+        assert_eq!(wrong_value.kind(), ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn is_louds() {
+        fn reference(bits: &[bool]) -> bool {
+            let (mut zeros, mut ones) = (0, 0);
+            for &bit in bits {
+                if bit {
+                    ones += 1;
+                } else {
+                    zeros += 1;
+                }
+                if zeros > ones + 1 {
+                    return false;
+                }
+            }
+            bits.starts_with(&[true, false]) && zeros == ones + 1
+        }
+        fn pack(bits: &[bool]) -> super::Bits {
+            let mut words = vec![0u64; (bits.len() + 63) / 64];
+            for (i, &bit) in bits.iter().enumerate() {
+                words[i / 64] |= (bit as u64) << (i % 64);
+            }
+            super::Bits {
+                words,
+                len: bits.len() as u64,
+            }
+        }
+        for len in 0..=12 {
+            for n in 0u32..1 << len {
+                let bits: Vec<bool> = (0..len).map(|i| n >> i & 1 == 1).collect();
+                assert_eq!(pack(&bits).is_louds(), reference(&bits), "{:?}", bits);
+            }
+        }
+        // Strings spanning many words, valid and with one bit flipped.
+        let trie = Trie::<u8, u32>::from_iter((0..300u32).map(|i| (format!("{}", i * 7), i)));
+        let mut bytes = Vec::new();
+        trie.write_to(&mut bytes).unwrap();
+        let nodes = u64::from_le_bytes(bytes[24..32].try_into().unwrap());
+        let words: Vec<u64> = bytes[32..]
+            .chunks(8)
+            .map(|word| u64::from_le_bytes(word.try_into().unwrap()))
+            .collect();
+        let bits: Vec<bool> = (0..2 * nodes + 1)
+            .map(|i| words[(i / 64) as usize] >> (i % 64) & 1 == 1)
+            .collect();
+        assert!(pack(&bits).is_louds());
+        for i in (0..bits.len()).step_by(7) {
+            let mut flipped = bits.clone();
+            flipped[i] = !flipped[i];
+            assert_eq!(pack(&flipped).is_louds(), reference(&flipped), "bit {}", i);
+        }
+    }
+
+    #[test]
+    fn unsorted_labels() {
+        let trie = Trie::<u8, u32>::from_iter([("a", 0), ("b", 1)]);
+        let mut bytes = to_bytes(&trie);
+        let labels = bytes.len() - 16;
+        bytes.swap(labels, labels + 1);
+        let err = Trie::<u8, u32>::read_from(&mut bytes.as_slice()).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidData);
+    }
+}
@@ -0,0 +1,117 @@
+//! Queries of `char` tries by `&str`.
+use super::Trie;
+use crate::iter::{PostfixIter, PrefixIter, SearchIter};
+use crate::try_collect::Collect;
+
+/// Return the `char`s of `s`.
+fn chars(s: &str) -> Vec<char> {
+    s.chars().collect()
+}
+
+impl<Value> Trie<char, Value> {
+    /// Return `Some(&Value)` if the `char`s of `query` are an exact match.
+    ///
+    /// ```rust
+    /// use trie_rs::map::Trie;
+    ///
+    /// let trie = Trie::from_iter([("すし".chars().collect::<Vec<_>>(), 0)]);
+    /// assert_eq!(trie.exact_match_str("すし"), Some(&0));
+    /// ```
+    pub fn exact_match_str(&self, query: &str) -> Option<&Value> {
+        self.exact_match(chars(query))
+    }
+
+    /// Return true if the `char`s of `query` are a prefix.
+    pub fn is_prefix_str(&self, query: &str) -> bool {
+        self.is_prefix(chars(query))
+    }
+
+    /// Return all entries and their values that start with the `char`s of
+    /// `query`.
+    ///
+    /// ```rust
+    /// use trie_rs::map::TrieBuilder;
+    ///
+    /// let mut builder = TrieBuilder::new();
+    /// builder.insert("すし".chars(), 0);
+    /// builder.insert("すしや".chars(), 1);
+    /// let trie = builder.build();
+    /// let results: Vec<(String, &u8)> = trie.predictive_search_str("すし").collect();
+    /// assert_eq!(results, [("すし".to_string(), &0), ("すしや".to_string(), &1)]);
+    /// ```
+    pub fn predictive_search_str(
+        &self,
+        query: &str,
+    ) -> SearchIter<'_, char, Value, String, Collect> {
+        self.predictive_search(chars(query))
+    }
+
+    /// Return the entries that are prefixes of the `char`s of `query` and
+    /// their values.
+    pub fn common_prefix_search_str(
+        &self,
+        query: &str,
+    ) -> PrefixIter<'_, char, Value, String, Collect> {
+        self.common_prefix_search(chars(query))
+    }
+
+    /// Return the postfixes and values of all entries that start with the
+    /// `char`s of `query`.
+    pub fn postfix_search_str(&self, query: &str) -> PostfixIter<'_, char, Value, String, Collect> {
+        self.postfix_search(chars(query))
+    }
+}
+
+#[cfg(test)]
+mod chars_tests {
+    use crate::map::{Trie, TrieBuilder};
+
+    fn build_trie() -> Trie<char, u8> {
+        let mut builder = TrieBuilder::new();
+        builder.insert("a".chars(), 0);
+        builder.insert("app".chars(), 1);
+        builder.insert("apple".chars(), 2);
+        builder.insert("better".chars(), 3);
+        builder.insert("application".chars(), 4);
+        builder.insert("アップル🍎".chars(), 5);
+        builder.build()
+    }
+
+    mod str_tests {
+        macro_rules! parameterized_tests {
+            ($($name:ident: $value:expr,)*) => {
+            $(
+                #[test]
+                fn $name() {
+                    let query: &str = $value;
+                    let trie = super::build_trie();
+                    let chars: Vec<char> = query.chars().collect();
+                    assert_eq!(trie.exact_match_str(query), trie.exact_match(&chars));
+                    assert_eq!(trie.is_prefix_str(query), trie.is_prefix(&chars));
+                    let results: Vec<(String, &u8)> = trie.predictive_search_str(query).collect();
+                    let expected: Vec<(String, &u8)> = trie.predictive_search(&chars).collect();
+                    assert_eq!(results, expected);
+                    let results: Vec<(String, &u8)> =
+                        trie.common_prefix_search_str(query).collect();
+                    let expected: Vec<(String, &u8)> = trie.common_prefix_search(&chars).collect();
+                    assert_eq!(results, expected);
+                    let results: Vec<(String, &u8)> = trie.postfix_search_str(query).collect();
+                    let expected: Vec<(String, &u8)> = trie.postfix_search(&chars).collect();
+                    assert_eq!(results, expected);
+                }
+            )*
+            }
+        }
+
+        parameterized_tests! {
+            t1: "",
+            t2: "a",
+            t3: "app",
+            t4: "appl",
+            t5: "applesauce",
+            t6: "アップ",
+            t7: "アップル🍎",
+            t8: "z",
+        }
+    }
+}
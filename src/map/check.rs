@@ -0,0 +1,216 @@
+//! Validation of a trie's structural invariants.
+use super::Trie;
+use crate::inc_search::Position;
+use louds_rs::LoudsNodeNum;
+use std::collections::VecDeque;
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// A broken structural invariant found by [Trie::check].
+pub enum InvariantError {
+    /// The children of `parent` are not strictly sorted by label.
+    UnsortedChildren {
+        /// The node whose children are out of order.
+        parent: Position,
+    },
+    /// Nodes are not numbered consecutively in breadth-first order.
+    Numbering {
+        /// The node number that should come next.
+        expected: Position,
+        /// The node number found instead.
+        found: Position,
+    },
+    /// The number of labels does not match the number of nodes.
+    NodeCount {
+        /// Number of labels stored.
+        labels: usize,
+        /// Number of nodes reachable from the root.
+        nodes: usize,
+    },
+    /// A node has no children and no value, so it leads to no entry.
+    DeadEnd {
+        /// The offending node.
+        node: Position,
+    },
+    /// The number of values does not match the number of terminal nodes, so
+    /// values would be looked up for the wrong nodes.
+    ValueCount {
+        /// Number of values stored.
+        values: usize,
+        /// Number of nodes marked terminal.
+        terminals: usize,
+    },
+}
+
+impl fmt::Display for InvariantError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InvariantError::UnsortedChildren { parent } => {
+                write!(f, "children of node {} are not sorted", parent.0)
+            }
+            InvariantError::Numbering { expected, found } => {
+                write!(f, "expected node {} but found node {}", expected.0, found.0)
+            }
+            InvariantError::NodeCount { labels, nodes } => {
+                write!(f, "{} labels stored for {} nodes", labels, nodes)
+            }
+            InvariantError::DeadEnd { node } => {
+                write!(f, "node {} has neither children nor a value", node.0)
+            }
+            InvariantError::ValueCount { values, terminals } => {
+                write!(
+                    f,
+                    "{} values stored for {} terminal nodes",
+                    values, terminals
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for InvariantError {}
+
+impl<Label: Ord, Value> Trie<Label, Value> {
+    /// Verify the trie's structural invariants: children are strictly sorted
+    /// by label, nodes are numbered in breadth-first order, every label
+    /// belongs to a node, every leaf holds a value, and there is one value per
+    /// terminal node.
+    ///
+    /// A trie made by [crate::map::TrieBuilder] always passes. This is
+    /// meant for testing and fuzzing code that constructs or transforms tries.
+    /// It is _O(n)_.
+    ///
+    /// Unlike a `debug_assert!`, it is available in release builds:
+    /// [Trie::read_from] runs it on every trie it reads to reject corrupt
+    /// data rather than panic on it later, and callers may do the same with
+    /// tries from other untrusted sources.
+    pub fn check(&self) -> Result<(), InvariantError> {
+        let mut expected = LoudsNodeNum(2);
+        let mut nodes = 0;
+        let mut queue = VecDeque::from([LoudsNodeNum(1)]);
+        while let Some(parent) = queue.pop_front() {
+            let children: Vec<_> = self.children_node_nums(parent).collect();
+            for child in &children {
+                if *child != expected {
+                    return Err(InvariantError::Numbering {
+                        expected,
+                        found: *child,
+                    });
+                }
+                if child.0 as usize - 2 >= self.labels.len() {
+                    return Err(InvariantError::NodeCount {
+                        labels: self.labels.len(),
+                        nodes: child.0 as usize - 1,
+                    });
+                }
+                expected.0 += 1;
+            }
+            if children
+                .windows(2)
+                .any(|pair| self.label(pair[0]).cmp(self.label(pair[1])).is_ge())
+            {
+                return Err(InvariantError::UnsortedChildren { parent });
+            }
+            if parent.0 > 1 && children.is_empty() && !self.is_terminal(parent) {
+                return Err(InvariantError::DeadEnd { node: parent });
+            }
+            nodes += children.len();
+            queue.extend(children);
+        }
+        if nodes != self.labels.len() {
+            return Err(InvariantError::NodeCount {
+                labels: self.labels.len(),
+                nodes,
+            });
+        }
+        let terminals = self.terminals.rank(self.terminals.len() - 1) as usize;
+        if terminals != self.values.len() {
+            return Err(InvariantError::ValueCount {
+                values: self.values.len(),
+                terminals,
+            });
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod check_tests {
+    use super::InvariantError;
+    use crate::map::{Trie, TrieBuilder};
+    use louds_rs::LoudsNodeNum;
+
+    fn build_trie() -> Trie<u8, u8> {
+        let mut builder = TrieBuilder::new();
+        builder.push("a", 0);
+        builder.push("app", 1);
+        builder.push("apple", 2);
+        builder.push("better", 3);
+        builder.push("application", 4);
+        builder.push("アップル🍎", 5);
+        builder.build()
+    }
+
+    #[test]
+    fn valid() {
+        assert_eq!(build_trie().check(), Ok(()));
+        assert_eq!(TrieBuilder::<u8, u8>::new().build().check(), Ok(()));
+    }
+
+    #[test]
+    fn unsorted_children() {
+        let mut trie = build_trie();
+        // Root's children are 'a', 'b', and 0xE3.
+        trie.labels.swap(0, 1);
+        assert_eq!(
+            trie.check(),
+            Err(InvariantError::UnsortedChildren {
+                parent: LoudsNodeNum(1)
+            })
+        );
+    }
+
+    #[test]
+    fn dead_end() {
+        let mut trie = Trie::<u8, u8>::from_iter([("a", 0), ("b", 1)]);
+        trie.terminals = fid_rs::Fid::from(&[false, true, false][..]);
+        trie.values.pop();
+        assert_eq!(
+            trie.check(),
+            Err(InvariantError::DeadEnd {
+                node: LoudsNodeNum(3)
+            })
+        );
+    }
+
+    #[test]
+    fn value_count() {
+        let mut trie = build_trie();
+        trie.values.push(6);
+        assert_eq!(
+            trie.check(),
+            Err(InvariantError::ValueCount {
+                values: 7,
+                terminals: 6
+            })
+        );
+        trie.values.truncate(5);
+        assert_eq!(
+            trie.check(),
+            Err(InvariantError::ValueCount {
+                values: 5,
+                terminals: 6
+            })
+        );
+    }
+
+    #[test]
+    fn node_count() {
+        let mut trie = build_trie();
+        trie.labels.push(b'z');
+        assert!(matches!(
+            trie.check(),
+            Err(InvariantError::NodeCount { .. })
+        ));
+    }
+}
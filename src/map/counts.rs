@@ -0,0 +1,206 @@
+use super::Trie;
+#[cfg(doc)]
+use crate::inc_search::IncSearch;
+use crate::inc_search::Position;
+#[cfg(feature = "counts")]
+use louds_rs::LoudsNodeNum;
+
+impl<Label: Ord, Value> Trie<Label, Value> {
+    /// Return the number of entries that start with `prefix`, i.e., how many
+    /// results [Trie::predictive_search] would return.
+    ///
+    /// With the "counts" crate feature this takes _O(m log k)_ time for a
+    /// prefix of length _m_; otherwise the matching subtree is walked.
+    ///
+    /// ```rust
+    /// use trie_rs::map::Trie;
+    ///
+    /// let trie = Trie::from_iter([("a", 0), ("app", 1), ("apple", 2), ("b", 3)]);
+    /// assert_eq!(trie.prefix_count("a"), 3);
+    /// assert_eq!(trie.prefix_count("app"), 2);
+    /// assert_eq!(trie.prefix_count(""), 4);
+    /// assert_eq!(trie.prefix_count("c"), 0);
+    /// ```
+    pub fn prefix_count(&self, prefix: impl AsRef<[Label]>) -> usize {
+        self.prefix_node(prefix)
+            .map_or(0, |node| self.subtree_count(node))
+    }
+
+    /// Return the number of entries in the subtree rooted at `node`,
+    /// including `node` itself, e.g., at a position of [IncSearch] or
+    /// [Trie::lcp_node].
+    ///
+    /// With the "counts" crate feature this takes _O(1)_ time; otherwise the
+    /// subtree is walked.
+    ///
+    /// # Panics
+    /// If `node` is not a node of this trie.
+    ///
+    /// ```rust
+    /// use trie_rs::map::Trie;
+    ///
+    /// let trie = Trie::from_iter([("a", 0), ("app", 1), ("apple", 2), ("b", 3)]);
+    /// let mut search = trie.inc_search();
+    /// search.query_until("ap").unwrap();
+    /// assert_eq!(trie.subtree_count(search.position()), 2);
+    /// assert_eq!(trie.subtree_count(trie.inc_search().position()), 4);
+    /// ```
+    #[cfg(feature = "counts")]
+    pub fn subtree_count(&self, node: Position) -> usize {
+        assert!(
+            node.0 >= 1 && node.0 <= self.last_node_num().0,
+            "node {:?} is not in this trie",
+            node
+        );
+        self.counts[node.0 as usize - 1] as usize
+    }
+
+    /// Return the number of entries in the subtree rooted at `node`,
+    /// including `node` itself, e.g., at a position of [IncSearch] or
+    /// [Trie::lcp_node].
+    ///
+    /// With the "counts" crate feature this takes _O(1)_ time; otherwise the
+    /// subtree is walked.
+    ///
+    /// # Panics
+    /// If `node` is not a node of this trie.
+    ///
+    /// ```rust
+    /// use trie_rs::map::Trie;
+    ///
+    /// let trie = Trie::from_iter([("a", 0), ("app", 1), ("apple", 2), ("b", 3)]);
+    /// let mut search = trie.inc_search();
+    /// search.query_until("ap").unwrap();
+    /// assert_eq!(trie.subtree_count(search.position()), 2);
+    /// assert_eq!(trie.subtree_count(trie.inc_search().position()), 4);
+    /// ```
+    #[cfg(not(feature = "counts"))]
+    pub fn subtree_count(&self, node: Position) -> usize {
+        assert!(
+            node.0 >= 1 && node.0 <= self.last_node_num().0,
+            "node {:?} is not in this trie",
+            node
+        );
+        let mut count = 0;
+        let mut stack = vec![node];
+        while let Some(node) = stack.pop() {
+            count += self.is_terminal(node) as usize;
+            stack.extend(self.children_node_nums(node));
+        }
+        count
+    }
+
+    /// Fill `counts` from `terminals`. Children are numbered after their
+    /// parent, so visiting nodes in reverse sums each subtree before its root.
+    ///
+    /// Panics if there are more than `u32::MAX` entries, which no count may
+    /// exceed.
+    #[cfg(feature = "counts")]
+    pub(crate) fn count_subtrees(&mut self) {
+        assert!(
+            u32::try_from(self.values.len()).is_ok(),
+            "the \"counts\" feature supports at most u32::MAX entries"
+        );
+        let nodes = self.labels.len() + 1;
+        let mut counts = vec![0u32; nodes];
+        for i in (1..nodes).rev() {
+            let node = LoudsNodeNum(i as u64 + 1);
+            counts[i] += self.is_terminal(node) as u32;
+            if let Some(parent) = self.parent(node) {
+                counts[parent.0 as usize - 1] += counts[i];
+            }
+        }
+        self.counts = counts;
+    }
+}
+
+#[cfg(test)]
+mod counts_tests {
+    use crate::map::{Trie, TrieBuilder};
+
+    fn build_trie() -> Trie<u8, u8> {
+        let mut builder = TrieBuilder::new();
+        builder.push("a", 0);
+        builder.push("app", 1);
+        builder.push("apple", 2);
+        builder.push("better", 3);
+        builder.push("application", 4);
+        builder.push("アップル🍎", 5);
+        builder.build()
+    }
+
+    mod prefix_count_tests {
+        macro_rules! parameterized_tests {
+            ($($name:ident: $value:expr,)*) => {
+            $(
+                #[test]
+                fn $name() {
+                    let (query, expected) = $value;
+                    let trie = super::build_trie();
+                    assert_eq!(trie.prefix_count(query), expected);
+                    assert_eq!(trie.prefix_count(query), trie.predictive_search::<Vec<u8>, _>(query).count());
+                }
+            )*
+            }
+        }
+
+        parameterized_tests! {
+            t1: ("", 6),
+            t2: ("a", 4),
+            t3: ("app", 3),
+            t4: ("appl", 2),
+            t5: ("apple", 1),
+            t6: ("b", 1),
+            t7: ("アップ", 1),
+            t8: ("c", 0),
+            t9: ("applesauce", 0),
+        }
+    }
+
+    #[test]
+    fn prefix_count_empty() {
+        let trie: Trie<u8, u8> = TrieBuilder::new().build();
+        assert_eq!(trie.prefix_count(""), 0);
+        assert_eq!(trie.prefix_count("a"), 0);
+    }
+
+    #[test]
+    fn subtree_count() {
+        let trie = build_trie();
+        for prefix in ["", "a", "ap", "app", "appl", "apple", "b", "アップ"] {
+            let mut search = trie.inc_search();
+            if !prefix.is_empty() {
+                search.query_until(prefix).unwrap();
+            }
+            assert_eq!(
+                trie.subtree_count(search.position()),
+                trie.predictive_search::<Vec<u8>, _>(prefix).count(),
+                "{:?}",
+                prefix
+            );
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "is not in this trie")]
+    fn subtree_count_out_of_range() {
+        let trie = build_trie();
+        trie.subtree_count(louds_rs::LoudsNodeNum(1000));
+    }
+
+    #[cfg(feature = "counts")]
+    #[test]
+    fn counts_match_walk() {
+        let trie = build_trie();
+        for (i, count) in trie.counts.iter().enumerate() {
+            let node = louds_rs::LoudsNodeNum(i as u64 + 1);
+            let mut walked = 0;
+            let mut stack = vec![node];
+            while let Some(node) = stack.pop() {
+                walked += trie.is_terminal(node) as u32;
+                stack.extend(trie.children_node_nums(node));
+            }
+            assert_eq!(*count, walked);
+        }
+    }
+}
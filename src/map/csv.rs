@@ -0,0 +1,162 @@
+//! CSV of `u8` tries with UTF-8 keys.
+use super::binary::invalid_data;
+use super::{Trie, TrieBuilder};
+use std::fmt::Display;
+use std::io::{self, Read, Write};
+use std::str::FromStr;
+
+impl<Value: Display> Trie<u8, Value> {
+    /// Write a `key,value` row for each entry to `w` in lexicographic order.
+    ///
+    /// Fields with a comma, quote, or line break are quoted as in RFC 4180.
+    /// Returns an error of kind [io::ErrorKind::InvalidData] if a key is not
+    /// UTF-8.
+    ///
+    /// ```rust
+    /// use trie_rs::map::Trie;
+    ///
+    /// let trie = Trie::from_iter([("a", 0), ("b,c", 1)]);
+    /// let mut csv = Vec::new();
+    /// trie.write_csv(&mut csv).unwrap();
+    /// assert_eq!(csv, b"a,0\n\"b,c\",1\n");
+    /// ```
+    pub fn write_csv(&self, w: &mut impl Write) -> io::Result<()> {
+        for entry in self.try_iter::<String, _>() {
+            let (key, value) = entry.map_err(|e| invalid_data(&e.to_string()))?;
+            write_field(w, &key)?;
+            w.write_all(b",")?;
+            write_field(w, &value.to_string())?;
+            w.write_all(b"\n")?;
+        }
+        Ok(())
+    }
+}
+
+impl<Value: FromStr> Trie<u8, Value>
+where
+    Value::Err: Display,
+{
+    /// Build a trie from `key,value` rows as written by [Trie::write_csv]. If
+    /// a key repeats, its last value wins.
+    ///
+    /// Returns an error of kind [io::ErrorKind::InvalidData] if a row does
+    /// not have two fields, a key is empty, or a value does not parse.
+    ///
+    /// ```rust
+    /// use trie_rs::map::Trie;
+    ///
+    /// let trie = Trie::<u8, u32>::read_csv(&mut &b"a,0\n\"b,c\",1\n"[..]).unwrap();
+    /// assert_eq!(trie.exact_match("b,c"), Some(&1));
+    /// ```
+    pub fn read_csv(r: &mut impl Read) -> io::Result<Self> {
+        let mut text = String::new();
+        r.read_to_string(&mut text)?;
+        let mut builder = TrieBuilder::new();
+        for (line, row) in parse(&text)?.into_iter().enumerate() {
+            match <[String; 2]>::try_from(row) {
+                Ok([key, value]) => {
+                    if key.is_empty() {
+                        return Err(invalid_data(&format!("row {}: empty key", line + 1)));
+                    }
+                    let value = value.parse().map_err(|e: Value::Err| {
+                        invalid_data(&format!("row {}: {}", line + 1, e))
+                    })?;
+                    builder.insert(key.into_bytes(), value);
+                }
+                Err(_) => {
+                    return Err(invalid_data(&format!(
+                        "row {}: expected two fields",
+                        line + 1
+                    )))
+                }
+            }
+        }
+        Ok(builder.build())
+    }
+}
+
+fn write_field(w: &mut impl Write, field: &str) -> io::Result<()> {
+    if field.contains([',', '"', '\n', '\r']) {
+        write!(w, "\"{}\"", field.replace('"', "\"\""))
+    } else {
+        w.write_all(field.as_bytes())
+    }
+}
+
+/// Split `text` into rows of fields. Quoted fields may hold commas, quotes
+/// doubled, and line breaks.
+fn parse(text: &str) -> io::Result<Vec<Vec<String>>> {
+    let mut rows = vec![];
+    let mut row = vec![];
+    let mut field = String::new();
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if field.is_empty() => loop {
+                match chars.next() {
+                    Some('"') if chars.peek() == Some(&'"') => {
+                        chars.next();
+                        field.push('"');
+                    }
+                    Some('"') => break,
+                    Some(c) => field.push(c),
+                    None => return Err(invalid_data("unterminated quote")),
+                }
+            },
+            ',' => row.push(std::mem::take(&mut field)),
+            '\r' if chars.peek() == Some(&'\n') => {}
+            '\n' => {
+                row.push(std::mem::take(&mut field));
+                rows.push(std::mem::take(&mut row));
+            }
+            c => field.push(c),
+        }
+    }
+    if !field.is_empty() || !row.is_empty() {
+        row.push(field);
+        rows.push(row);
+    }
+    Ok(rows)
+}
+
+#[cfg(test)]
+mod csv_tests {
+    use crate::map::Trie;
+    use std::io::ErrorKind;
+
+    #[test]
+    fn round_trip() {
+        let trie = Trie::<u8, String>::from_iter([
+            ("a", "plain".to_string()),
+            ("b,c", "comma".into()),
+            ("say \"hi\"", "quote".into()),
+            ("two\nlines", "line, break\r\n".into()),
+            ("アップル🍎", "".into()),
+        ]);
+        let mut csv = Vec::new();
+        trie.write_csv(&mut csv).unwrap();
+        assert_eq!(Trie::read_csv(&mut csv.as_slice()).unwrap(), trie);
+    }
+
+    #[test]
+    fn crlf_and_no_trailing_newline() {
+        let trie = Trie::<u8, u8>::read_csv(&mut &b"a,0\r\napp,1"[..]).unwrap();
+        assert_eq!(trie, Trie::from_iter([("a", 0), ("app", 1)]));
+    }
+
+    #[test]
+    fn invalid_data() {
+        let read = |csv: &[u8]| Trie::<u8, u8>::read_csv(&mut &csv[..]).unwrap_err().kind();
+        assert_eq!(read(b"a,0,1\n"), ErrorKind::InvalidData);
+        assert_eq!(read(b"a\n"), ErrorKind::InvalidData);
+        assert_eq!(read(b"a,zero\n"), ErrorKind::InvalidData);
+        assert_eq!(read(b"\"a,0\n"), ErrorKind::InvalidData);
+        assert_eq!(read(b",1\n"), ErrorKind::InvalidData);
+        assert_eq!(read(b"a,0\n\"\",1\n"), ErrorKind::InvalidData);
+        let trie = Trie::from_iter([(&b"o\xff"[..], 0)]);
+        assert_eq!(
+            trie.write_csv(&mut Vec::new()).unwrap_err().kind(),
+            ErrorKind::InvalidData
+        );
+    }
+}
@@ -0,0 +1,340 @@
+//! Minimization of a trie into a directed acyclic word graph.
+//!
+//! The builder's tree is taken apart depth-first. Each node is made once all
+//! of its children are, so its edges point to finished nodes, and it is
+//! replaced by an existing node with the same value and edges if there is one.
+use super::Dawg;
+use crate::internal_data_structure::naive_trie::NaiveTrie;
+use crate::try_collect::{TryCollect, TryFromIterator};
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+
+/// A node of the builder's tree whose children are being made.
+struct Frame<Label, Value> {
+    label: Option<Label>,
+    value: Option<Value>,
+    children: std::vec::IntoIter<NaiveTrie<Label, Value>>,
+    edges: Vec<(Label, u32)>,
+}
+
+impl<Label: Ord, Value> Frame<Label, Value> {
+    fn new(mut node: NaiveTrie<Label, Value>) -> Self {
+        let children: Vec<_> = node.drain_children().collect();
+        let (label, value) = match node {
+            NaiveTrie::IntermOrLeaf(node) => (Some(node.label), node.value),
+            _ => (None, None),
+        };
+        Self {
+            label,
+            value,
+            children: children.into_iter(),
+            edges: Vec::new(),
+        }
+    }
+}
+
+impl<Label: Ord + Hash, Value: Eq + Hash> Dawg<Label, Value> {
+    /// Merge the equal subtrees of `naive_trie`.
+    pub(super) fn minimize(naive_trie: NaiveTrie<Label, Value>) -> Self {
+        let mut dawg = Dawg {
+            offsets: vec![0],
+            edges: Vec::new(),
+            values: Vec::new(),
+            root: 0,
+            len: 0,
+        };
+        // Nodes by the hash of their value and edges.
+        let mut register: HashMap<u64, Vec<u32>> = HashMap::new();
+        let mut stack = vec![Frame::new(naive_trie)];
+        while let Some(frame) = stack.last_mut() {
+            if let Some(child) = frame.children.next() {
+                stack.push(Frame::new(child));
+                continue;
+            }
+            let frame = stack.pop().expect("stack is not empty");
+            dawg.len += frame.value.is_some() as usize;
+            let node = dawg.register(&mut register, frame.value, frame.edges);
+            match stack.last_mut() {
+                Some(parent) => parent
+                    .edges
+                    .push((frame.label.expect("a child has a label"), node)),
+                None => dawg.root = node,
+            }
+        }
+        dawg
+    }
+
+    /// Return the node with `value` and `edges`, making it if there is none.
+    fn register(
+        &mut self,
+        register: &mut HashMap<u64, Vec<u32>>,
+        value: Option<Value>,
+        edges: Vec<(Label, u32)>,
+    ) -> u32 {
+        let mut hasher = DefaultHasher::new();
+        value.hash(&mut hasher);
+        edges.hash(&mut hasher);
+        let nodes = register.entry(hasher.finish()).or_default();
+        if let Some(&node) = nodes
+            .iter()
+            .find(|&&node| self.values[node as usize] == value && self.edges_of(node) == edges)
+        {
+            return node;
+        }
+        let node = u32::try_from(self.values.len()).expect("a Dawg holds at most u32::MAX nodes");
+        self.edges.extend(edges);
+        self.offsets
+            .push(u32::try_from(self.edges.len()).expect("a Dawg holds at most u32::MAX edges"));
+        self.values.push(value);
+        nodes.push(node);
+        node
+    }
+}
+
+impl<Label: Ord, Value> Dawg<Label, Value> {
+    /// Return the edges out of `node` sorted by label.
+    fn edges_of(&self, node: u32) -> &[(Label, u32)] {
+        let node = node as usize;
+        &self.edges[self.offsets[node] as usize..self.offsets[node + 1] as usize]
+    }
+
+    /// Return the node reached by following `query` from the root.
+    fn node(&self, query: impl AsRef<[Label]>) -> Option<u32> {
+        let mut node = self.root;
+        for label in query.as_ref() {
+            let edges = self.edges_of(node);
+            let i = edges.binary_search_by(|(l, _)| l.cmp(label)).ok()?;
+            node = edges[i].1;
+        }
+        Some(node)
+    }
+
+    /// Return the number of entries.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Return true if there are no entries.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Return the number of nodes, not counting the root, which is at most
+    /// [Stats::nodes](super::Stats::nodes) of the equivalent [super::Trie].
+    pub fn node_count(&self) -> usize {
+        self.values.len() - 1
+    }
+
+    /// Return `Some(&Value)` if query is an exact match.
+    pub fn exact_match(&self, query: impl AsRef<[Label]>) -> Option<&Value> {
+        self.values[self.node(query)? as usize].as_ref()
+    }
+
+    /// Return true if `query` is a prefix.
+    ///
+    /// Note: A prefix may be an exact match or not, and an exact match may be a
+    /// prefix or not.
+    pub fn is_prefix(&self, query: impl AsRef<[Label]>) -> bool {
+        self.node(query)
+            .map_or(false, |node| !self.edges_of(node).is_empty())
+    }
+
+    /// Return all entries and their values that match `query` in
+    /// lexicographic order.
+    ///
+    /// ```rust
+    /// use trie_rs::map::TrieBuilder;
+    ///
+    /// let mut builder = TrieBuilder::new();
+    /// builder.push("tap", 0);
+    /// builder.push("taps", 1);
+    /// builder.push("map", 0);
+    /// builder.push("maps", 1);
+    /// let dawg = builder.build_minimized();
+    /// let results: Vec<(String, &u8)> = dawg.predictive_search("ma").collect();
+    /// assert_eq!(results, [("map".to_string(), &0), ("maps".to_string(), &1)]);
+    /// ```
+    pub fn predictive_search<C, M>(
+        &self,
+        query: impl AsRef<[Label]>,
+    ) -> std::vec::IntoIter<(C, &Value)>
+    where
+        C: TryFromIterator<Label, M>,
+        Label: Clone,
+    {
+        let query = query.as_ref();
+        let mut results = vec![];
+        let start = match self.node(query) {
+            Some(node) => node,
+            None => return results.into_iter(),
+        };
+        let mut key = query.to_vec();
+        let mut stack = vec![(key.len(), None, start)];
+        while let Some((depth, label, node)) = stack.pop() {
+            key.truncate(depth);
+            if let Some(label) = label {
+                key.push(Label::clone(label));
+            }
+            if let Some(value) = &self.values[node as usize] {
+                let entry = key
+                    .iter()
+                    .cloned()
+                    .try_collect()
+                    .expect("Could not collect");
+                results.push((entry, value));
+            }
+            for (label, child) in self.edges_of(node).iter().rev() {
+                stack.push((key.len(), Some(label), *child));
+            }
+        }
+        results.into_iter()
+    }
+
+    /// Return the common prefixes of `query` and their values.
+    pub fn common_prefix_search<C, M>(
+        &self,
+        query: impl AsRef<[Label]>,
+    ) -> std::vec::IntoIter<(C, &Value)>
+    where
+        C: TryFromIterator<Label, M>,
+        Label: Clone,
+    {
+        let query = query.as_ref();
+        let mut results = vec![];
+        let mut node = self.root;
+        for (i, label) in query.iter().enumerate() {
+            let edges = self.edges_of(node);
+            node = match edges.binary_search_by(|(l, _)| l.cmp(label)) {
+                Ok(j) => edges[j].1,
+                Err(_) => break,
+            };
+            if let Some(value) = &self.values[node as usize] {
+                let key = query[..=i]
+                    .iter()
+                    .cloned()
+                    .try_collect()
+                    .expect("Could not collect");
+                results.push((key, value));
+            }
+        }
+        results.into_iter()
+    }
+
+    /// Return every entry and its value in lexicographic order.
+    pub fn iter<C, M>(&self) -> std::vec::IntoIter<(C, &Value)>
+    where
+        C: TryFromIterator<Label, M>,
+        Label: Clone,
+    {
+        self.predictive_search([])
+    }
+}
+
+#[cfg(test)]
+mod dawg_tests {
+    use crate::map::{Dawg, Trie, TrieBuilder};
+    use proptest::prelude::*;
+
+    fn build(entries: &[(&str, u8)]) -> (Trie<u8, u8>, Dawg<u8, u8>) {
+        let mut builder = TrieBuilder::new();
+        for (key, value) in entries {
+            builder.push(key, *value);
+        }
+        (builder.clone().build(), builder.build_minimized())
+    }
+
+    fn assert_same(trie: &Trie<u8, u8>, dawg: &Dawg<u8, u8>, query: &[u8]) {
+        assert_eq!(dawg.exact_match(query), trie.exact_match(query));
+        assert_eq!(dawg.is_prefix(query), trie.is_prefix(query));
+        let results: Vec<(Vec<u8>, &u8)> = dawg.predictive_search(query).collect();
+        let expected: Vec<(Vec<u8>, &u8)> = trie.predictive_search(query).collect();
+        assert_eq!(results, expected);
+        let results: Vec<(Vec<u8>, &u8)> = dawg.common_prefix_search(query).collect();
+        let expected: Vec<(Vec<u8>, &u8)> = trie.common_prefix_search(query).collect();
+        assert_eq!(results, expected);
+    }
+
+    mod queries_tests {
+        macro_rules! parameterized_tests {
+            ($($name:ident: $value:expr,)*) => {
+            $(
+                #[test]
+                fn $name() {
+                    let query = $value;
+                    let (trie, dawg) = super::build(&[
+                        ("a", 0),
+                        ("app", 1),
+                        ("apple", 2),
+                        ("better", 3),
+                        ("application", 4),
+                        ("アップル🍎", 5),
+                        ("maple", 2),
+                        ("ample", 2),
+                    ]);
+                    super::assert_same(&trie, &dawg, query.as_bytes());
+                }
+            )*
+            }
+        }
+
+        parameterized_tests! {
+            t1: "",
+            t2: "a",
+            t3: "app",
+            t4: "appl",
+            t5: "apple",
+            t6: "applesauce",
+            t7: "b",
+            t8: "アップ",
+            t9: "ma",
+            t10: "z",
+        }
+    }
+
+    #[test]
+    fn merges_equal_values_only() {
+        // "ple" with value 2 is shared by "apple", "maple", and "ample".
+        let (trie, dawg) = build(&[("apple", 2), ("maple", 2), ("ample", 2)]);
+        assert_eq!(trie.stats().nodes, 14);
+        assert_eq!(dawg.node_count(), 6);
+        assert_eq!(dawg.len(), 3);
+        // Different values keep the suffixes apart.
+        let (_, dawg) = build(&[("apple", 0), ("maple", 1), ("ample", 2)]);
+        assert_eq!(dawg.node_count(), 14);
+    }
+
+    #[test]
+    fn empty() {
+        let dawg = TrieBuilder::<u8, u8>::new().build_minimized();
+        assert!(dawg.is_empty());
+        assert_eq!(dawg.node_count(), 0);
+        assert_eq!(dawg.exact_match("a"), None);
+        assert_eq!(dawg.iter::<Vec<u8>, _>().next(), None);
+    }
+
+    #[test]
+    fn long_key() {
+        let key = "a".repeat(10_000);
+        let mut builder = TrieBuilder::new();
+        builder.push(&key, 0);
+        builder.push("b", 0);
+        let dawg = builder.build_minimized();
+        assert_eq!(dawg.exact_match(&key), Some(&0));
+        assert_eq!(dawg.node_count(), 10_000);
+    }
+
+    proptest! {
+        #[test]
+        fn matches_trie(
+            entries in prop::collection::btree_map("[abc]{1,5}", 0u8..3, 0..40),
+            query in "[abc]{0,3}",
+        ) {
+            let entries: Vec<(&str, u8)> = entries.iter().map(|(k, v)| (k.as_str(), *v)).collect();
+            let (trie, dawg) = build(&entries);
+            prop_assert_eq!(dawg.len(), entries.len());
+            prop_assert!(dawg.node_count() <= trie.stats().nodes);
+            assert_same(&trie, &dawg, query.as_bytes());
+        }
+    }
+}
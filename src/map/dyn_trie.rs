@@ -0,0 +1,272 @@
+//! A mutable trie for when entries change after it is built.
+use super::{DuplicatePolicy, DynTrie, Trie, TrieBuilder};
+use crate::internal_data_structure::naive_trie::NaiveTrie;
+use crate::iter::DynSearchIter;
+use crate::try_collect::{TryCollect, TryFromIterator};
+
+impl<Label: Ord, Value> Default for DynTrie<Label, Value> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<Label: Ord, Value> DynTrie<Label, Value> {
+    /// Return an empty [DynTrie].
+    pub fn new() -> Self {
+        Self {
+            naive_trie: NaiveTrie::make_root(),
+            len: 0,
+        }
+    }
+
+    /// Add a cloneable entry and value. Return the entry's previous value if
+    /// it was already present.
+    ///
+    /// # Panics
+    /// If `entry` is empty, as with [TrieBuilder::push].
+    pub fn push<Arr: AsRef<[Label]>>(&mut self, entry: Arr, value: Value) -> Option<Value>
+    where
+        Label: Clone,
+    {
+        self.insert(entry.as_ref().iter().cloned(), value)
+    }
+
+    /// Add an entry and value. Return the entry's previous value if it was
+    /// already present.
+    ///
+    /// # Panics
+    /// If `entry` is empty, as with [TrieBuilder::push].
+    pub fn insert<Arr: IntoIterator<Item = Label>>(
+        &mut self,
+        entry: Arr,
+        value: Value,
+    ) -> Option<Value> {
+        let old = self.naive_trie.push(entry.into_iter(), value);
+        if old.is_none() {
+            self.len += 1;
+        }
+        old
+    }
+
+    /// Remove an entry and return its value if it was present.
+    pub fn remove(&mut self, entry: impl AsRef<[Label]>) -> Option<Value> {
+        let old = self.naive_trie.remove(entry.as_ref());
+        if old.is_some() {
+            self.len -= 1;
+        }
+        old
+    }
+
+    /// Return the number of entries.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Return true if there are no entries.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Return `Some(&Value)` if query is an exact match.
+    pub fn exact_match(&self, query: impl AsRef<[Label]>) -> Option<&Value> {
+        match self.naive_trie.get(query.as_ref())? {
+            NaiveTrie::IntermOrLeaf(node) => node.value.as_ref(),
+            _ => None,
+        }
+    }
+
+    /// Return `Some(&mut Value)` if query is an exact match.
+    pub fn exact_match_mut(&mut self, query: impl AsRef<[Label]>) -> Option<&mut Value> {
+        match self.naive_trie.get_mut(query.as_ref())? {
+            NaiveTrie::IntermOrLeaf(node) => node.value.as_mut(),
+            _ => None,
+        }
+    }
+
+    /// Return true if `query` is a prefix.
+    ///
+    /// Note: A prefix may be an exact match or not, and an exact match may be a
+    /// prefix or not.
+    pub fn is_prefix(&self, query: impl AsRef<[Label]>) -> bool {
+        self.naive_trie
+            .get(query.as_ref())
+            .map_or(false, |node| !node.children().is_empty())
+    }
+
+    /// Return all entries and their values that match `query` in
+    /// lexicographic order.
+    pub fn predictive_search<C, M>(
+        &self,
+        query: impl AsRef<[Label]>,
+    ) -> DynSearchIter<'_, Label, Value, C, M>
+    where
+        C: TryFromIterator<Label, M>,
+        Label: Clone,
+    {
+        DynSearchIter::new(&self.naive_trie, query.as_ref())
+    }
+
+    /// Return the common prefixes of `query` and their values.
+    pub fn common_prefix_search<C, M>(
+        &self,
+        query: impl AsRef<[Label]>,
+    ) -> std::vec::IntoIter<(C, &Value)>
+    where
+        C: TryFromIterator<Label, M>,
+        Label: Clone,
+    {
+        let query = query.as_ref();
+        let mut results = vec![];
+        let mut trie = &self.naive_trie;
+        for (i, chr) in query.iter().enumerate() {
+            trie = match trie.get(std::slice::from_ref(chr)) {
+                Some(child) => child,
+                None => break,
+            };
+            if let Some(value) = trie.value() {
+                let key = query[..=i]
+                    .iter()
+                    .cloned()
+                    .try_collect()
+                    .expect("Could not collect");
+                results.push((key, value));
+            }
+        }
+        results.into_iter()
+    }
+
+    /// Return every entry and its value in lexicographic order.
+    pub fn iter<C, M>(&self) -> DynSearchIter<'_, Label, Value, C, M>
+    where
+        C: TryFromIterator<Label, M>,
+        Label: Clone,
+    {
+        self.predictive_search([])
+    }
+
+    /// Convert into the compact, immutable [Trie].
+    pub fn freeze(self) -> Trie<Label, Value> {
+        TrieBuilder {
+            naive_trie: self.naive_trie,
+            capacity: self.len,
+            insertion_order: None,
+            on_duplicate: DuplicatePolicy::Overwrite,
+            duplicates: 0,
+        }
+        .build()
+    }
+}
+
+impl<Label: Ord, Value, C> FromIterator<(C, Value)> for DynTrie<Label, Value>
+where
+    C: AsRef<[Label]>,
+    Label: Clone,
+{
+    fn from_iter<T>(iter: T) -> Self
+    where
+        Self: Sized,
+        T: IntoIterator<Item = (C, Value)>,
+    {
+        let mut trie = DynTrie::new();
+        for (k, v) in iter {
+            trie.push(k, v);
+        }
+        trie
+    }
+}
+
+#[cfg(test)]
+mod dyn_trie_tests {
+    use crate::map::{DynTrie, Trie};
+
+    fn entries() -> [(&'static str, u8); 6] {
+        [
+            ("a", 0),
+            ("app", 1),
+            ("apple", 2),
+            ("better", 3),
+            ("application", 4),
+            ("アップル🍎", 5),
+        ]
+    }
+
+    #[test]
+    #[should_panic(expected = "An empty entry cannot be pushed")]
+    fn push_empty_entry() {
+        let mut trie = DynTrie::new();
+        trie.push("", 0);
+    }
+
+    #[test]
+    fn insert_and_remove() {
+        let mut trie = DynTrie::new();
+        assert!(trie.is_empty());
+        assert_eq!(trie.push("app", 1), None);
+        assert_eq!(trie.push("apple", 2), None);
+        assert_eq!(trie.push("app", 3), Some(1));
+        assert_eq!(trie.len(), 2);
+        assert_eq!(trie.exact_match("app"), Some(&3));
+        assert_eq!(trie.remove("ap"), None);
+        assert_eq!(trie.remove("apple"), Some(2));
+        assert_eq!(trie.remove("apple"), None);
+        assert_eq!(trie.len(), 1);
+        // Removing "apple" prunes its dangling branch.
+        assert!(!trie.is_prefix("app"));
+        assert_eq!(trie.remove("app"), Some(3));
+        assert!(trie.is_empty());
+        assert!(!trie.is_prefix(""));
+    }
+
+    #[test]
+    fn remove_keeps_shared_branches() {
+        let mut trie = DynTrie::from_iter(entries());
+        assert_eq!(trie.remove("application"), Some(4));
+        assert_eq!(trie.remove("app"), Some(1));
+        assert!(trie.is_prefix("app"));
+        let results: Vec<(String, &u8)> = trie.iter().collect();
+        assert_eq!(
+            results,
+            [
+                ("a".to_string(), &0),
+                ("apple".to_string(), &2),
+                ("better".to_string(), &3),
+                ("アップル🍎".to_string(), &5),
+            ]
+        );
+    }
+
+    #[test]
+    fn exact_match_mut() {
+        let mut trie = DynTrie::from_iter(entries());
+        *trie.exact_match_mut("apple").unwrap() = 9;
+        assert_eq!(trie.exact_match("apple"), Some(&9));
+        assert_eq!(trie.exact_match_mut("appl"), None);
+    }
+
+    #[test]
+    fn searches_match_trie() {
+        let dyn_trie = DynTrie::from_iter(entries());
+        let trie = Trie::from_iter(entries());
+        for query in ["", "a", "app", "appl", "b", "アップル🍎", "z"] {
+            let expected: Vec<(String, &u8)> = trie.predictive_search(query).collect();
+            let results: Vec<(String, &u8)> = dyn_trie.predictive_search(query).collect();
+            assert_eq!(results, expected);
+            let expected: Vec<(String, &u8)> = trie.common_prefix_search(query).collect();
+            let results: Vec<(String, &u8)> = dyn_trie.common_prefix_search(query).collect();
+            assert_eq!(results, expected);
+            assert_eq!(dyn_trie.is_prefix(query), trie.is_prefix(query));
+            assert_eq!(dyn_trie.exact_match(query), trie.exact_match(query));
+        }
+    }
+
+    #[test]
+    fn freeze() {
+        let mut dyn_trie = DynTrie::from_iter(entries());
+        dyn_trie.remove("better");
+        dyn_trie.push("best", 6);
+        let mut entries = entries().to_vec();
+        entries.retain(|(k, _)| *k != "better");
+        entries.push(("best", 6));
+        assert_eq!(dyn_trie.freeze(), Trie::from_iter(entries));
+    }
+}
@@ -0,0 +1,436 @@
+//! Approximate search by edit distance.
+//!
+//! All searches here walk the trie once while maintaining a row of the
+//! Levenshtein dynamic programming table per depth, so each node costs
+//! _O(m)_ for a query of length _m_. Subtrees whose row minimum already
+//! exceeds the distance of interest are pruned.
+use super::Trie;
+use crate::iter::PostfixIter;
+use crate::try_collect::{Collect, TryCollect, TryFromIterator};
+use louds_rs::LoudsNodeNum;
+use std::collections::BinaryHeap;
+
+impl<Label: Ord, Value> Trie<Label, Value> {
+    /// Walk the nodes in lexicographic order with the Levenshtein row of their
+    /// key against `query`; `row[query.len()]` is the distance between the two.
+    ///
+    /// `visit` returns the largest distance still of interest below that node
+    /// or `None` to skip its subtree entirely.
+    pub(crate) fn levenshtein_walk(
+        &self,
+        query: &[Label],
+        mut visit: impl FnMut(LoudsNodeNum, &[usize]) -> Option<usize>,
+    ) {
+        let root_row: Vec<usize> = (0..=query.len()).collect();
+        let mut rows = vec![root_row];
+        let mut stack = vec![(LoudsNodeNum(1), 0)];
+        while let Some((node_num, depth)) = stack.pop() {
+            if depth > 0 {
+                let label = self.label(node_num);
+                if rows.len() == depth {
+                    rows.push(vec![0; query.len() + 1]);
+                }
+                let (parents, children) = rows.split_at_mut(depth);
+                let prev = &parents[depth - 1];
+                let row = &mut children[0];
+                row[0] = prev[0] + 1;
+                for j in 1..=query.len() {
+                    let substitution = prev[j - 1] + usize::from(query[j - 1] != *label);
+                    row[j] = substitution.min(prev[j] + 1).min(row[j - 1] + 1);
+                }
+            }
+            let row = &rows[depth];
+            let limit = match visit(node_num, row) {
+                Some(limit) => limit,
+                None => continue,
+            };
+            if row.iter().min().map_or(false, |min| *min <= limit) {
+                let n = stack.len();
+                stack.extend(
+                    self.children_node_nums(node_num)
+                        .map(|child| (child, depth + 1)),
+                );
+                stack[n..].reverse();
+            }
+        }
+    }
+
+    /// Return the entry closest to `query` by edit (Levenshtein) distance
+    /// along with that distance. Ties are broken lexicographically.
+    ///
+    /// The search tightens its bound as closer entries are found, so it
+    /// visits far fewer nodes than enumerating every fuzzy match.
+    ///
+    /// ```rust
+    /// use trie_rs::map::Trie;
+    ///
+    /// let trie = Trie::from_iter([("apple", 0), ("apply", 1), ("maple", 2)]);
+    /// assert_eq!(trie.nearest("appel"), Some(("apple".to_string(), &0, 2)));
+    /// ```
+    pub fn nearest<C, M>(&self, query: impl AsRef<[Label]>) -> Option<(C, &Value, usize)>
+    where
+        C: TryFromIterator<Label, M>,
+        Label: Clone,
+    {
+        let query = query.as_ref();
+        let mut best: Option<(LoudsNodeNum, usize)> = None;
+        self.levenshtein_walk(query, |node_num, row| {
+            let distance = row[query.len()];
+            if self.is_terminal(node_num) && best.map_or(true, |(_, d)| distance < d) {
+                best = Some((node_num, distance));
+            }
+            // Only strictly closer entries can replace the best.
+            match best {
+                Some((_, d)) => d.checked_sub(1),
+                None => Some(usize::MAX),
+            }
+        });
+        best.and_then(|(node_num, distance)| {
+            self.value(node_num)
+                .map(|value| (self.node_key(node_num), value, distance))
+        })
+    }
+
+    /// Return the `k` entries closest to `query` by edit distance sorted by
+    /// distance then lexicographically.
+    ///
+    /// ```rust
+    /// use trie_rs::map::Trie;
+    ///
+    /// let trie = Trie::from_iter([("apple", 0), ("apply", 1), ("maple", 2), ("zebra", 3)]);
+    /// let results: Vec<(String, &u8, usize)> = trie.knn("appel", 2);
+    /// assert_eq!(results, [("apple".to_string(), &0, 2), ("apply".to_string(), &1, 2)]);
+    /// ```
+    pub fn knn<C, M>(&self, query: impl AsRef<[Label]>, k: usize) -> Vec<(C, &Value, usize)>
+    where
+        C: TryFromIterator<Label, M>,
+        Label: Clone,
+    {
+        let query = query.as_ref();
+        if k == 0 {
+            return vec![];
+        }
+        // Max-heap of (distance, visit order, node). Nodes are visited in
+        // lexicographic order so the visit order breaks ties.
+        let mut heap: BinaryHeap<(usize, usize, u64)> = BinaryHeap::with_capacity(k + 1);
+        let mut order = 0;
+        self.levenshtein_walk(query, |node_num, row| {
+            let distance = row[query.len()];
+            if self.is_terminal(node_num) {
+                if heap.len() < k {
+                    heap.push((distance, order, node_num.0));
+                } else if heap.peek().map_or(false, |worst| distance < worst.0) {
+                    heap.pop();
+                    heap.push((distance, order, node_num.0));
+                }
+                order += 1;
+            }
+            match heap.peek() {
+                Some((worst, _, _)) if heap.len() == k => worst.checked_sub(1),
+                _ => Some(usize::MAX),
+            }
+        });
+        heap.into_sorted_vec()
+            .into_iter()
+            .filter_map(|(distance, _, node_num)| {
+                let node_num = LoudsNodeNum(node_num);
+                self.value(node_num)
+                    .map(|value| (self.node_key(node_num), value, distance))
+            })
+            .collect()
+    }
+
+    /// Return up to `limit` entries that start with a prefix within
+    /// `max_distance` edits of `query` in lexicographic order.
+    ///
+    /// Each entry is returned once even when several of its prefixes are close
+    /// enough to `query`.
+    ///
+    /// ```rust
+    /// use trie_rs::map::Trie;
+    ///
+    /// let trie = Trie::from_iter([("apple", 0), ("application", 1), ("banana", 2)]);
+    /// let results: Vec<(String, &u8)> = trie.fuzzy_predictive_search("aplp", 1, 10);
+    /// assert_eq!(results, [("apple".to_string(), &0), ("application".to_string(), &1)]);
+    /// ```
+    pub fn fuzzy_predictive_search<C, M>(
+        &self,
+        query: impl AsRef<[Label]>,
+        max_distance: usize,
+        limit: usize,
+    ) -> Vec<(C, &Value)>
+    where
+        C: TryFromIterator<Label, M>,
+        Label: Clone,
+    {
+        let query = query.as_ref();
+        // A matching node's subtree holds every completion below it, so the
+        // walk stops there and no completion is found twice. Every subtree
+        // holds at least one entry.
+        let mut prefixes = vec![];
+        self.levenshtein_walk(query, |node_num, row| {
+            if prefixes.len() >= limit {
+                None
+            } else if row[query.len()] <= max_distance {
+                prefixes.push(node_num);
+                None
+            } else {
+                Some(max_distance)
+            }
+        });
+        let mut results = vec![];
+        for node_num in prefixes {
+            let prefix: Vec<Label> = self.node_key(node_num);
+            let value = self.value(node_num).map(|value| (vec![], value));
+            let postfixes =
+                PostfixIter::<'_, Label, Value, Vec<Label>, Collect>::new(self, node_num);
+            for (postfix, value) in value.into_iter().chain(postfixes) {
+                if results.len() >= limit {
+                    return results;
+                }
+                let key = prefix
+                    .iter()
+                    .cloned()
+                    .chain(postfix)
+                    .try_collect()
+                    .expect("Could not collect");
+                results.push((key, value));
+            }
+        }
+        results
+    }
+
+    /// Return up to `limit` entries within `max_distance` edits of `query`
+    /// ranked for a "did you mean" suggestion, treating values as frequencies.
+    ///
+    /// The score is `distance - ln(frequency)` and lower is better, so a
+    /// common word one edit away may outrank a rare one that matches exactly.
+    /// Ties keep lexicographic order. Use [Trie::suggest_by] for a different
+    /// score.
+    ///
+    /// ```rust
+    /// use trie_rs::map::Trie;
+    ///
+    /// let trie = Trie::from_iter([("their", 500u32), ("there", 1000), ("thew", 1)]);
+    /// let results: Vec<(String, &u32)> = trie.suggest("thew", 2, 2);
+    /// assert_eq!(results, [("there".to_string(), &1000), ("their".to_string(), &500)]);
+    /// ```
+    pub fn suggest<C, M>(
+        &self,
+        query: impl AsRef<[Label]>,
+        max_distance: usize,
+        limit: usize,
+    ) -> Vec<(C, &Value)>
+    where
+        C: TryFromIterator<Label, M>,
+        Label: Clone,
+        Value: Copy + Into<f64>,
+    {
+        self.suggest_by(query, max_distance, limit, |distance, frequency| {
+            distance as f64 - (*frequency).into().ln()
+        })
+    }
+
+    /// Return up to `limit` entries within `max_distance` edits of `query`
+    /// sorted by `score(distance, value)` in ascending order. Ties keep
+    /// lexicographic order.
+    ///
+    /// ```rust
+    /// use trie_rs::map::Trie;
+    ///
+    /// let trie = Trie::from_iter([("apple", 3), ("apply", 9), ("maple", 1)]);
+    /// // Prefer closer entries, then higher values.
+    /// let results: Vec<(String, &i32)> =
+    ///     trie.suggest_by("appl", 2, 10, |d, v| d as f64 * 100.0 - *v as f64);
+    /// assert_eq!(results, [("apply".to_string(), &9), ("apple".to_string(), &3)]);
+    /// ```
+    pub fn suggest_by<C, M>(
+        &self,
+        query: impl AsRef<[Label]>,
+        max_distance: usize,
+        limit: usize,
+        mut score: impl FnMut(usize, &Value) -> f64,
+    ) -> Vec<(C, &Value)>
+    where
+        C: TryFromIterator<Label, M>,
+        Label: Clone,
+    {
+        let query = query.as_ref();
+        let mut candidates = vec![];
+        self.levenshtein_walk(query, |node_num, row| {
+            let distance = row[query.len()];
+            if distance <= max_distance {
+                if let Some(value) = self.value(node_num) {
+                    candidates.push((score(distance, value), node_num));
+                }
+            }
+            Some(max_distance)
+        });
+        // A stable sort keeps ties in lexicographic order.
+        candidates.sort_by(|a, b| a.0.total_cmp(&b.0));
+        candidates
+            .into_iter()
+            .take(limit)
+            .filter_map(|(_, node_num)| {
+                self.value(node_num)
+                    .map(|value| (self.node_key(node_num), value))
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod fuzzy_tests {
+    use crate::map::{Trie, TrieBuilder};
+
+    fn build_trie() -> Trie<u8, u8> {
+        let mut builder = TrieBuilder::new();
+        builder.push("a", 0);
+        builder.push("app", 1);
+        builder.push("apple", 2);
+        builder.push("better", 3);
+        builder.push("application", 4);
+        builder.push("アップル🍎", 5);
+        builder.build()
+    }
+
+    mod nearest_tests {
+        macro_rules! parameterized_tests {
+            ($($name:ident: $value:expr,)*) => {
+            $(
+                #[test]
+                fn $name() {
+                    let (query, expected) = $value;
+                    let trie = super::build_trie();
+                    let result: Option<(String, &u8, usize)> = trie.nearest(query);
+                    let expected = expected.map(|(k, v, d): (&str, u8, usize)| (k.to_string(), v, d));
+                    assert_eq!(result.map(|(k, v, d)| (k, *v, d)), expected);
+                }
+            )*
+            }
+        }
+
+        parameterized_tests! {
+            t1: ("apple", Some(("apple", 2, 0))),
+            t2: ("appel", Some(("app", 1, 2))),
+            t3: ("aple", Some(("apple", 2, 1))),
+            t4: ("bettor", Some(("better", 3, 1))),
+            t5: ("", Some(("a", 0, 1))),
+            t6: ("ab", Some(("a", 0, 1))),
+            t7: ("applicant", Some(("application", 4, 4))),
+            // "a" and "app" are both 1 away; "a" comes first.
+            t8: ("ap", Some(("a", 0, 1))),
+        }
+    }
+
+    mod knn_tests {
+        macro_rules! parameterized_tests {
+            ($($name:ident: $value:expr,)*) => {
+            $(
+                #[test]
+                fn $name() {
+                    let (query, k, expected_results) = $value;
+                    let trie = super::build_trie();
+                    let results: Vec<(String, &u8, usize)> = trie.knn(query, k);
+                    let results: Vec<(String, u8, usize)> = results.into_iter().map(|(k, v, d)| (k, *v, d)).collect();
+                    let expected_results: Vec<(String, u8, usize)> = expected_results.iter().map(|s: &(&str, u8, usize)| (s.0.to_string(), s.1, s.2)).collect();
+                    assert_eq!(results, expected_results);
+                }
+            )*
+            }
+        }
+
+        parameterized_tests! {
+            t1: ("apple", 1, vec![("apple", 2, 0)]),
+            t2: ("apple", 3, vec![("apple", 2, 0), ("app", 1, 2), ("a", 0, 4)]),
+            // "a" and "app" tie at 1; "a" is lexicographically first.
+            t3: ("ap", 1, vec![("a", 0, 1)]),
+            t4: ("ap", 2, vec![("a", 0, 1), ("app", 1, 1)]),
+            t5: ("ab", 0, Vec::<(&str, u8, usize)>::new()),
+            t6: ("ab", 100, vec![
+                ("a", 0, 1),
+                ("app", 1, 2),
+                ("apple", 2, 4),
+                ("better", 3, 6),
+                ("application", 4, 10),
+                ("アップル🍎", 5, 16),
+            ]),
+        }
+    }
+
+    mod fuzzy_predictive_search_tests {
+        macro_rules! parameterized_tests {
+            ($($name:ident: $value:expr,)*) => {
+            $(
+                #[test]
+                fn $name() {
+                    let (query, max_distance, limit, expected_results) = $value;
+                    let trie = super::build_trie();
+                    let results: Vec<(String, &u8)> = trie.fuzzy_predictive_search(query, max_distance, limit);
+                    let expected_results: Vec<(String, &u8)> = expected_results.iter().map(|s: &(&str, u8)| (s.0.to_string(), &s.1)).collect();
+                    assert_eq!(results, expected_results);
+                }
+            )*
+            }
+        }
+
+        parameterized_tests! {
+            t1: ("appl", 0, 10, vec![("apple", 2), ("application", 4)]),
+            t2: ("apl", 1, 10, vec![("app", 1), ("apple", 2), ("application", 4)]),
+            t3: ("bett", 1, 10, vec![("better", 3)]),
+            t4: ("btter", 1, 10, vec![("better", 3)]),
+            t5: ("apl", 1, 2, vec![("app", 1), ("apple", 2)]),
+            t9: ("apl", 2, 10, vec![("a", 0), ("app", 1), ("apple", 2), ("application", 4)]),
+            t6: ("xyz", 1, 10, Vec::<(&str, u8)>::new()),
+            t7: ("", 0, 10, vec![("a", 0), ("app", 1), ("apple", 2), ("application", 4), ("better", 3), ("アップル🍎", 5)]),
+            t8: ("apple", 0, 0, Vec::<(&str, u8)>::new()),
+        }
+    }
+
+    mod suggest_tests {
+        macro_rules! parameterized_tests {
+            ($($name:ident: $value:expr,)*) => {
+            $(
+                #[test]
+                fn $name() {
+                    let (query, max_distance, limit, expected_results) = $value;
+                    let trie = super::build_trie();
+                    let results: Vec<(String, &u8)> = trie.suggest(query, max_distance, limit);
+                    let expected_results: Vec<(String, &u8)> = expected_results.iter().map(|s: &(&str, u8)| (s.0.to_string(), &s.1)).collect();
+                    assert_eq!(results, expected_results);
+                }
+            )*
+            }
+        }
+
+        parameterized_tests! {
+            // "apple" (1 edit, ln 2) outranks "app" (1 edit, ln 1 = 0).
+            t1: ("appe", 1, 10, vec![("apple", 2), ("app", 1)]),
+            // "a" has frequency 0 so it ranks last.
+            t2: ("ap", 1, 10, vec![("app", 1), ("a", 0)]),
+            t3: ("appe", 1, 1, vec![("apple", 2)]),
+            t4: ("bettor", 1, 10, vec![("better", 3)]),
+            t5: ("xyz", 1, 10, Vec::<(&str, u8)>::new()),
+        }
+    }
+
+    #[test]
+    fn suggest_by_distance_only() {
+        let trie = build_trie();
+        let results: Vec<(String, &u8)> = trie.suggest_by("appe", 3, 10, |d, _| d as f64);
+        assert_eq!(
+            results,
+            [
+                ("app".to_string(), &1),
+                ("apple".to_string(), &2),
+                ("a".to_string(), &0)
+            ]
+        );
+    }
+
+    #[test]
+    fn nearest_empty() {
+        let trie: Trie<u8, u8> = TrieBuilder::new().build();
+        assert_eq!(trie.nearest::<String, _>("a"), None);
+    }
+}
@@ -0,0 +1,85 @@
+//! Keys of grapheme clusters. Requires the "unicode-segmentation" crate
+//! feature.
+//!
+//! A grapheme cluster is what a user perceives as one character, e.g., a flag
+//! "🇯🇵" is two `char`s and "é" may be an "e" and a combining accent. Keys
+//! split into grapheme clusters never match half of one.
+use super::{Trie, TrieBuilder};
+use crate::iter::SearchIter;
+use crate::try_collect::Collect;
+use unicode_segmentation::UnicodeSegmentation;
+
+/// Return the extended grapheme clusters of `s`.
+pub(crate) fn graphemes(s: &str) -> Vec<String> {
+    s.graphemes(true).map(String::from).collect()
+}
+
+impl<Value> TrieBuilder<String, Value> {
+    /// Add an entry and value whose labels are the grapheme clusters of `s`.
+    pub fn push_graphemes(&mut self, s: &str, value: Value) {
+        self.insert(graphemes(s), value);
+    }
+}
+
+impl<Value> Trie<String, Value> {
+    /// Return `Some(&Value)` if the grapheme clusters of `query` are an exact
+    /// match.
+    pub fn exact_match_graphemes(&self, query: &str) -> Option<&Value> {
+        self.exact_match(graphemes(query))
+    }
+
+    /// Return all entries and their values that start with the grapheme
+    /// clusters of `query`.
+    ///
+    /// ```rust
+    /// use trie_rs::map::TrieBuilder;
+    ///
+    /// let mut builder = TrieBuilder::new();
+    /// builder.push_graphemes("🇯🇵🍣", 0);
+    /// builder.push_graphemes("🇯🇲", 1);
+    /// let trie = builder.build();
+    ///
+    /// let results: Vec<(String, &u8)> = trie.predictive_search_graphemes("🇯🇵").collect();
+    /// assert_eq!(results, [("🇯🇵🍣".to_string(), &0)]);
+    /// // "🇯" is half of both flags, but not a grapheme cluster of either.
+    /// assert_eq!(trie.predictive_search_graphemes("🇯").count(), 0);
+    /// ```
+    pub fn predictive_search_graphemes(
+        &self,
+        query: &str,
+    ) -> SearchIter<'_, String, Value, String, Collect> {
+        self.predictive_search(graphemes(query))
+    }
+}
+
+#[cfg(test)]
+mod graphemes_tests {
+    use crate::map::{Trie, TrieBuilder};
+
+    fn build_trie() -> Trie<String, u8> {
+        let mut builder = TrieBuilder::new();
+        builder.push_graphemes("🇯🇵", 0);
+        builder.push_graphemes("cafe\u{301}", 1);
+        builder.push_graphemes("アップル🍎", 2);
+        builder.build()
+    }
+
+    #[test]
+    fn one_label_per_grapheme() {
+        let trie = build_trie();
+        let keys: Vec<(Vec<String>, &u8)> = trie.iter().collect();
+        assert_eq!(keys[2], (vec!["🇯🇵".to_string()], &0));
+        assert_eq!(trie.exact_match_graphemes("🇯🇵"), Some(&0));
+        assert_eq!(trie.exact_match_graphemes("\u{1f1ef}"), None);
+    }
+
+    #[test]
+    fn combining_accent() {
+        let trie = build_trie();
+        assert_eq!(trie.exact_match_graphemes("cafe\u{301}"), Some(&1));
+        // The accent belongs to the "e", so "cafe" is not a prefix.
+        assert_eq!(trie.predictive_search_graphemes("cafe").count(), 0);
+        let results: Vec<(String, &u8)> = trie.predictive_search_graphemes("caf").collect();
+        assert_eq!(results, [("cafe\u{301}".to_string(), &1)]);
+    }
+}
@@ -0,0 +1,104 @@
+//! Human-readable JSON of `u8` tries with UTF-8 keys.
+use super::{Trie, TrieBuilder};
+use serde::de::DeserializeOwned;
+use serde::ser::{Error, SerializeMap};
+use serde::{Serialize, Serializer};
+use std::collections::BTreeMap;
+
+impl<Value: Serialize> Trie<u8, Value> {
+    /// Return the entries of this trie as a JSON object of keys to values.
+    ///
+    /// Fails if a key is not UTF-8 or a value cannot be written as JSON.
+    ///
+    /// ```rust
+    /// use trie_rs::map::Trie;
+    ///
+    /// let trie = Trie::from_iter([("app", 1), ("a", 0)]);
+    /// assert_eq!(trie.to_json().unwrap(), r#"{"a":0,"app":1}"#);
+    /// ```
+    pub fn to_json(&self) -> serde_json::Result<String> {
+        serde_json::to_string(&Entries(self))
+    }
+}
+
+impl<Value: DeserializeOwned> Trie<u8, Value> {
+    /// Build a trie from a JSON object of keys to values, as written by
+    /// [Trie::to_json]. If a key repeats, its last value wins.
+    ///
+    /// Fails if a key is empty, which a trie cannot hold.
+    ///
+    /// ```rust
+    /// use trie_rs::map::Trie;
+    ///
+    /// let trie = Trie::<u8, u32>::from_json(r#"{"a": 0, "app": 1}"#).unwrap();
+    /// assert_eq!(trie.exact_match("app"), Some(&1));
+    /// ```
+    pub fn from_json(json: &str) -> serde_json::Result<Self> {
+        let entries: BTreeMap<String, Value> = serde_json::from_str(json)?;
+        if entries.contains_key("") {
+            return Err(serde::de::Error::custom("an empty key cannot be an entry"));
+        }
+        Ok(TrieBuilder::build_from_sorted_unchecked(
+            entries
+                .into_iter()
+                .map(|(key, value)| (key.into_bytes(), value)),
+        ))
+    }
+}
+
+/// Serialize a trie's entries as a map.
+struct Entries<'a, Value>(&'a Trie<u8, Value>);
+
+impl<Value: Serialize> Serialize for Entries<'_, Value> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(None)?;
+        for entry in self.0.try_iter::<String, _>() {
+            let (key, value) = entry.map_err(S::Error::custom)?;
+            map.serialize_entry(&key, value)?;
+        }
+        map.end()
+    }
+}
+
+#[cfg(test)]
+mod json_tests {
+    use crate::map::Trie;
+
+    #[test]
+    fn round_trip() {
+        let trie = Trie::<u8, Vec<u8>>::from_iter([
+            ("a", vec![0]),
+            ("app", vec![1, 2]),
+            ("apple", vec![]),
+            ("アップル🍎", vec![3]),
+        ]);
+        let json = trie.to_json().unwrap();
+        assert_eq!(Trie::from_json(&json).unwrap(), trie);
+    }
+
+    #[test]
+    fn empty() {
+        let trie = Trie::<u8, u8>::from_json("{}").unwrap();
+        assert_eq!(trie.stats().terminals, 0);
+        assert_eq!(trie.to_json().unwrap(), "{}");
+    }
+
+    #[test]
+    fn invalid_utf8_key() {
+        let trie = Trie::from_iter([(&b"o\xff"[..], 0)]);
+        assert!(trie.to_json().is_err());
+    }
+
+    #[test]
+    fn empty_key() {
+        let err = Trie::<u8, u8>::from_json(r#"{"":1}"#).unwrap_err();
+        assert!(err.to_string().contains("empty key"));
+        assert!(Trie::<u8, u8>::from_json(r#"{"a":0,"":1}"#).is_err());
+    }
+
+    #[test]
+    fn invalid_json() {
+        assert!(Trie::<u8, u8>::from_json("[1, 2]").is_err());
+        assert!(Trie::<u8, u8>::from_json(r#"{"a": "zero"}"#).is_err());
+    }
+}
@@ -0,0 +1,91 @@
+use super::{Trie, TrieBuilder};
+use rayon::iter::{FromParallelIterator, IntoParallelIterator, ParallelIterator};
+use rayon::slice::ParallelSliceMut;
+
+/// Collect a trie from a parallel iterator.
+///
+/// The entries are collected and sorted in parallel, then built with
+/// [TrieBuilder::build_from_sorted_unchecked]. As with [FromIterator], the
+/// last value of a duplicate key wins.
+///
+/// # Panics
+/// If a key is empty, as with [TrieBuilder::push].
+///
+/// ```rust
+/// use rayon::prelude::*;
+/// use trie_rs::map::Trie;
+///
+/// let trie: Trie<u8, usize> = ["a", "app", "apple", "a"]
+///     .into_par_iter()
+///     .enumerate()
+///     .map(|(i, key)| (key, i))
+///     .collect();
+/// assert_eq!(trie.exact_match("a"), Some(&3));
+/// assert_eq!(trie.exact_match("apple"), Some(&2));
+/// ```
+impl<Label, Value, C> FromParallelIterator<(C, Value)> for Trie<Label, Value>
+where
+    C: AsRef<[Label]> + Send,
+    Label: Ord + Clone + Send,
+    Value: Send,
+{
+    fn from_par_iter<I>(par_iter: I) -> Self
+    where
+        I: IntoParallelIterator<Item = (C, Value)>,
+    {
+        let mut entries: Vec<(Vec<Label>, Value)> = par_iter
+            .into_par_iter()
+            .map(|(key, value)| (key.as_ref().to_vec(), value))
+            .collect();
+        // A stable sort keeps duplicates in their original order.
+        entries.par_sort_by(|a, b| a.0.cmp(&b.0));
+        let mut deduped: Vec<(Vec<Label>, Value)> = Vec::with_capacity(entries.len());
+        for entry in entries {
+            match deduped.last_mut() {
+                Some(last) if last.0 == entry.0 => *last = entry,
+                _ => deduped.push(entry),
+            }
+        }
+        // An empty key sorts first.
+        assert!(
+            deduped.first().map_or(true, |(key, _)| !key.is_empty()),
+            "An empty entry cannot be pushed"
+        );
+        TrieBuilder::build_from_sorted_unchecked(deduped)
+    }
+}
+
+#[cfg(test)]
+mod par_tests {
+    use crate::map::Trie;
+    use rayon::prelude::*;
+
+    #[test]
+    fn matches_serial() {
+        let entries: Vec<(String, usize)> =
+            (0..1000).map(|i| (format!("{}", i * 7 % 300), i)).collect();
+        let serial: Trie<u8, usize> = entries.iter().cloned().collect();
+        let parallel: Trie<u8, usize> = entries.into_par_iter().collect();
+        assert_eq!(parallel, serial);
+        assert_eq!(parallel.exact_match("0"), Some(&900));
+    }
+
+    #[test]
+    fn set_trie() {
+        let keys = ["a", "app", "apple", "app"];
+        let trie: crate::Trie<u8> = keys.into_par_iter().collect();
+        assert_eq!(trie, keys.into_iter().collect());
+    }
+
+    #[test]
+    #[should_panic(expected = "empty entry")]
+    fn empty_key() {
+        let _: Trie<u8, u8> = vec![("a", 0), ("", 1)].into_par_iter().collect();
+    }
+
+    #[test]
+    fn empty() {
+        let trie: Trie<u8, u8> = Vec::<(&str, u8)>::new().into_par_iter().collect();
+        assert_eq!(trie.iter::<Vec<u8>, _>().next(), None);
+    }
+}
@@ -0,0 +1,213 @@
+//! Search by glob-like patterns of labels and wildcards.
+//!
+//! A pattern is matched by walking the trie once in lexicographic order while
+//! keeping, per node, the set of pattern positions its key can have reached.
+//! Subtrees where that set is empty are pruned.
+use super::Trie;
+use crate::try_collect::TryFromIterator;
+use louds_rs::LoudsNodeNum;
+
+/// One item of a pattern for [Trie::pattern_search].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Glob<Label> {
+    /// Exactly this label.
+    Label(Label),
+    /// Any one label, like `?`.
+    One,
+    /// Any run of labels, including none, like `*`.
+    Any,
+}
+
+impl Glob<u8> {
+    /// Return the pattern of `s` where `?` is [Glob::One], `*` is [Glob::Any],
+    /// and every other byte is itself.
+    ///
+    /// ```rust
+    /// use trie_rs::map::Glob;
+    ///
+    /// assert_eq!(Glob::parse("a?*"), [Glob::Label(b'a'), Glob::One, Glob::Any]);
+    /// ```
+    pub fn parse(s: &str) -> Vec<Glob<u8>> {
+        s.bytes()
+            .map(|byte| match byte {
+                b'?' => Glob::One,
+                b'*' => Glob::Any,
+                _ => Glob::Label(byte),
+            })
+            .collect()
+    }
+}
+
+impl<Label: Ord, Value> Trie<Label, Value> {
+    /// Walk the nodes whose key is a prefix of some match of `pattern` in
+    /// lexicographic order, with whether the key itself matches.
+    ///
+    /// `visit` returns false to stop the walk.
+    pub(crate) fn pattern_walk(
+        &self,
+        pattern: &[Glob<Label>],
+        mut visit: impl FnMut(LoudsNodeNum, bool) -> bool,
+    ) {
+        let mut stack = vec![(LoudsNodeNum(1), skip_any(pattern, vec![0]))];
+        while let Some((node_num, positions)) = stack.pop() {
+            let matched = positions.last() == Some(&pattern.len());
+            if !visit(node_num, matched) {
+                return;
+            }
+            let n = stack.len();
+            for child in self.children_node_nums(node_num) {
+                let label = self.label(child);
+                let mut next = Vec::new();
+                for &i in &positions {
+                    match pattern.get(i) {
+                        Some(Glob::Label(l)) if l == label => next.push(i + 1),
+                        Some(Glob::One) => next.push(i + 1),
+                        Some(Glob::Any) => next.push(i),
+                        _ => {}
+                    }
+                }
+                next.dedup();
+                if !next.is_empty() {
+                    stack.push((child, skip_any(pattern, next)));
+                }
+            }
+            stack[n..].reverse();
+        }
+    }
+
+    /// Return every entry that matches `pattern` in lexicographic order.
+    ///
+    /// ```rust
+    /// use trie_rs::map::{Glob, Trie};
+    ///
+    /// let trie = Trie::from_iter([("apple", 0), ("apply", 1), ("maple", 2), ("ample", 3)]);
+    /// let results: Vec<(String, &u8)> = trie.pattern_search(&Glob::parse("a*le"));
+    /// assert_eq!(results, [("ample".to_string(), &3), ("apple".to_string(), &0)]);
+    /// let results: Vec<(String, &u8)> = trie.pattern_search(&Glob::parse("appl?"));
+    /// assert_eq!(results, [("apple".to_string(), &0), ("apply".to_string(), &1)]);
+    /// ```
+    pub fn pattern_search<C, M>(&self, pattern: &[Glob<Label>]) -> Vec<(C, &Value)>
+    where
+        C: TryFromIterator<Label, M>,
+        Label: Clone,
+    {
+        let mut results = vec![];
+        self.pattern_walk(pattern, |node_num, matched| {
+            if matched {
+                if let Some(value) = self.value(node_num) {
+                    results.push((self.node_key(node_num), value));
+                }
+            }
+            true
+        });
+        results
+    }
+
+    /// Return true if any entry matches `pattern`, stopping at the first
+    /// one found rather than enumerating them. See [Trie::pattern_search].
+    ///
+    /// ```rust
+    /// use trie_rs::map::{Glob, Trie};
+    ///
+    /// let trie = Trie::from_iter([("img/cat.png", 0), ("img/dog.jpg", 1)]);
+    /// assert!(trie.matches_pattern(&Glob::parse("img/*.jpg")));
+    /// assert!(!trie.matches_pattern(&Glob::parse("img/*.gif")));
+    /// ```
+    pub fn matches_pattern(&self, pattern: &[Glob<Label>]) -> bool {
+        let mut found = false;
+        self.pattern_walk(pattern, |node_num, matched| {
+            found = matched && self.is_terminal(node_num);
+            !found
+        });
+        found
+    }
+}
+
+/// Add the positions after each [Glob::Any] in `positions`, which may match
+/// no labels, and return them sorted.
+fn skip_any<Label>(pattern: &[Glob<Label>], mut positions: Vec<usize>) -> Vec<usize> {
+    let mut k = 0;
+    while k < positions.len() {
+        let i = positions[k];
+        if let Some(Glob::Any) = pattern.get(i) {
+            if !positions.contains(&(i + 1)) {
+                positions.push(i + 1);
+            }
+        }
+        k += 1;
+    }
+    positions.sort_unstable();
+    positions
+}
+
+#[cfg(test)]
+mod pattern_tests {
+    use super::Glob;
+    use crate::map::Trie;
+
+    fn build_trie() -> Trie<u8, u8> {
+        Trie::from_iter([
+            ("a", 0),
+            ("app", 1),
+            ("apple", 2),
+            ("better", 3),
+            ("application", 4),
+            ("アップル🍎", 5),
+        ])
+    }
+
+    mod pattern_search_tests {
+        use super::Glob;
+
+        macro_rules! parameterized_tests {
+            ($($name:ident: $value:expr,)*) => {
+            $(
+                #[test]
+                fn $name() {
+                    let (pattern, expected_results) = $value;
+                    let trie = super::build_trie();
+                    let pattern = Glob::parse(pattern);
+                    let results: Vec<(String, &u8)> = trie.pattern_search(&pattern);
+                    let expected_results: Vec<(String, &u8)> = expected_results.iter().map(|s| (s.0.to_string(), &s.1)).collect();
+                    assert_eq!(results, expected_results);
+                    assert_eq!(trie.matches_pattern(&pattern), !expected_results.is_empty());
+                }
+            )*
+            }
+        }
+
+        parameterized_tests! {
+            t1: ("*", vec![("a", 0), ("app", 1), ("apple", 2), ("application", 4), ("better", 3), ("アップル🍎", 5)]),
+            t2: ("a*", vec![("a", 0), ("app", 1), ("apple", 2), ("application", 4)]),
+            t3: ("?pp", vec![("app", 1)]),
+            t4: ("*e*", vec![("apple", 2), ("better", 3)]),
+            t5: ("**p*l*", vec![("apple", 2), ("application", 4)]),
+            t6: ("a?", Vec::<(&str, u8)>::new()),
+            t7: ("", Vec::<(&str, u8)>::new()),
+            t8: ("*n", vec![("application", 4)]),
+            t9: ("b?tt?r", vec![("better", 3)]),
+            t10: ("apple?*", Vec::<(&str, u8)>::new()),
+        }
+    }
+
+    #[test]
+    fn matches_pattern_stops_early() {
+        // A large subtree under "a" of keys like "a0000".."a9999" and a match
+        // deep inside it that comes first lexicographically.
+        let mut keys: Vec<String> = (0..10_000).map(|i| format!("a{:04}", i)).collect();
+        keys.push("a0000/deep/match".to_string());
+        let trie: Trie<u8, ()> = keys.iter().map(|key| (key.as_str(), ())).collect();
+        let pattern = Glob::parse("a*/match");
+        assert!(trie.matches_pattern(&pattern));
+
+        let mut visited = 0;
+        let mut found = false;
+        trie.pattern_walk(&pattern, |node_num, matched| {
+            visited += 1;
+            found = matched && trie.is_terminal(node_num);
+            !found
+        });
+        assert!(found);
+        assert!(visited < 50, "visited {} nodes", visited);
+    }
+}
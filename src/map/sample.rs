@@ -0,0 +1,324 @@
+//! Random sampling of entries. Requires the "rand" crate feature.
+use super::{Trie, TrieBuilder};
+use crate::try_collect::TryFromIterator;
+use louds_rs::LoudsNodeNum;
+use rand::Rng;
+
+#[derive(Debug, Clone)]
+/// Samples entries of a [Trie] at random with probability proportional to
+/// their values, for a trie that was not built with
+/// [TrieBuilder::build_weighted].
+///
+/// Creating a sampler sums the weights of every subtree once, which is _O(n)_.
+/// Afterwards each sample only walks one path from the root, which is
+/// _O(m k)_ where _m_ is the length of the sampled key and _k_ the most
+/// children of a node along it. Keep a sampler to draw many samples.
+///
+/// ```rust
+/// use rand::rngs::StdRng;
+/// use rand::SeedableRng;
+/// use trie_rs::map::Trie;
+///
+/// let trie = Trie::from_iter([("app", 1u8), ("apple", 3)]);
+/// let sampler = trie.sampler();
+/// let mut rng = StdRng::seed_from_u64(0);
+/// for _ in 0..10 {
+///     let (key, _): (String, &u8) = sampler.sample(&mut rng).unwrap();
+///     assert!(key.starts_with("app"));
+/// }
+/// ```
+pub struct Sampler<'a, Label, Value> {
+    trie: &'a Trie<Label, Value>,
+    /// (LoudsNodeNum - 1) -> (weight of node's own entry, weight of subtree)
+    weights: Vec<(f64, f64)>,
+}
+
+impl<Label: Ord, Value> TrieBuilder<Label, Value> {
+    /// Build a [Trie] that can [sample](Trie::sample) its entries with
+    /// probability proportional to their values. Negative and NaN values are
+    /// treated as zero.
+    ///
+    /// This sums the weights of every subtree once, which is _O(n)_, and keeps
+    /// one `f64` per node in the trie.
+    ///
+    /// # Panics
+    /// Like [TrieBuilder::build].
+    pub fn build_weighted(self) -> Trie<Label, Value>
+    where
+        Value: Clone + Into<f64>,
+    {
+        let mut trie = self.build();
+        let weights = subtree_weights(&trie)
+            .into_iter()
+            .map(|(_, subtree)| subtree)
+            .collect();
+        trie.weights = Some(weights);
+        trie
+    }
+}
+
+impl<Label: Ord, Value> Trie<Label, Value> {
+    /// Choose an entry with probability proportional to its value. Each
+    /// sample walks one path from the root, which is _O(m k)_ where _m_ is
+    /// the length of the sampled key and _k_ the most children of a node
+    /// along it.
+    ///
+    /// Returns `None` if the total weight is zero, or if the trie has no
+    /// weights: it was not built with [TrieBuilder::build_weighted], or a
+    /// value has since been changed in place, e.g., by
+    /// [Trie::exact_match_mut]. Use [Trie::sampler] for such a trie.
+    ///
+    /// ```rust
+    /// use rand::rngs::StdRng;
+    /// use rand::SeedableRng;
+    /// use trie_rs::map::TrieBuilder;
+    ///
+    /// let mut builder = TrieBuilder::new();
+    /// builder.push("app", 0u8);
+    /// builder.push("apple", 3);
+    /// let trie = builder.build_weighted();
+    /// let mut rng = StdRng::seed_from_u64(0);
+    /// let (key, value): (String, &u8) = trie.sample(&mut rng).unwrap();
+    /// assert_eq!((key.as_str(), value), ("apple", &3));
+    /// ```
+    pub fn sample<R, C, M>(&self, rng: &mut R) -> Option<(C, &Value)>
+    where
+        R: Rng + ?Sized,
+        C: TryFromIterator<Label, M>,
+        Label: Clone,
+        Value: Clone + Into<f64>,
+    {
+        let weights = self.weights.as_ref()?;
+        let node_num = self.descend(rng, |node_num| {
+            let own = self.value(node_num).map_or(0.0, entry_weight);
+            (own, weights[node_num.0 as usize - 1])
+        })?;
+        self.value(node_num)
+            .map(|value| (self.node_key(node_num), value))
+    }
+
+    /// Choose every entry with equal probability, or return `None` if there
+    /// are none. This selects a terminal node by rank, which is
+    /// _O(m + log n)_ where _m_ is the length of the sampled key, and needs
+    /// no weights.
+    pub fn sample_uniform<R, C, M>(&self, rng: &mut R) -> Option<(C, &Value)>
+    where
+        R: Rng + ?Sized,
+        C: TryFromIterator<Label, M>,
+        Label: Clone,
+    {
+        if self.values.is_empty() {
+            return None;
+        }
+        let i = rng.gen_range(0, self.values.len() as u64);
+        let node_num = LoudsNodeNum(self.terminals.select(i + 1)? + 1);
+        self.value(node_num)
+            .map(|value| (self.node_key(node_num), value))
+    }
+
+    /// Return a [Sampler] that chooses an entry with probability proportional
+    /// to its value. Negative and NaN values are treated as zero.
+    pub fn sampler(&self) -> Sampler<'_, Label, Value>
+    where
+        Value: Clone + Into<f64>,
+    {
+        Sampler {
+            trie: self,
+            weights: subtree_weights(self),
+        }
+    }
+
+    /// Walk down from the root to the node of a random entry given each
+    /// node's (own, subtree) weights.
+    fn descend<R>(
+        &self,
+        rng: &mut R,
+        weight: impl Fn(LoudsNodeNum) -> (f64, f64),
+    ) -> Option<LoudsNodeNum>
+    where
+        R: Rng + ?Sized,
+    {
+        let (_, total) = weight(LoudsNodeNum(1));
+        if total <= 0.0 {
+            return None;
+        }
+        let mut r = rng.gen::<f64>() * total;
+        let mut node_num = LoudsNodeNum(1);
+        'descend: loop {
+            let (own, _) = weight(node_num);
+            if r < own {
+                break;
+            }
+            r -= own;
+            let mut fallback = None;
+            for child in self.children_node_nums(node_num) {
+                let (_, subtree) = weight(child);
+                if subtree <= 0.0 {
+                    continue;
+                }
+                if r < subtree {
+                    node_num = child;
+                    continue 'descend;
+                }
+                r -= subtree;
+                fallback = Some(child);
+            }
+            // Rounding may leave `r` past the last child; take the last one
+            // with any weight.
+            match fallback {
+                Some(child) => {
+                    node_num = child;
+                    r = 0.0;
+                }
+                None => break,
+            }
+        }
+        Some(node_num)
+    }
+}
+
+impl<'a, Label: Ord, Value> Sampler<'a, Label, Value> {
+    fn weight(&self, node_num: LoudsNodeNum) -> (f64, f64) {
+        self.weights[node_num.0 as usize - 1]
+    }
+
+    /// Return the sum of all weights.
+    pub fn total_weight(&self) -> f64 {
+        self.weight(LoudsNodeNum(1)).1
+    }
+
+    /// Choose an entry at random. Returns `None` if the total weight is zero.
+    pub fn sample<R, C, M>(&self, rng: &mut R) -> Option<(C, &'a Value)>
+    where
+        R: Rng + ?Sized,
+        C: TryFromIterator<Label, M>,
+        Label: Clone,
+    {
+        let node_num = self.trie.descend(rng, |node_num| self.weight(node_num))?;
+        self.trie
+            .value(node_num)
+            .map(|value| (self.trie.node_key(node_num), value))
+    }
+}
+
+/// Return the weight of an entry, which is its value or zero if that is
+/// negative or NaN.
+fn entry_weight<Value: Clone + Into<f64>>(value: &Value) -> f64 {
+    let weight = value.clone().into();
+    if weight > 0.0 {
+        weight
+    } else {
+        0.0
+    }
+}
+
+/// Return (LoudsNodeNum - 1) -> (weight of node's own entry, weight of
+/// subtree).
+fn subtree_weights<Label: Ord, Value: Clone + Into<f64>>(
+    trie: &Trie<Label, Value>,
+) -> Vec<(f64, f64)> {
+    let node_count = trie.labels.len() + 1;
+    let mut weights = vec![(0.0, 0.0); node_count];
+    // Children always have greater node numbers than their parents.
+    for i in (0..node_count).rev() {
+        let node_num = LoudsNodeNum(i as u64 + 1);
+        let own = trie.value(node_num).map_or(0.0, entry_weight);
+        let children: f64 = trie
+            .children_node_nums(node_num)
+            .map(|child| weights[child.0 as usize - 1].1)
+            .sum();
+        weights[i] = (own, own + children);
+    }
+    weights
+}
+
+#[cfg(test)]
+mod sample_tests {
+    use crate::map::{Trie, TrieBuilder};
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+    use std::collections::HashMap;
+
+    fn build_builder() -> TrieBuilder<u8, u8> {
+        let mut builder = TrieBuilder::new();
+        builder.push("a", 0);
+        builder.push("app", 1);
+        builder.push("apple", 3);
+        builder.push("better", 4);
+        builder
+    }
+
+    fn histogram<'a>(
+        trie: &'a Trie<u8, u8>,
+        sample: impl Fn(&mut StdRng) -> Option<(String, &'a u8)>,
+    ) -> HashMap<String, usize> {
+        let mut rng = StdRng::seed_from_u64(0);
+        let mut counts = HashMap::new();
+        for _ in 0..8000 {
+            let (key, value) = sample(&mut rng).unwrap();
+            assert_eq!(trie.exact_match(&key), Some(value));
+            *counts.entry(key).or_insert(0) += 1;
+        }
+        counts
+    }
+
+    fn assert_weighted(counts: &HashMap<String, usize>) {
+        assert_eq!(counts.get("a"), None);
+        // Expect 1000, 3000, and 4000.
+        assert!((800..1200).contains(&counts["app"]));
+        assert!((2700..3300).contains(&counts["apple"]));
+        assert!((3700..4300).contains(&counts["better"]));
+    }
+
+    #[test]
+    fn weighted() {
+        let trie = build_builder().build_weighted();
+        assert_weighted(&histogram(&trie, |rng| trie.sample(rng)));
+    }
+
+    #[test]
+    fn sampler() {
+        let trie = build_builder().build();
+        let sampler = trie.sampler();
+        assert_eq!(sampler.total_weight(), 8.0);
+        assert_weighted(&histogram(&trie, |rng| sampler.sample(rng)));
+    }
+
+    #[test]
+    fn uniform() {
+        let trie = build_builder().build();
+        let counts = histogram(&trie, |rng| trie.sample_uniform(rng));
+        assert_eq!(counts.len(), 4);
+        for count in counts.values() {
+            assert!((1800..2200).contains(count));
+        }
+    }
+
+    #[test]
+    fn without_weights() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let trie = build_builder().build();
+        assert_eq!(trie.sample::<_, String, _>(&mut rng), None);
+        let mut trie = build_builder().build_weighted();
+        assert!(trie.sample::<_, String, _>(&mut rng).is_some());
+        *trie.exact_match_mut("a").unwrap() = 5;
+        assert_eq!(trie.sample::<_, String, _>(&mut rng), None);
+    }
+
+    #[test]
+    fn empty() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let trie: Trie<u8, u8> = TrieBuilder::new().build_weighted();
+        assert_eq!(trie.sample::<_, String, _>(&mut rng), None);
+        assert_eq!(trie.sample_uniform::<_, String, _>(&mut rng), None);
+        assert_eq!(trie.sampler().sample::<_, String, _>(&mut rng), None);
+        let mut builder = TrieBuilder::new();
+        builder.push("a", 0u8);
+        let trie = builder.build_weighted();
+        assert_eq!(trie.sample::<_, String, _>(&mut rng), None);
+        assert_eq!(
+            trie.sample_uniform::<_, String, _>(&mut rng),
+            Some(("a".to_string(), &0))
+        );
+    }
+}
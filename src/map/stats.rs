@@ -0,0 +1,217 @@
+//! Memory and structure reporting.
+use super::{Trie, Visitor};
+use std::mem::size_of;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[non_exhaustive]
+/// Structural statistics of a trie. See [Trie::stats].
+pub struct Stats {
+    /// Number of nodes, not counting the root.
+    pub nodes: usize,
+    /// Number of terminal nodes, i.e., the number of entries.
+    pub terminals: usize,
+    /// Length of the longest key.
+    pub max_depth: usize,
+    /// Sum of the lengths of all keys.
+    pub total_depth: usize,
+    /// Number of bits in the LOUDS bit vector, two per node plus three.
+    pub louds_bits: usize,
+}
+
+impl Stats {
+    /// Return the mean length of the keys, or zero if there are none.
+    pub fn avg_depth(&self) -> f64 {
+        if self.terminals == 0 {
+            0.0
+        } else {
+            self.total_depth as f64 / self.terminals as f64
+        }
+    }
+}
+
+impl<Label: Ord, Value> Trie<Label, Value> {
+    /// Return the approximate number of bytes this trie has allocated on the
+    /// heap.
+    ///
+    /// Included are:
+    /// - the LOUDS bit vector and its rank/select index,
+    /// - the bit vector of terminal nodes and its rank index,
+    /// - the `Label`s and `Value`s, counting the full capacity of their vectors,
+    /// - the subtree counts with the "counts" crate feature,
+    /// - the sampling weights if built with `build_weighted()`, and
+    /// - the insertion order if kept.
+    ///
+    /// Not included is any heap memory owned by the `Label`s or `Value`s
+    /// themselves. Use the "mem_dbg" crate feature for an exact accounting.
+    pub fn heap_size_bytes(&self) -> usize {
+        let louds_bits = 2 * self.labels.len() as u64 + 3;
+        let terminal_bits = self.labels.len() as u64 + 1;
+        fid_heap_size(louds_bits)
+            + fid_heap_size(terminal_bits)
+            + self.labels.capacity() * size_of::<Label>()
+            + self.values.capacity() * size_of::<Value>()
+            + self.counts_capacity() * size_of::<u32>()
+            + self.weights_capacity() * size_of::<f64>()
+            + self.insertion_order.as_ref().map_or(0, Vec::capacity) * size_of::<u64>()
+    }
+
+    #[cfg(feature = "counts")]
+    fn counts_capacity(&self) -> usize {
+        self.counts.capacity()
+    }
+
+    #[cfg(not(feature = "counts"))]
+    fn counts_capacity(&self) -> usize {
+        0
+    }
+
+    #[cfg(feature = "rand")]
+    fn weights_capacity(&self) -> usize {
+        self.weights.as_ref().map_or(0, Vec::capacity)
+    }
+
+    #[cfg(not(feature = "rand"))]
+    fn weights_capacity(&self) -> usize {
+        0
+    }
+
+    /// Release any excess capacity held by this trie back to the allocator.
+    ///
+    /// Building a trie may leave spare capacity in its internal vectors. Call
+    /// this on long-lived tries; [Trie::heap_size_bytes] reflects the effect.
+    pub fn shrink_to_fit(&mut self) {
+        self.labels.shrink_to_fit();
+        self.values.shrink_to_fit();
+        #[cfg(feature = "counts")]
+        self.counts.shrink_to_fit();
+        #[cfg(feature = "rand")]
+        if let Some(weights) = &mut self.weights {
+            weights.shrink_to_fit();
+        }
+        if let Some(orders) = &mut self.insertion_order {
+            orders.shrink_to_fit();
+        }
+    }
+
+    /// Return the structure of this trie: its node and terminal counts, key
+    /// depths, and LOUDS size.
+    ///
+    /// ```rust
+    /// use trie_rs::map::Trie;
+    ///
+    /// let trie = Trie::from_iter([("a", 0), ("app", 1), ("apple", 2)]);
+    /// let stats = trie.stats();
+    /// assert_eq!((stats.nodes, stats.terminals, stats.max_depth), (5, 3, 5));
+    /// assert_eq!(stats.avg_depth(), 3.0);
+    /// assert_eq!(stats.louds_bits, 13);
+    /// ```
+    pub fn stats(&self) -> Stats {
+        struct Collect(Stats, usize);
+        impl<Label, Value> Visitor<Label, Value> for Collect {
+            fn enter_node(&mut self, _label: Option<&Label>, depth: usize) {
+                self.0.max_depth = self.0.max_depth.max(depth);
+                self.1 = depth;
+            }
+
+            fn leaf(&mut self, _value: &Value) {
+                self.0.terminals += 1;
+                self.0.total_depth += self.1;
+            }
+        }
+        let mut collect = Collect(
+            Stats {
+                nodes: self.labels.len(),
+                louds_bits: 2 * self.labels.len() + 3,
+                ..Stats::default()
+            },
+            0,
+        );
+        self.visit(&mut collect);
+        collect.0
+    }
+}
+
+/// Estimate the heap size of a fid-rs bit vector with `bits` bits. This mirrors
+/// the layout of fid-rs: raw bits, chunks of _(log N)<sup>2</sup>_ bits, blocks
+/// of _(log N)/2_ bits, and a popcount table for one block.
+fn fid_heap_size(bits: u64) -> usize {
+    let lg2 = (bits as f64).log2() as u64;
+    let chunk_size = (lg2 * lg2).max(1);
+    let block_size = (lg2 / 2).max(1);
+    let chunks = ceil_div(bits, chunk_size);
+    let blocks = chunks * ceil_div(chunk_size, block_size);
+    // Chunk { value: u64, blocks: Blocks { Vec<Block>, u16 } }
+    let chunk_bytes = size_of::<u64>() + size_of::<Vec<()>>() + size_of::<u64>();
+    // Block { value: u16, length: u8 }
+    let block_bytes = size_of::<u32>();
+    (ceil_div(bits, 8)
+        + chunks * chunk_bytes as u64
+        + blocks * block_bytes as u64
+        + (1 << block_size)) as usize
+}
+
+fn ceil_div(n: u64, d: u64) -> u64 {
+    n / d + if n % d == 0 { 0 } else { 1 }
+}
+
+#[cfg(test)]
+mod stats_tests {
+    use crate::map::{Stats, Trie, TrieBuilder};
+
+    #[test]
+    fn stats() {
+        let trie = Trie::<u8, u8>::from_iter([("a", 0), ("app", 1), ("apple", 2), ("b", 3)]);
+        assert_eq!(
+            trie.stats(),
+            Stats {
+                nodes: 6,
+                terminals: 4,
+                max_depth: 5,
+                total_depth: 10,
+                louds_bits: 15,
+            }
+        );
+        assert_eq!(trie.stats().avg_depth(), 2.5);
+        let trie: Trie<u8, u8> = TrieBuilder::new().build();
+        assert_eq!(
+            trie.stats(),
+            Stats {
+                louds_bits: 3,
+                ..Stats::default()
+            }
+        );
+        assert_eq!(trie.stats().avg_depth(), 0.0);
+    }
+
+    #[test]
+    fn heap_size_bytes() {
+        let small = Trie::<u8, u8>::from_iter([("a", 0)]);
+        let large = Trie::<u8, u8>::from_iter([("a", 0), ("app", 1), ("apple", 2), ("b", 3)]);
+        assert!(small.heap_size_bytes() > 0);
+        assert!(small.heap_size_bytes() < large.heap_size_bytes());
+        let wide = Trie::<u8, [u8; 32]>::from_iter([("a", [0; 32])]);
+        assert!(small.heap_size_bytes() + 32 <= wide.heap_size_bytes());
+    }
+
+    #[test]
+    fn wide_values_out_of_line() {
+        // Ten keys of 100 labels have 1000 nodes but only 10 values.
+        let keys: Vec<Vec<u8>> = (0..10u8).map(|i| vec![i; 100]).collect();
+        let mut trie = Trie::<u8, [u8; 32]>::from_iter(keys.iter().map(|k| (k, [0; 32])));
+        trie.shrink_to_fit();
+        assert_eq!(trie.stats().nodes, 1000);
+        // Storing a value slot per node would take over 32,000 bytes.
+        assert!(trie.heap_size_bytes() < 1000 * 32 / 2);
+    }
+
+    #[test]
+    fn shrink_to_fit() {
+        let mut trie = Trie::<u8, u8>::from_iter([("a", 0), ("app", 1), ("apple", 2), ("b", 3)]);
+        let before = trie.heap_size_bytes();
+        trie.shrink_to_fit();
+        assert_eq!(trie.labels.capacity(), trie.labels.len());
+        assert_eq!(trie.values.capacity(), trie.values.len());
+        assert!(trie.heap_size_bytes() <= before);
+        assert_eq!(trie.exact_match("apple"), Some(&2));
+    }
+}
@@ -0,0 +1,170 @@
+//! A trie over reversed keys for "ends with" queries.
+use super::SuffixTrie;
+use crate::try_collect::{Collect, TryCollect, TryFromIterator};
+
+impl<Label: Ord + Clone, Value> SuffixTrie<Label, Value> {
+    /// Return `Some(&Value)` if `query` is an exact match.
+    pub fn exact_match(&self, query: impl AsRef<[Label]>) -> Option<&Value> {
+        self.0.exact_match(reversed(query))
+    }
+
+    /// Return all entries and their values that end with `suffix`.
+    ///
+    /// Keys are returned in their original, unreversed order. Results are
+    /// sorted by their reversed keys.
+    ///
+    /// ```rust
+    /// use trie_rs::map::TrieBuilder;
+    ///
+    /// let mut builder = TrieBuilder::new();
+    /// builder.push("apple", 0);
+    /// builder.push("maple", 1);
+    /// builder.push("app", 2);
+    /// let trie = builder.build_reversed();
+    ///
+    /// let results: Vec<(String, &u8)> = trie.suffix_predictive_search("ple").collect();
+    /// assert_eq!(results, [("maple".to_string(), &1), ("apple".to_string(), &0)]);
+    /// ```
+    pub fn suffix_predictive_search<C, M>(
+        &self,
+        suffix: impl AsRef<[Label]>,
+    ) -> impl Iterator<Item = (C, &Value)> + '_
+    where
+        C: TryFromIterator<Label, M>,
+    {
+        self.0
+            .predictive_search::<Vec<Label>, Collect>(reversed(suffix))
+            .map(|(key, value)| {
+                (
+                    key.into_iter()
+                        .rev()
+                        .try_collect()
+                        .expect("Could not collect"),
+                    value,
+                )
+            })
+    }
+
+    /// Return the longest entry that `query` ends with and its value, or
+    /// `None` if there is none, e.g., to match file extensions.
+    ///
+    /// ```rust
+    /// use trie_rs::map::TrieBuilder;
+    ///
+    /// let mut builder = TrieBuilder::new();
+    /// builder.push(".gz", "gzip");
+    /// builder.push(".tar.gz", "tarball");
+    /// let trie = builder.build_reversed();
+    ///
+    /// assert_eq!(trie.longest_suffix("src.tar.gz"), Some((".tar.gz".to_string(), &"tarball")));
+    /// assert_eq!(trie.longest_suffix("log.gz"), Some((".gz".to_string(), &"gzip")));
+    /// assert_eq!(trie.longest_suffix::<String, _>("src.zip"), None);
+    /// ```
+    pub fn longest_suffix<C, M>(&self, query: impl AsRef<[Label]>) -> Option<(C, &Value)>
+    where
+        C: TryFromIterator<Label, M>,
+    {
+        self.0
+            .longest_common_prefix::<Vec<Label>, Collect>(reversed(query))
+            .map(|(key, value)| {
+                (
+                    key.into_iter()
+                        .rev()
+                        .try_collect()
+                        .expect("Could not collect"),
+                    value,
+                )
+            })
+    }
+}
+
+fn reversed<Label: Clone>(query: impl AsRef<[Label]>) -> Vec<Label> {
+    query.as_ref().iter().rev().cloned().collect()
+}
+
+#[cfg(test)]
+mod search_tests {
+    use crate::map::{SuffixTrie, TrieBuilder};
+
+    fn build_trie() -> SuffixTrie<u8, u8> {
+        let mut builder = TrieBuilder::new();
+        builder.push("a", 0);
+        builder.push("app", 1);
+        builder.push("apple", 2);
+        builder.push("better", 3);
+        builder.push("application", 4);
+        builder.push("アップル🍎", 5);
+        builder.build_reversed()
+    }
+
+    #[test]
+    fn exact_match() {
+        let trie = build_trie();
+        assert_eq!(trie.exact_match("apple"), Some(&2));
+        assert_eq!(trie.exact_match("アップル🍎"), Some(&5));
+        assert_eq!(trie.exact_match("elppa"), None);
+    }
+
+    mod longest_suffix_tests {
+        use crate::map::TrieBuilder;
+
+        macro_rules! parameterized_tests {
+            ($($name:ident: $value:expr,)*) => {
+            $(
+                #[test]
+                fn $name() {
+                    let (query, expected_match): (&str, Option<(&str, u8)>) = $value;
+                    let mut builder = TrieBuilder::new();
+                    builder.push(".gz", 0);
+                    builder.push(".tar.gz", 1);
+                    builder.push(".tar", 2);
+                    builder.push(".tgz", 3);
+                    builder.push("z", 4);
+                    let trie = builder.build_reversed();
+                    let result: Option<(String, &u8)> = trie.longest_suffix(query);
+                    let expected_match = expected_match.map(|(s, v)| (s.to_string(), v));
+                    assert_eq!(result.map(|(s, v)| (s, *v)), expected_match);
+                }
+            )*
+            }
+        }
+
+        parameterized_tests! {
+            t1: ("src.tar.gz", Some((".tar.gz", 1))),
+            t2: ("log.gz", Some((".gz", 0))),
+            t3: ("src.tar", Some((".tar", 2))),
+            t4: ("src.tgz", Some((".tgz", 3))),
+            t5: ("fizz", Some(("z", 4))),
+            t6: ("src.zip", None),
+            t7: ("", None),
+            t8: (".tar.gz", Some((".tar.gz", 1))),
+        }
+    }
+
+    mod suffix_predictive_search_tests {
+        macro_rules! parameterized_tests {
+            ($($name:ident: $value:expr,)*) => {
+            $(
+                #[test]
+                fn $name() {
+                    let (query, expected_results) = $value;
+                    let trie = super::build_trie();
+                    let results: Vec<(String, &u8)> = trie.suffix_predictive_search(query).collect();
+                    let expected_results: Vec<(String, &u8)> = expected_results.iter().map(|s| (s.0.to_string(), &s.1)).collect();
+                    assert_eq!(results, expected_results);
+                }
+            )*
+            }
+        }
+
+        parameterized_tests! {
+            t1: ("le", vec![("apple", 2)]),
+            t2: ("p", vec![("app", 1)]),
+            t3: ("on", vec![("application", 4)]),
+            t4: ("a", vec![("a", 0)]),
+            t5: ("r", vec![("better", 3)]),
+            t6: ("x", Vec::<(&str, u8)>::new()),
+            t7: ("ル🍎", vec![("アップル🍎", 5)]),
+        }
+    }
+}
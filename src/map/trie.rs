@@ -1,26 +1,237 @@
 //! A trie map stores a value with each word or key.
-use super::Trie;
-use crate::inc_search::IncSearch;
-use crate::iter::{PostfixIter, PrefixIter, SearchIter};
+use super::{TerminalId, Trie};
+use crate::inc_search::{CommonPrefixMatcher, FuzzyIncSearch, IncSearch, Position};
+use crate::iter::{
+    AnnotatedPrefixIter, BfNodes, BoundedSearchIter, DfNodes, FindIter, FirstLabelGroups,
+    OrderedSearchIter, PostfixIter, PrefixIter, SearchCursor, SearchIter, SearchOrder,
+};
 use crate::try_collect::{TryCollect, TryFromIterator};
 use louds_rs::{AncestorNodeIter, ChildNodeIter, LoudsNodeNum};
+use std::borrow::Borrow;
+use std::collections::{BTreeMap, HashMap};
 use std::iter::FromIterator;
 
+// Node numbers are `u64` while labels and values are indexed by `usize`. There
+// is one label per node but the root, so a node number of a trie is at most
+// `labels.len() + 1`. With `usize` no wider than `u64`, converting a length to
+// a node number is lossless, and so is converting a node number less one or
+// two back to an index.
+const _: () = assert!(usize::BITS <= u64::BITS);
+
 impl<Label: Ord, Value> Trie<Label, Value> {
     /// Return `Some(&Value)` if query is an exact match.
+    ///
+    /// An empty `query` never matches, since the empty sequence cannot be an
+    /// entry.
     pub fn exact_match(&self, query: impl AsRef<[Label]>) -> Option<&Value> {
         self.exact_match_node(query)
             .and_then(move |x| self.value(x))
     }
 
+    /// Return the number of labels in `query` if it is an exact match, e.g.,
+    /// to slice the input that matched without measuring it again.
+    ///
+    /// ```rust
+    /// use trie_rs::map::Trie;
+    ///
+    /// let trie = Trie::from_iter([("café", 0), ("cafe", 1)]);
+    /// assert_eq!(trie.exact_match_len("café"), Some(5));
+    /// assert_eq!(trie.exact_match_len("caf"), None);
+    /// ```
+    pub fn exact_match_len(&self, query: impl AsRef<[Label]>) -> Option<usize> {
+        let query = query.as_ref();
+        self.exact_match_node(query).map(|_| query.len())
+    }
+
+    /// Return the stored key and its value if `query` is an exact match.
+    ///
+    /// The key is rebuilt from the trie's own labels, so where labels compare
+    /// equal without being identical, e.g., under a case-insensitive `Ord`, it
+    /// is the stored form rather than `query`.
+    ///
+    /// ```rust
+    /// use trie_rs::map::Trie;
+    ///
+    /// let trie = Trie::from_iter([("app", 0), ("apple", 1)]);
+    /// assert_eq!(trie.get_key_value::<String, _>("app"), Some(("app".to_string(), &0)));
+    /// assert_eq!(trie.get_key_value::<String, _>("appl"), None);
+    /// ```
+    pub fn get_key_value<C, M>(&self, query: impl AsRef<[Label]>) -> Option<(C, &Value)>
+    where
+        C: TryFromIterator<Label, M>,
+        Label: Clone,
+    {
+        let node_num = self.exact_match_node(query)?;
+        Some((self.node_key(node_num), self.value(node_num)?))
+    }
+
+    /// Return the number of labels in `key` if it is an entry or a prefix of
+    /// one.
+    ///
+    /// ```rust
+    /// use trie_rs::map::Trie;
+    ///
+    /// let trie = Trie::from_iter([("db/host", 0), ("db/port", 1)]);
+    /// assert_eq!(trie.depth_of("db/"), Some(3));
+    /// assert_eq!(trie.depth_of("db/port"), Some(7));
+    /// assert_eq!(trie.depth_of("db/user"), None);
+    /// ```
+    pub fn depth_of(&self, key: impl AsRef<[Label]>) -> Option<usize> {
+        let key = key.as_ref();
+        let node_num = self.prefix_node(key)?;
+        (self.is_terminal(node_num) || self.has_children_node_nums(node_num)).then_some(key.len())
+    }
+
+    /// Return true if `key` is an entry and no longer entry extends it.
+    ///
+    /// ```rust
+    /// use trie_rs::map::Trie;
+    ///
+    /// let trie = Trie::from_iter([("db", 0), ("db/port", 1)]);
+    /// assert!(trie.is_leaf("db/port"));
+    /// assert!(!trie.is_leaf("db"));
+    /// assert!(!trie.is_leaf("db/"));
+    /// assert!(!trie.is_leaf("web"));
+    /// ```
+    pub fn is_leaf(&self, key: impl AsRef<[Label]>) -> bool {
+        self.exact_match_node(key)
+            .map_or(false, |node_num| !self.has_children_node_nums(node_num))
+    }
+
+    /// Return the deepest node on the paths to both entries `a` and `b`, or
+    /// `None` if either is not an entry. The root is their lowest common
+    /// node if they share no prefix.
+    ///
+    /// ```rust
+    /// use trie_rs::map::Trie;
+    ///
+    /// let trie = Trie::from_iter([("apple", 0), ("apply", 1), ("banana", 2)]);
+    /// let node = trie.lcp_node("apple", "apply").unwrap();
+    /// let search = trie.inc_search_from(node);
+    /// assert_eq!(search.prefix::<String, _>(), "appl");
+    /// assert_eq!(trie.lcp_node("apple", "app"), None);
+    /// ```
+    pub fn lcp_node(&self, a: impl AsRef<[Label]>, b: impl AsRef<[Label]>) -> Option<Position> {
+        let (a, b) = (a.as_ref(), b.as_ref());
+        let mut a_node = self.exact_match_node(a)?;
+        let mut b_node = self.exact_match_node(b)?;
+        // A node's depth is the length of its key, so climb the deeper one
+        // to the other's depth, then both until the paths meet.
+        for _ in b.len()..a.len() {
+            a_node = self.parent(a_node)?;
+        }
+        for _ in a.len()..b.len() {
+            b_node = self.parent(b_node)?;
+        }
+        while a_node != b_node {
+            a_node = self.parent(a_node)?;
+            b_node = self.parent(b_node)?;
+        }
+        Some(a_node)
+    }
+
+    /// Return the length of the longest common prefix of entries `a` and
+    /// `b`, or `None` if either is not an entry. See [Trie::lcp_node].
+    ///
+    /// ```rust
+    /// use trie_rs::map::Trie;
+    ///
+    /// let trie = Trie::from_iter([("apple", 0), ("apply", 1), ("banana", 2)]);
+    /// assert_eq!(trie.common_prefix_len("apple", "apply"), Some(4));
+    /// assert_eq!(trie.common_prefix_len("apple", "banana"), Some(0));
+    /// assert_eq!(trie.common_prefix_len("apple", "apricot"), None);
+    /// ```
+    pub fn common_prefix_len(
+        &self,
+        a: impl AsRef<[Label]>,
+        b: impl AsRef<[Label]>,
+    ) -> Option<usize> {
+        let (a, b) = (a.as_ref(), b.as_ref());
+        self.lcp_node(a, b)?;
+        Some(a.iter().zip(b).take_while(|(x, y)| x == y).count())
+    }
+
+    /// Return `Some(&Value)` for each query that is an exact match, in order.
+    ///
+    /// The buffer of child nodes used while descending is shared by all the
+    /// queries rather than allocated per label.
+    ///
+    /// ```rust
+    /// use trie_rs::map::Trie;
+    ///
+    /// let trie = Trie::from_iter([("a", 0), ("app", 1), ("apple", 2)]);
+    /// let results: Vec<Option<&u8>> = trie.exact_match_many(["app", "ap", "apple"]).collect();
+    /// assert_eq!(results, [Some(&1), None, Some(&2)]);
+    /// ```
+    pub fn exact_match_many<'a, Q>(
+        &'a self,
+        queries: impl IntoIterator<Item = Q> + 'a,
+    ) -> impl Iterator<Item = Option<&'a Value>> + 'a
+    where
+        Q: AsRef<[Label]> + 'a,
+    {
+        let mut children_node_nums = Vec::new();
+        queries.into_iter().map(move |query| {
+            self.exact_match_node_in(query, &mut children_node_nums)
+                .and_then(|x| self.value(x))
+        })
+    }
+
+    /// Return the sequence number of the push that set `key`'s value, if
+    /// `key` is an entry and the trie was built
+    /// [with insertion order](super::TrieBuilder::with_insertion_order).
+    pub fn insertion_index(&self, key: impl AsRef<[Label]>) -> Option<u64> {
+        self.exact_match_node(key)
+            .and_then(|node_num| self.order(node_num))
+    }
+
+    /// Return true if every key is an exact match, stopping at the first that
+    /// is not. True for no keys.
+    ///
+    /// ```rust
+    /// use trie_rs::map::Trie;
+    ///
+    /// let trie = Trie::from_iter([("a", 0), ("app", 1), ("apple", 2)]);
+    /// assert!(trie.contains_all(["a", "apple"]));
+    /// assert!(!trie.contains_all(["a", "ap"]));
+    /// ```
+    pub fn contains_all<Q: AsRef<[Label]>>(&self, keys: impl IntoIterator<Item = Q>) -> bool {
+        self.exact_match_many(keys).all(|value| value.is_some())
+    }
+
+    /// Return true if any key is an exact match, stopping at the first that
+    /// is. False for no keys.
+    ///
+    /// ```rust
+    /// use trie_rs::map::Trie;
+    ///
+    /// let trie = Trie::from_iter([("a", 0), ("app", 1), ("apple", 2)]);
+    /// assert!(trie.contains_any(["ap", "apple"]));
+    /// assert!(!trie.contains_any(["ap", "b"]));
+    /// ```
+    pub fn contains_any<Q: AsRef<[Label]>>(&self, keys: impl IntoIterator<Item = Q>) -> bool {
+        self.exact_match_many(keys).any(|value| value.is_some())
+    }
+
     /// Return `Node` if query is an exact match.
     #[inline]
-    fn exact_match_node(&self, query: impl AsRef<[Label]>) -> Option<LoudsNodeNum> {
+    pub(crate) fn exact_match_node(&self, query: impl AsRef<[Label]>) -> Option<LoudsNodeNum> {
+        self.exact_match_node_in(query, &mut Vec::new())
+    }
+
+    /// Return `Node` if query is an exact match using `children_node_nums` as
+    /// scratch space.
+    #[inline]
+    fn exact_match_node_in(
+        &self,
+        query: impl AsRef<[Label]>,
+        children_node_nums: &mut Vec<LoudsNodeNum>,
+    ) -> Option<LoudsNodeNum> {
         let mut cur_node_num = LoudsNodeNum(1);
 
         for (i, chr) in query.as_ref().iter().enumerate() {
-            let children_node_nums: Vec<LoudsNodeNum> =
-                self.children_node_nums(cur_node_num).collect();
+            children_node_nums.clear();
+            children_node_nums.extend(self.children_node_nums(cur_node_num));
             let res = self.bin_search_by_children_labels(chr, &children_node_nums[..]);
 
             match res {
@@ -43,12 +254,109 @@ impl<Label: Ord, Value> Trie<Label, Value> {
             .and_then(move |x| self.value_mut(x))
     }
 
+    /// Apply `f` to the value of every entry that is a prefix of `query`,
+    /// from shortest to longest, e.g., to count every prefix of a token
+    /// sequence in one pass.
+    ///
+    /// This is a single descent that builds no keys and allocates nothing.
+    ///
+    /// ```rust
+    /// use trie_rs::map::Trie;
+    ///
+    /// let mut trie = Trie::from_iter([("a", 0), ("app", 0), ("apple", 0), ("b", 0)]);
+    /// trie.bump_prefixes("apply", |count| *count += 1);
+    /// assert_eq!(trie.exact_match("a"), Some(&1));
+    /// assert_eq!(trie.exact_match("app"), Some(&1));
+    /// assert_eq!(trie.exact_match("apple"), Some(&0));
+    /// ```
+    pub fn bump_prefixes(&mut self, query: impl AsRef<[Label]>, mut f: impl FnMut(&mut Value)) {
+        let mut node_num = LoudsNodeNum(1);
+        for chr in query.as_ref() {
+            node_num = match self.child_by_label(node_num, chr) {
+                Some(child) => child,
+                None => return,
+            };
+            if let Some(value) = self.value_mut(node_num) {
+                f(value);
+            }
+        }
+    }
+
     /// Create an incremental search. Useful for interactive applications. See
     /// [crate::inc_search] for details.
     pub fn inc_search(&self) -> IncSearch<'_, Label, Value> {
         IncSearch::new(self)
     }
 
+    /// Create an incremental search starting at `position`, e.g., a cached
+    /// [IncSearch::position], without descending to it again.
+    ///
+    /// # Panics
+    /// In debug builds, if `position` is not a node of this trie. See
+    /// [IncSearch::resume].
+    ///
+    /// ```rust
+    /// use trie_rs::map::Trie;
+    ///
+    /// let trie = Trie::from_iter([("https://a.com", 0), ("https://b.org", 1)]);
+    /// let mut search = trie.inc_search();
+    /// search.query_until("https://").unwrap();
+    /// let https = search.position();
+    ///
+    /// let mut search = trie.inc_search_from(https);
+    /// search.query_until("b.org").unwrap();
+    /// assert_eq!(search.value(), Some(&1));
+    /// ```
+    pub fn inc_search_from(&self, position: Position) -> IncSearch<'_, Label, Value> {
+        IncSearch::resume(self, position)
+    }
+
+    /// Create an incremental search that tolerates up to `max_distance`
+    /// edits. See [FuzzyIncSearch].
+    pub fn fuzzy_inc_search(&self, max_distance: usize) -> FuzzyIncSearch<'_, Label, Value> {
+        FuzzyIncSearch::new(self, max_distance)
+    }
+
+    /// Create a [Trie::common_prefix_search] that is fed one label at a time.
+    /// See [CommonPrefixMatcher].
+    pub fn common_prefix_matcher(&self) -> CommonPrefixMatcher<'_, Label, Value> {
+        CommonPrefixMatcher::new(self)
+    }
+
+    /// Return `Some(&Value)` if query is an exact match, comparing a borrowed
+    /// form of each label like [BTreeMap::get].
+    ///
+    /// This avoids building owned labels for a query, e.g., a
+    /// `Trie<String, _>` may be queried with `&[&str]`.
+    ///
+    /// ```rust
+    /// use trie_rs::map::Trie;
+    ///
+    /// let path = |p: &str| p.split('/').map(String::from).collect::<Vec<_>>();
+    /// let trie = Trie::from_iter([(path("usr/bin"), 0), (path("usr/lib"), 1)]);
+    /// assert_eq!(trie.exact_match_borrowed::<str>(&["usr", "lib"]), Some(&1));
+    /// assert_eq!(trie.exact_match_borrowed::<str>(&["usr"]), None);
+    /// ```
+    pub fn exact_match_borrowed<Q>(&self, query: &[&Q]) -> Option<&Value>
+    where
+        Label: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.prefix_node_borrowed(query)
+            .and_then(|node_num| self.value(node_num))
+    }
+
+    /// Return true if `query` is a prefix, comparing a borrowed form of each
+    /// label. See [Trie::exact_match_borrowed].
+    pub fn is_prefix_borrowed<Q>(&self, query: &[&Q]) -> bool
+    where
+        Label: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.prefix_node_borrowed(query)
+            .map_or(false, |node_num| self.has_children_node_nums(node_num))
+    }
+
     /// Return true if `query` is a prefix.
     ///
     /// Note: A prefix may be an exact match or not, and an exact match may be a
@@ -68,7 +376,114 @@ impl<Label: Ord, Value> Trie<Label, Value> {
         self.has_children_node_nums(cur_node_num)
     }
 
+    /// Return true if any entry is a prefix of `query`, i.e., if
+    /// [Trie::common_prefix_search] would return anything. Stops at the first
+    /// entry found.
+    pub fn has_common_prefix(&self, query: impl AsRef<[Label]>) -> bool {
+        let mut cur_node_num = LoudsNodeNum(1);
+        for chr in query.as_ref().iter() {
+            let children_node_nums: Vec<_> = self.children_node_nums(cur_node_num).collect();
+            match self.bin_search_by_children_labels(chr, &children_node_nums[..]) {
+                Ok(j) => cur_node_num = children_node_nums[j],
+                Err(_) => return false,
+            }
+            if self.is_terminal(cur_node_num) {
+                return true;
+            }
+        }
+        false
+    }
+
+    /// Return the labels that follow `key` in some entry in order, whether
+    /// or not `key` is itself an entry. Empty if no entry starts with `key`.
+    ///
+    /// ```rust
+    /// use trie_rs::map::Trie;
+    ///
+    /// let trie = Trie::from_iter([("/api/users", 0), ("/api/items", 1), ("/about", 2)]);
+    /// let labels: Vec<&u8> = trie.children_labels_at("/a").collect();
+    /// assert_eq!(labels, [&b'b', &b'p']);
+    /// assert_eq!(trie.children_labels_at("/x").count(), 0);
+    /// ```
+    pub fn children_labels_at(&self, key: impl AsRef<[Label]>) -> impl Iterator<Item = &Label> {
+        self.prefix_node(key).into_iter().flat_map(move |node_num| {
+            self.children_node_nums(node_num)
+                .map(move |child| self.label(child))
+        })
+    }
+
+    /// Return each label that follows `prefix` like
+    /// [Trie::children_labels_at], with whether `prefix` followed by that
+    /// label is an entry and how many labels can follow it in turn, e.g., to
+    /// reveal a tree of completions one level at a time. Empty if no entry
+    /// starts with `prefix`.
+    ///
+    /// ```rust
+    /// use trie_rs::map::Trie;
+    ///
+    /// let trie = Trie::from_iter([("src", 0), ("src/a", 1), ("src/b", 2), ("sx", 3)]);
+    /// let level: Vec<(&u8, bool, usize)> = trie.children_summary("s").collect();
+    /// assert_eq!(level, [(&b'r', false, 1), (&b'x', true, 0)]);
+    /// let level: Vec<(&u8, bool, usize)> = trie.children_summary("sr").collect();
+    /// assert_eq!(level, [(&b'c', true, 1)]);
+    /// ```
+    pub fn children_summary(
+        &self,
+        prefix: impl AsRef<[Label]>,
+    ) -> impl Iterator<Item = (&Label, bool, usize)> {
+        self.prefix_node(prefix)
+            .into_iter()
+            .flat_map(move |node_num| {
+                self.children_node_nums(node_num).map(move |child| {
+                    (
+                        self.label(child),
+                        self.is_terminal(child),
+                        self.children_node_nums(child).count(),
+                    )
+                })
+            })
+    }
+
+    /// Return true if any entry starts with `prefix`, i.e., if
+    /// [Trie::predictive_search] would return anything.
+    pub fn has_completion(&self, prefix: impl AsRef<[Label]>) -> bool {
+        // Every node but the root leads to at least one entry.
+        match self.prefix_node(prefix) {
+            Some(LoudsNodeNum(1)) => self.has_children_node_nums(LoudsNodeNum(1)),
+            Some(_) => true,
+            None => false,
+        }
+    }
+
+    /// Return true if no entry is a prefix of another entry.
+    ///
+    /// ```rust
+    /// use trie_rs::map::Trie;
+    ///
+    /// assert!(!Trie::from_iter([("a", 0), ("app", 1)]).is_prefix_free());
+    /// assert!(Trie::from_iter([("app", 0), ("bat", 1)]).is_prefix_free());
+    /// ```
+    pub fn is_prefix_free(&self) -> bool {
+        // Every subtree holds an entry, so an entry is a prefix of another
+        // exactly when its node has children.
+        (2..=self.last_node_num().0)
+            .map(LoudsNodeNum)
+            .all(|node_num| !self.is_terminal(node_num) || !self.has_children_node_nums(node_num))
+    }
+
     /// Return all entries and their values that match `query`.
+    ///
+    /// Entries are yielded in ascending lexicographic order of their `Label`
+    /// sequences, the same order as a `BTreeMap<Vec<Label>, _>`. For `u8`
+    /// labels of UTF-8 strings, byte order agrees with `str`'s order.
+    ///
+    /// Each key is collected into a new `C`. To avoid allocating a key per
+    /// entry, see [Trie::predictive_search_cursor].
+    ///
+    /// An empty `query` yields every entry.
+    ///
+    /// # Panics
+    /// If a key cannot be collected into `C`; see [Trie::try_predictive_search].
     pub fn predictive_search<C, M>(
         &self,
         query: impl AsRef<[Label]>,
@@ -80,91 +495,513 @@ impl<Label: Ord, Value> Trie<Label, Value> {
         SearchIter::new(self, query)
     }
 
-    /// Return the postfixes and values of all entries that match `query`.
-    pub fn postfix_search<C, M>(
+    /// Return all entries and their values that match `query` in `order`,
+    /// e.g., [SearchOrder::ByLength] to show the shortest completions first.
+    ///
+    /// ```rust
+    /// use trie_rs::iter::SearchOrder;
+    /// use trie_rs::map::Trie;
+    ///
+    /// let trie = Trie::from_iter([("app", 0), ("apple", 1), ("apt", 2), ("ape", 3)]);
+    /// let results: Vec<(String, &u8)> = trie
+    ///     .predictive_search_ordered("ap", SearchOrder::ByLength)
+    ///     .collect();
+    /// let keys: Vec<&str> = results.iter().map(|(key, _)| key.as_str()).collect();
+    /// assert_eq!(keys, ["ape", "app", "apt", "apple"]);
+    /// ```
+    pub fn predictive_search_ordered<C, M>(
         &self,
         query: impl AsRef<[Label]>,
-    ) -> PostfixIter<'_, Label, Value, C, M>
+        order: SearchOrder,
+    ) -> OrderedSearchIter<'_, Label, Value, C, M>
     where
-        C: TryFromIterator<Label, M>,
+        C: TryFromIterator<Label, M> + Clone,
         Label: Clone,
     {
-        let mut cur_node_num = LoudsNodeNum(1);
+        OrderedSearchIter::new(self, query, order)
+    }
 
-        // Consumes query (prefix)
-        for chr in query.as_ref() {
-            let children_node_nums: Vec<_> = self.children_node_nums(cur_node_num).collect();
-            let res = self.bin_search_by_children_labels(chr, &children_node_nums[..]);
-            match res {
-                Ok(i) => cur_node_num = children_node_nums[i],
-                Err(_) => {
-                    return PostfixIter::empty(self);
-                }
-            }
-        }
+    /// Return all entries and their values that match `query` in lexicographic
+    /// order except `query` itself, i.e., only completions strictly longer
+    /// than `query`.
+    ///
+    /// ```rust
+    /// use trie_rs::map::Trie;
+    ///
+    /// let trie = Trie::from_iter([("a", 0), ("app", 1), ("apple", 2)]);
+    /// let results: Vec<(String, &u8)> = trie.predictive_search_strict("app").collect();
+    /// assert_eq!(results, [("apple".to_string(), &2)]);
+    /// ```
+    pub fn predictive_search_strict<C, M>(
+        &self,
+        query: impl AsRef<[Label]>,
+    ) -> BoundedSearchIter<'_, Label, Value, C, M>
+    where
+        C: TryFromIterator<Label, M>,
+        Label: Clone,
+    {
+        BoundedSearchIter::new(self, query, usize::MAX).min_depth(1)
+    }
 
-        PostfixIter::new(self, cur_node_num)
+    /// Return all entries and their values that match `query` in lexicographic
+    /// order, lending each key as a slice of one reusable buffer.
+    ///
+    /// [Trie::predictive_search] collects every key into a new `C`, since a
+    /// key's labels are not stored contiguously and cannot be borrowed. When
+    /// each key is only needed briefly, this avoids those allocations.
+    ///
+    /// ```rust
+    /// use trie_rs::map::Trie;
+    ///
+    /// let trie = Trie::from_iter([("a", 0), ("app", 1), ("apple", 2), ("better", 3)]);
+    /// let mut cursor = trie.predictive_search_cursor("app");
+    /// let mut total = 0;
+    /// while let Some((key, value)) = cursor.next_entry() {
+    ///     assert!(key.starts_with(b"app"));
+    ///     total += value;
+    /// }
+    /// assert_eq!(total, 3);
+    /// ```
+    pub fn predictive_search_cursor(
+        &self,
+        query: impl AsRef<[Label]>,
+    ) -> SearchCursor<'_, Label, Value>
+    where
+        Label: Clone,
+    {
+        SearchCursor::new(self, query)
     }
 
-    /// Returns an iterator across all keys in the trie.
+    /// Return all entries and their values that match `query` in lexicographic
+    /// order while holding no more than `max_frontier` nodes, which makes its
+    /// memory use predictable on adversarially deep tries.
     ///
-    /// # Examples
-    /// In the following example we illustrate how to iterate over all keys in the trie.
-    /// Note that the order of the keys is not guaranteed, as they will be returned in
-    /// lexicographical order.
+    /// Entries more than `max_frontier` labels longer than `query` are skipped;
+    /// [BoundedSearchIter::is_truncated] reports whether any were.
     ///
     /// ```rust
     /// use trie_rs::map::Trie;
-    /// let trie = Trie::from_iter([("a", 0), ("app", 1), ("apple", 2), ("better", 3), ("application", 4)]);
-    /// let results: Vec<(String, &u8)> = trie.iter().collect();
-    /// assert_eq!(results, [("a".to_string(), &0u8), ("app".to_string(), &1u8), ("apple".to_string(), &2u8), ("application".to_string(), &4u8), ("better".to_string(), &3u8)]);
+    ///
+    /// let trie = Trie::from_iter([("a", 0), ("app", 1), ("apple", 2)]);
+    /// let mut iter = trie.predictive_search_bounded::<String, _>("a", 2);
+    /// let results: Vec<_> = iter.by_ref().collect();
+    /// assert_eq!(results, [("a".to_string(), &0), ("app".to_string(), &1)]);
+    /// assert!(iter.is_truncated());
     /// ```
-    pub fn iter<C, M>(&self) -> PostfixIter<'_, Label, Value, C, M>
+    pub fn predictive_search_bounded<C, M>(
+        &self,
+        query: impl AsRef<[Label]>,
+        max_frontier: usize,
+    ) -> BoundedSearchIter<'_, Label, Value, C, M>
     where
         C: TryFromIterator<Label, M>,
         Label: Clone,
     {
-        self.postfix_search([])
+        BoundedSearchIter::new(self, query, max_frontier)
     }
 
-    /// Return the common prefixes of `query`.
-    pub fn common_prefix_search<C, M>(
+    /// Return all entries whose keys have exactly `len` labels in
+    /// lexicographic order. Branches deeper than `len` are never visited.
+    pub fn keys_of_len<C, M>(&self, len: usize) -> BoundedSearchIter<'_, Label, Value, C, M>
+    where
+        C: TryFromIterator<Label, M>,
+        Label: Clone,
+    {
+        self.keys_in_len_range(len, len)
+    }
+
+    /// Return all entries whose keys have from `min` to `max` labels, inclusive,
+    /// in lexicographic order. Branches deeper than `max` are never visited.
+    ///
+    /// ```rust
+    /// use trie_rs::map::Trie;
+    ///
+    /// let trie = Trie::from_iter([("a", 0), ("app", 1), ("apple", 2), ("bet", 3)]);
+    /// let results: Vec<(String, &u8)> = trie.keys_in_len_range(2, 4).collect();
+    /// assert_eq!(results, [("app".to_string(), &1), ("bet".to_string(), &3)]);
+    /// ```
+    pub fn keys_in_len_range<C, M>(
         &self,
-        query: impl AsRef<[Label]>,
-    ) -> PrefixIter<'_, Label, Value, C, M>
+        min: usize,
+        max: usize,
+    ) -> BoundedSearchIter<'_, Label, Value, C, M>
     where
         C: TryFromIterator<Label, M>,
         Label: Clone,
     {
-        PrefixIter::new(self, query)
+        BoundedSearchIter::new(self, [], max).min_depth(min)
     }
 
-    /// Return the longest shared prefix or terminal of `query`.
-    pub fn longest_prefix<C, M>(&self, query: impl AsRef<[Label]>) -> Option<C>
+    /// Return the postfixes and values of all entries that match `query`.
+    ///
+    /// An empty `query` yields every entry whole, since each is its own
+    /// postfix.
+    ///
+    /// # Panics
+    /// If a key cannot be collected into `C`; see [Trie::try_postfix_search].
+    pub fn postfix_search<C, M>(
+        &self,
+        query: impl AsRef<[Label]>,
+    ) -> PostfixIter<'_, Label, Value, C, M>
     where
         C: TryFromIterator<Label, M>,
         Label: Clone,
     {
         let mut cur_node_num = LoudsNodeNum(1);
-        let mut buffer = Vec::new();
 
         // Consumes query (prefix)
         for chr in query.as_ref() {
             let children_node_nums: Vec<_> = self.children_node_nums(cur_node_num).collect();
             let res = self.bin_search_by_children_labels(chr, &children_node_nums[..]);
             match res {
-                Ok(i) => {
-                    cur_node_num = children_node_nums[i];
-                    buffer.push(cur_node_num);
-                }
+                Ok(i) => cur_node_num = children_node_nums[i],
                 Err(_) => {
-                    return None;
+                    return PostfixIter::empty(self);
                 }
             }
         }
 
-        // Walk the trie as long as there is only one path and it isn't a terminal value.
-        while !self.is_terminal(cur_node_num) {
+        PostfixIter::new(self, cur_node_num)
+    }
+
+    /// Return the values of all entries that [Trie::postfix_search] would
+    /// return for `query`, in the same order, without building any keys.
+    ///
+    /// ```rust
+    /// use trie_rs::map::Trie;
+    ///
+    /// let trie = Trie::from_iter([("fruit/apple", 3), ("fruit/pear", 4), ("veg/kale", 5)]);
+    /// let values: Vec<&u8> = trie.postfix_values("fruit/").collect();
+    /// assert_eq!(values, [&3, &4]);
+    /// ```
+    pub fn postfix_values(&self, query: impl AsRef<[Label]>) -> impl Iterator<Item = &Value> {
+        let mut stack: Vec<_> = match self.prefix_node(query) {
+            Some(node_num) => self.children_node_nums(node_num).rev().collect(),
+            None => vec![],
+        };
+        std::iter::from_fn(move || {
+            while let Some(node_num) = stack.pop() {
+                stack.extend(self.children_node_nums(node_num).rev());
+                if let Some(value) = self.value(node_num) {
+                    return Some(value);
+                }
+            }
+            None
+        })
+    }
+
+    /// Return an id for each entry that [Trie::predictive_search] would
+    /// return for `query`, in the same order. Look up or change a value by its
+    /// id with [Trie::value_by_id] and [Trie::value_by_id_mut].
+    ///
+    /// ```rust
+    /// use trie_rs::map::Trie;
+    ///
+    /// let mut trie = Trie::from_iter([("a", 0), ("app", 1), ("apple", 2), ("b", 3)]);
+    /// let ids = trie.predictive_search_indices("app");
+    /// for (i, id) in ids.into_iter().enumerate() {
+    ///     *trie.value_by_id_mut(id) += 10 * i as u8;
+    /// }
+    /// assert_eq!(trie.exact_match("app"), Some(&1));
+    /// assert_eq!(trie.exact_match("apple"), Some(&12));
+    /// ```
+    pub fn predictive_search_indices(&self, query: impl AsRef<[Label]>) -> Vec<TerminalId> {
+        let mut stack: Vec<_> = self.prefix_node(query).into_iter().collect();
+        let mut ids = vec![];
+        while let Some(node_num) = stack.pop() {
+            if self.is_terminal(node_num) {
+                ids.push(TerminalId(node_num));
+            }
+            stack.extend(self.children_node_nums(node_num).rev());
+        }
+        ids
+    }
+
+    /// Return the value of the entry `id`.
+    ///
+    /// # Panics
+    /// If `id` is not an entry of this trie.
+    pub fn value_by_id(&self, id: TerminalId) -> &Value {
+        self.value(id.0)
+            .expect("TerminalId is not an entry of this trie")
+    }
+
+    /// Return the value of the entry `id` to change it.
+    ///
+    /// # Panics
+    /// If `id` is not an entry of this trie.
+    pub fn value_by_id_mut(&mut self, id: TerminalId) -> &mut Value {
+        self.value_mut(id.0)
+            .expect("TerminalId is not an entry of this trie")
+    }
+
+    /// Return the entries grouped by their first label in lexicographic
+    /// order, one group per child of the root, e.g., to process shards in
+    /// parallel. Each group is the [Trie::predictive_search] of its label.
+    ///
+    /// ```rust
+    /// use trie_rs::map::Trie;
+    ///
+    /// let trie = Trie::from_iter([("a", 0), ("app", 1), ("b", 2)]);
+    /// let groups: Vec<(&u8, Vec<(String, &u8)>)> = trie
+    ///     .group_by_first()
+    ///     .map(|(label, group)| (label, group.collect()))
+    ///     .collect();
+    /// assert_eq!(
+    ///     groups,
+    ///     [
+    ///         (&b'a', vec![("a".to_string(), &0), ("app".to_string(), &1)]),
+    ///         (&b'b', vec![("b".to_string(), &2)]),
+    ///     ]
+    /// );
+    /// ```
+    pub fn group_by_first<C, M>(&self) -> FirstLabelGroups<'_, Label, Value, C, M>
+    where
+        C: TryFromIterator<Label, M> + Clone,
+        Label: Clone,
+    {
+        FirstLabelGroups::new(self)
+    }
+
+    /// Returns an iterator across all keys in the trie.
+    ///
+    /// # Examples
+    /// In the following example we illustrate how to iterate over all keys in the trie.
+    /// Note that the order of the keys is not guaranteed, as they will be returned in
+    /// lexicographical order.
+    ///
+    /// ```rust
+    /// use trie_rs::map::Trie;
+    /// let trie = Trie::from_iter([("a", 0), ("app", 1), ("apple", 2), ("better", 3), ("application", 4)]);
+    /// let results: Vec<(String, &u8)> = trie.iter().collect();
+    /// assert_eq!(results, [("a".to_string(), &0u8), ("app".to_string(), &1u8), ("apple".to_string(), &2u8), ("application".to_string(), &4u8), ("better".to_string(), &3u8)]);
+    /// ```
+    pub fn iter<C, M>(&self) -> PostfixIter<'_, Label, Value, C, M>
+    where
+        C: TryFromIterator<Label, M>,
+        Label: Clone,
+    {
+        self.postfix_search([])
+    }
+
+    /// Return every node but the root in breadth-first order.
+    ///
+    /// ```rust
+    /// use trie_rs::map::Trie;
+    ///
+    /// let trie = Trie::from_iter([("ab", 0), ("b", 1)]);
+    /// let nodes: Vec<(u8, usize, bool)> = trie
+    ///     .bf_nodes()
+    ///     .map(|node| (*node.label(), node.depth(), node.is_terminal()))
+    ///     .collect();
+    /// assert_eq!(nodes, [(b'a', 1, false), (b'b', 1, true), (b'b', 2, true)]);
+    /// ```
+    pub fn bf_nodes(&self) -> BfNodes<'_, Label, Value> {
+        BfNodes::new(self)
+    }
+
+    /// Return every node but the root in depth-first order, which is the
+    /// lexicographic order of their keys.
+    ///
+    /// ```rust
+    /// use trie_rs::map::Trie;
+    ///
+    /// let trie = Trie::from_iter([("ab", 0), ("b", 1)]);
+    /// let nodes: Vec<(u8, usize, Option<&u8>)> = trie
+    ///     .df_nodes()
+    ///     .map(|node| (*node.label(), node.depth(), node.value()))
+    ///     .collect();
+    /// assert_eq!(nodes, [(b'a', 1, None), (b'b', 2, Some(&0)), (b'b', 1, Some(&1))]);
+    /// ```
+    pub fn df_nodes(&self) -> DfNodes<'_, Label, Value> {
+        DfNodes::new(self)
+    }
+
+    /// Return the common prefixes of `query`.
+    ///
+    /// An empty `query` yields nothing, since its only prefix is the empty
+    /// sequence, which cannot be an entry.
+    ///
+    /// # Panics
+    /// If a key cannot be collected into `C`; see [Trie::try_common_prefix_search].
+    pub fn common_prefix_search<C, M>(
+        &self,
+        query: impl AsRef<[Label]>,
+    ) -> PrefixIter<'_, Label, Value, C, M>
+    where
+        C: TryFromIterator<Label, M>,
+        Label: Clone,
+    {
+        PrefixIter::new(self, query)
+    }
+
+    /// Return the common prefixes of `query` and the number of labels of
+    /// `query` consumed by the longest one, so `&query[consumed..]` is the
+    /// unmatched remainder. If no entry is a prefix of `query`, `consumed` is
+    /// zero.
+    ///
+    /// ```rust
+    /// use trie_rs::map::Trie;
+    ///
+    /// let trie = Trie::from_iter([("/", 0), ("/users", 1), ("/users/new", 2)]);
+    /// let query = "/users/42";
+    /// let (iter, consumed) = trie.common_prefix_search_with_rest::<String, _>(query);
+    /// assert_eq!(iter.count(), 2);
+    /// assert_eq!(&query[consumed..], "/42");
+    /// ```
+    pub fn common_prefix_search_with_rest<C, M>(
+        &self,
+        query: impl AsRef<[Label]>,
+    ) -> (PrefixIter<'_, Label, Value, C, M>, usize)
+    where
+        C: TryFromIterator<Label, M>,
+        Label: Clone,
+    {
+        let query = query.as_ref();
+        let mut consumed = 0;
+        let mut node_num = LoudsNodeNum(1);
+        for (i, chr) in query.iter().enumerate() {
+            let children_node_nums: Vec<_> = self.children_node_nums(node_num).collect();
+            match self.bin_search_by_children_labels(chr, &children_node_nums[..]) {
+                Ok(j) => node_num = children_node_nums[j],
+                Err(_) => break,
+            }
+            if self.is_terminal(node_num) {
+                consumed = i + 1;
+            }
+        }
+        (PrefixIter::new(self, query), consumed)
+    }
+
+    /// Return the common prefixes of `query` with whether each one is also a
+    /// prefix of longer entries, e.g., to tell a word that continues from a
+    /// leaf.
+    ///
+    /// ```rust
+    /// use trie_rs::map::Trie;
+    ///
+    /// let trie = Trie::from_iter([("a", 0), ("app", 1), ("application", 2), ("apple", 3)]);
+    /// let results: Vec<(String, &u8, bool)> =
+    ///     trie.common_prefix_search_annotated("application").collect();
+    /// assert_eq!(
+    ///     results,
+    ///     [
+    ///         ("a".to_string(), &0, true),
+    ///         ("app".to_string(), &1, true),
+    ///         ("application".to_string(), &2, false),
+    ///     ]
+    /// );
+    /// ```
+    pub fn common_prefix_search_annotated<C, M>(
+        &self,
+        query: impl AsRef<[Label]>,
+    ) -> AnnotatedPrefixIter<'_, Label, Value, C, M>
+    where
+        C: TryFromIterator<Label, M>,
+        Label: Clone,
+    {
+        AnnotatedPrefixIter::new(self, query)
+    }
+
+    /// Return each entry that is a proper prefix of `key`, from shortest to
+    /// longest, if `key` itself is an entry. Otherwise return nothing.
+    ///
+    /// Unlike [Trie::common_prefix_search], `key` must be in the trie and is
+    /// not among the results.
+    ///
+    /// ```rust
+    /// use trie_rs::map::Trie;
+    ///
+    /// let trie = Trie::from_iter([("/", 0), ("/usr", 1), ("/usr/bin", 2), ("/usr/b", 3)]);
+    /// let rules: Vec<(String, &u8)> = trie.prefixes_of("/usr/bin").collect();
+    /// assert_eq!(rules, [("/".into(), &0), ("/usr".into(), &1), ("/usr/b".into(), &3)]);
+    /// assert_eq!(trie.prefixes_of::<String, _>("/usr/lib").count(), 0);
+    /// ```
+    pub fn prefixes_of<C, M>(&self, key: impl AsRef<[Label]>) -> std::vec::IntoIter<(C, &Value)>
+    where
+        C: TryFromIterator<Label, M>,
+        Label: Clone,
+    {
+        let key = key.as_ref();
+        let mut found = vec![];
+        let mut node_num = LoudsNodeNum(1);
+        for (i, chr) in key.iter().enumerate() {
+            let children_node_nums: Vec<_> = self.children_node_nums(node_num).collect();
+            match self.bin_search_by_children_labels(chr, &children_node_nums[..]) {
+                Ok(j) => node_num = children_node_nums[j],
+                Err(_) => return vec![].into_iter(),
+            }
+            if i + 1 < key.len() {
+                found.extend(self.value(node_num).map(|value| (i + 1, value)));
+            }
+        }
+        if key.is_empty() || !self.is_terminal(node_num) {
+            return vec![].into_iter();
+        }
+        found
+            .into_iter()
+            .map(|(len, value)| {
+                let prefix = key[..len]
+                    .iter()
+                    .cloned()
+                    .try_collect()
+                    .expect("Could not collect");
+                (prefix, value)
+            })
+            .collect::<Vec<_>>()
+            .into_iter()
+    }
+
+    /// Return the entries found anywhere in `text` with their positions, e.g.,
+    /// to highlight search terms. Each [Match](crate::iter::Match) spans `text[start..end]`.
+    ///
+    /// If `non_overlapping` is false, every occurrence is reported, ordered by
+    /// start and then by end. If true, the longest entry at the leftmost
+    /// position is reported and the search resumes after it.
+    ///
+    /// ```rust
+    /// use trie_rs::iter::Match;
+    /// use trie_rs::map::Trie;
+    ///
+    /// let trie = Trie::from_iter([("he", 0), ("hers", 1), ("she", 2)]);
+    /// let found: Vec<_> = trie.find_in(b"ushers", true).collect();
+    /// assert_eq!(found, [Match { start: 1, end: 4, value: &2 }]);
+    /// assert_eq!(trie.find_in(b"ushers", false).count(), 3);
+    /// ```
+    pub fn find_in<'a>(
+        &'a self,
+        text: &'a [Label],
+        non_overlapping: bool,
+    ) -> FindIter<'a, Label, Value> {
+        FindIter::new(self, text, non_overlapping)
+    }
+
+    /// Return the longest shared prefix or terminal of `query`.
+    pub fn longest_prefix<C, M>(&self, query: impl AsRef<[Label]>) -> Option<C>
+    where
+        C: TryFromIterator<Label, M>,
+        Label: Clone,
+    {
+        let mut cur_node_num = LoudsNodeNum(1);
+        let mut buffer = Vec::new();
+
+        // Consumes query (prefix)
+        for chr in query.as_ref() {
+            let children_node_nums: Vec<_> = self.children_node_nums(cur_node_num).collect();
+            let res = self.bin_search_by_children_labels(chr, &children_node_nums[..]);
+            match res {
+                Ok(i) => {
+                    cur_node_num = children_node_nums[i];
+                    buffer.push(cur_node_num);
+                }
+                Err(_) => {
+                    return None;
+                }
+            }
+        }
+
+        // Walk the trie as long as there is only one path and it isn't a terminal value.
+        while !self.is_terminal(cur_node_num) {
             let mut iter = self.children_node_nums(cur_node_num);
             let first = iter.next();
             let second = iter.next();
@@ -189,80 +1026,529 @@ impl<Label: Ord, Value> Trie<Label, Value> {
         }
     }
 
-    pub(crate) fn has_children_node_nums(&self, node_num: LoudsNodeNum) -> bool {
-        self.louds
-            .parent_to_children_indices(node_num)
-            .next()
-            .is_some()
-    }
-
-    pub(crate) fn children_node_nums(&self, node_num: LoudsNodeNum) -> ChildNodeIter {
-        self.louds.parent_to_children_nodes(node_num)
-    }
-
-    pub(crate) fn bin_search_by_children_labels(
-        &self,
-        query: &Label,
-        children_node_nums: &[LoudsNodeNum],
-    ) -> Result<usize, usize> {
-        children_node_nums.binary_search_by(|child_node_num| self.label(*child_node_num).cmp(query))
-    }
-
-    pub(crate) fn label(&self, node_num: LoudsNodeNum) -> &Label {
-        &self.trie_labels[(node_num.0 - 2) as usize].label
-    }
-
-    pub(crate) fn is_terminal(&self, node_num: LoudsNodeNum) -> bool {
-        if node_num.0 >= 2 {
-            self.trie_labels[(node_num.0 - 2) as usize].value.is_some()
-        } else {
-            false
+    /// Return the longest entry that is a prefix of `query` and its value, or
+    /// `None` if no entry is a prefix of `query`.
+    ///
+    /// Unlike [Trie::longest_prefix], this never continues past `query`; it is
+    /// the last result of [Trie::common_prefix_search], e.g., for
+    /// longest-prefix-match routing.
+    ///
+    /// ```rust
+    /// use trie_rs::map::Trie;
+    ///
+    /// let trie = Trie::from_iter([("a", 0), ("app", 1), ("application", 2)]);
+    /// assert_eq!(trie.longest_common_prefix("applx"), Some(("app".to_string(), &1)));
+    /// assert_eq!(trie.longest_common_prefix::<String, _>("b"), None);
+    /// ```
+    pub fn longest_common_prefix<C, M>(&self, query: impl AsRef<[Label]>) -> Option<(C, &Value)>
+    where
+        C: TryFromIterator<Label, M>,
+        Label: Clone,
+    {
+        let query = query.as_ref();
+        let mut found = None;
+        let mut node_num = LoudsNodeNum(1);
+        for (i, chr) in query.iter().enumerate() {
+            let children_node_nums: Vec<_> = self.children_node_nums(node_num).collect();
+            match self.bin_search_by_children_labels(chr, &children_node_nums[..]) {
+                Ok(j) => node_num = children_node_nums[j],
+                Err(_) => break,
+            }
+            if let Some(value) = self.value(node_num) {
+                found = Some((i + 1, value));
+            }
         }
+        found.map(|(len, value)| {
+            let key = query[..len]
+                .iter()
+                .cloned()
+                .try_collect()
+                .expect("Could not collect");
+            (key, value)
+        })
     }
 
-    pub(crate) fn value(&self, node_num: LoudsNodeNum) -> Option<&Value> {
-        if node_num.0 >= 2 {
-            self.trie_labels[(node_num.0 - 2) as usize].value.as_ref()
-        } else {
-            None
+    /// Return the smallest key and its value, or `None` if the trie is empty.
+    ///
+    /// Takes _O(m)_ for a key of length _m_ by following first children.
+    ///
+    /// ```rust
+    /// use trie_rs::map::Trie;
+    ///
+    /// let trie = Trie::from_iter([("b", 0), ("app", 1), ("apple", 2)]);
+    /// assert_eq!(trie.first_key(), Some(("app".to_string(), &1)));
+    /// ```
+    pub fn first_key<C, M>(&self) -> Option<(C, &Value)>
+    where
+        C: TryFromIterator<Label, M>,
+        Label: Clone,
+    {
+        // Every key below a node is greater than the node's own key, so the
+        // first terminal along the first children is the smallest.
+        let mut node_num = LoudsNodeNum(1);
+        while let Some(child) = self.children_node_nums(node_num).next() {
+            node_num = child;
+            if let Some(value) = self.value(node_num) {
+                return Some((self.node_key(node_num), value));
+            }
         }
+        None
     }
 
-    pub(crate) fn value_mut(&mut self, node_num: LoudsNodeNum) -> Option<&mut Value> {
-        self.trie_labels[(node_num.0 - 2) as usize].value.as_mut()
-    }
-
-    pub(crate) fn child_to_ancestors(&self, node_num: LoudsNodeNum) -> AncestorNodeIter {
-        self.louds.child_to_ancestors(node_num)
+    /// Return the largest key and its value, or `None` if the trie is empty.
+    ///
+    /// Takes _O(m)_ for a key of length _m_ by following last children.
+    ///
+    /// ```rust
+    /// use trie_rs::map::Trie;
+    ///
+    /// let trie = Trie::from_iter([("app", 0), ("b", 1), ("bat", 2)]);
+    /// assert_eq!(trie.last_key(), Some(("bat".to_string(), &2)));
+    /// ```
+    pub fn last_key<C, M>(&self) -> Option<(C, &Value)>
+    where
+        C: TryFromIterator<Label, M>,
+        Label: Clone,
+    {
+        // A key is smaller than its extensions and every leaf is terminal, so
+        // the leaf along the last children is the largest.
+        let mut node_num = LoudsNodeNum(1);
+        while let Some(child) = self.children_node_nums(node_num).next_back() {
+            node_num = child;
+        }
+        self.value(node_num)
+            .map(|value| (self.node_key(node_num), value))
     }
-}
 
-impl<Label, Value, C> FromIterator<(C, Value)> for Trie<Label, Value>
-where
-    C: AsRef<[Label]>,
-    Label: Ord + Clone,
-{
-    fn from_iter<T>(iter: T) -> Self
+    /// Return a trie of the entries for which `f` returns `Some`, with its
+    /// value in place of theirs. The trie is rebuilt since dropping entries
+    /// may remove nodes.
+    ///
+    /// ```rust
+    /// use trie_rs::map::Trie;
+    ///
+    /// let counts = Trie::from_iter([("a", 2), ("app", 0), ("apple", 6)]);
+    /// let probs = counts.filter_map_values(|n| (n > 0).then(|| n as f64 / 8.0));
+    /// assert_eq!(probs.exact_match("apple"), Some(&0.75));
+    /// assert_eq!(probs.exact_match("app"), None);
+    /// ```
+    pub fn filter_map_values<V2>(self, mut f: impl FnMut(Value) -> Option<V2>) -> Trie<Label, V2>
     where
-        Self: Sized,
-        T: IntoIterator<Item = (C, Value)>,
+        Label: Clone,
     {
         let mut builder = super::TrieBuilder::new();
-        for (k, v) in iter {
-            builder.push(k, v)
+        // Values are stored in the breadth-first order of their nodes.
+        let terminals = (1..=self.last_node_num().0)
+            .map(LoudsNodeNum)
+            .filter(|&node_num| self.is_terminal(node_num));
+        let keys: Vec<Vec<Label>> = terminals.map(|node_num| self.node_key(node_num)).collect();
+        for (key, value) in keys.into_iter().zip(self.values) {
+            if let Some(value) = f(value) {
+                builder.insert(key, value);
+            }
         }
         builder.build()
     }
-}
 
-#[cfg(test)]
-mod search_tests {
-    use crate::map::{Trie, TrieBuilder};
-    use std::iter::FromIterator;
-
-    fn build_trie() -> Trie<u8, u8> {
-        let mut builder = TrieBuilder::new();
-        builder.push("a", 0);
+    /// Add the entries of `other` to this trie. Where a key is in both,
+    /// `on_conflict` is called with this trie's value and `other`'s instead.
+    ///
+    /// Conflicts are resolved in place; the trie is rebuilt only if `other`
+    /// has new keys. A new key comes after every existing one in insertion
+    /// order.
+    ///
+    /// ```rust
+    /// use trie_rs::map::Trie;
+    ///
+    /// let mut base = Trie::from_iter([("apple", 1), ("pear", 2)]);
+    /// let user = Trie::from_iter([("apple", 10), ("plum", 3)]);
+    /// base.extend_from(&user, |ours, theirs| *ours += theirs);
+    /// assert_eq!(base, Trie::from_iter([("apple", 11), ("pear", 2), ("plum", 3)]));
+    /// ```
+    pub fn extend_from(&mut self, other: &Self, mut on_conflict: impl FnMut(&mut Value, &Value))
+    where
+        Label: Clone,
+        Value: Clone,
+    {
+        let mut new = vec![];
+        for (key, value) in other.iter::<Vec<Label>, _>() {
+            match self.exact_match_mut(&key) {
+                Some(ours) => on_conflict(ours, value),
+                None => new.push((key, value)),
+            }
+        }
+        if new.is_empty() {
+            return;
+        }
+        let mut builder = super::TrieBuilder::from_trie(self);
+        for (key, value) in new {
+            builder.insert(key, value.clone());
+        }
+        *self = builder.build();
+    }
+
+    /// Convert this trie into a set [crate::Trie] of its keys, dropping the
+    /// values. Its nodes and labels are reused as they are, not rebuilt.
+    ///
+    /// ```rust
+    /// use trie_rs::map::Trie;
+    ///
+    /// let trie = Trie::from_iter([("a", 0), ("app", 1)]);
+    /// let set = trie.into_set();
+    /// assert!(set.exact_match("app"));
+    /// assert!(!set.exact_match("ap"));
+    /// ```
+    pub fn into_set(self) -> crate::Trie<Label> {
+        crate::Trie(Trie {
+            louds: self.louds,
+            labels: self.labels,
+            terminals: self.terminals,
+            values: vec![(); self.values.len()],
+            #[cfg(feature = "counts")]
+            counts: self.counts,
+            #[cfg(feature = "rand")]
+            weights: None,
+            insertion_order: self.insertion_order,
+        })
+    }
+
+    /// Consume the trie and return its keys in lexicographic order.
+    ///
+    /// ```rust
+    /// use trie_rs::map::Trie;
+    ///
+    /// let trie = Trie::from_iter([("b", 0), ("a", 1)]);
+    /// let keys: Vec<String> = trie.into_keys().collect();
+    /// assert_eq!(keys, ["a", "b"]);
+    /// ```
+    pub fn into_keys<C, M>(self) -> std::vec::IntoIter<C>
+    where
+        C: TryFromIterator<Label, M>,
+        Label: Clone,
+    {
+        self.iter()
+            .map(|(key, _)| key)
+            .collect::<Vec<C>>()
+            .into_iter()
+    }
+
+    /// Consume the trie and return its values in the lexicographic order of
+    /// their keys. Values are moved out, never cloned.
+    ///
+    /// ```rust
+    /// use trie_rs::map::Trie;
+    ///
+    /// let trie = Trie::from_iter([("b", "bee".to_string()), ("a", "ant".to_string())]);
+    /// let values: Vec<String> = trie.into_values().collect();
+    /// assert_eq!(values, ["ant", "bee"]);
+    /// ```
+    pub fn into_values(self) -> std::vec::IntoIter<Value> {
+        // Values are stored in the breadth-first order of their nodes; find
+        // each one's place in depth-first order.
+        let mut order = Vec::with_capacity(self.values.len());
+        let mut stack = vec![LoudsNodeNum(1)];
+        while let Some(node_num) = stack.pop() {
+            if self.is_terminal(node_num) {
+                order.push(self.value_index(node_num));
+            }
+            stack.extend(self.children_node_nums(node_num).rev());
+        }
+        let mut values: Vec<Option<Value>> = self.values.into_iter().map(Some).collect();
+        order
+            .into_iter()
+            .map(|i| values[i].take().expect("Each value is taken once"))
+            .collect::<Vec<_>>()
+            .into_iter()
+    }
+
+    /// Return a trie of only the entries that start with `prefix`, keeping
+    /// their full keys. It is empty if none do.
+    ///
+    /// ```rust
+    /// use trie_rs::map::Trie;
+    ///
+    /// let trie = Trie::from_iter([("std::io", 0), ("std::fs", 1), ("core::fmt", 2)]);
+    /// let std = trie.retain_prefix("std::");
+    /// assert_eq!(std, Trie::from_iter([("std::io", 0), ("std::fs", 1)]));
+    /// ```
+    pub fn retain_prefix(&self, prefix: impl AsRef<[Label]>) -> Self
+    where
+        Label: Clone,
+        Value: Clone,
+    {
+        super::TrieBuilder::build_from_sorted_unchecked(
+            self.predictive_search::<Vec<Label>, _>(prefix)
+                .map(|(key, value)| (key, value.clone())),
+        )
+    }
+
+    /// Build a trie from a `BTreeMap`, moving its keys and values.
+    ///
+    /// Since the map is already ordered, this takes the faster path of
+    /// [super::TrieBuilder::build_from_sorted_unchecked].
+    ///
+    /// # Panics
+    /// If a key is empty, as with [super::TrieBuilder::push].
+    ///
+    /// ```rust
+    /// use std::collections::BTreeMap;
+    /// use trie_rs::map::Trie;
+    ///
+    /// let map = BTreeMap::from([(b"app".to_vec(), 1), (b"a".to_vec(), 0)]);
+    /// let trie = Trie::from_map(map);
+    /// assert_eq!(trie.exact_match("app"), Some(&1));
+    /// ```
+    pub fn from_map(map: BTreeMap<Vec<Label>, Value>) -> Self {
+        // An empty key sorts first.
+        assert!(
+            map.keys().next().map_or(true, |key| !key.is_empty()),
+            "An empty entry cannot be pushed"
+        );
+        super::TrieBuilder::build_from_sorted_unchecked(map)
+    }
+
+    /// Build a trie from a `HashMap`, moving its keys and values. The keys
+    /// are sorted first.
+    ///
+    /// # Panics
+    /// If a key is empty, as with [super::TrieBuilder::push].
+    ///
+    /// ```rust
+    /// use std::collections::HashMap;
+    /// use trie_rs::map::Trie;
+    ///
+    /// let map = HashMap::from([(b"app".to_vec(), 1), (b"a".to_vec(), 0)]);
+    /// let trie = Trie::from_hash_map(map);
+    /// assert_eq!(trie.exact_match("a"), Some(&0));
+    /// ```
+    pub fn from_hash_map<S>(map: HashMap<Vec<Label>, Value, S>) -> Self {
+        let mut entries: Vec<(Vec<Label>, Value)> = map.into_iter().collect();
+        entries.sort_unstable_by(|a, b| a.0.cmp(&b.0));
+        assert!(
+            entries.first().map_or(true, |(key, _)| !key.is_empty()),
+            "An empty entry cannot be pushed"
+        );
+        super::TrieBuilder::build_from_sorted_unchecked(entries)
+    }
+
+    /// Return the node reached by following `query` from the root.
+    pub(crate) fn prefix_node(&self, query: impl AsRef<[Label]>) -> Option<LoudsNodeNum> {
+        let mut cur_node_num = LoudsNodeNum(1);
+        for chr in query.as_ref() {
+            let children_node_nums: Vec<_> = self.children_node_nums(cur_node_num).collect();
+            let res = self.bin_search_by_children_labels(chr, &children_node_nums[..]);
+            match res {
+                Ok(i) => cur_node_num = children_node_nums[i],
+                Err(_) => return None,
+            }
+        }
+        Some(cur_node_num)
+    }
+
+    /// Return the node reached by following borrowed labels from the root.
+    fn prefix_node_borrowed<Q>(&self, query: &[&Q]) -> Option<LoudsNodeNum>
+    where
+        Label: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let mut cur_node_num = LoudsNodeNum(1);
+        for chr in query {
+            let children_node_nums: Vec<_> = self.children_node_nums(cur_node_num).collect();
+            match self.bin_search_by_children_labels(*chr, &children_node_nums[..]) {
+                Ok(i) => cur_node_num = children_node_nums[i],
+                Err(_) => return None,
+            }
+        }
+        Some(cur_node_num)
+    }
+
+    /// Return every entry as references to its labels and value in
+    /// lexicographic order.
+    pub(crate) fn entry_refs(&self) -> impl Iterator<Item = (Vec<&Label>, &Value)> + '_ {
+        let mut stack: Vec<(usize, LoudsNodeNum)> = self
+            .children_node_nums(LoudsNodeNum(1))
+            .rev()
+            .map(|child| (0, child))
+            .collect();
+        let mut key = Vec::new();
+        std::iter::from_fn(move || {
+            while let Some((depth, node_num)) = stack.pop() {
+                key.truncate(depth);
+                key.push(self.label(node_num));
+                stack.extend(
+                    self.children_node_nums(node_num)
+                        .rev()
+                        .map(|child| (depth + 1, child)),
+                );
+                if let Some(value) = self.value(node_num) {
+                    return Some((key.clone(), value));
+                }
+            }
+            None
+        })
+    }
+
+    pub(crate) fn has_children_node_nums(&self, node_num: LoudsNodeNum) -> bool {
+        self.louds
+            .parent_to_children_indices(node_num)
+            .next()
+            .is_some()
+    }
+
+    pub(crate) fn children_node_nums(&self, node_num: LoudsNodeNum) -> ChildNodeIter<'_> {
+        self.louds.parent_to_children_nodes(node_num)
+    }
+
+    pub(crate) fn bin_search_by_children_labels<Q>(
+        &self,
+        query: &Q,
+        children_node_nums: &[LoudsNodeNum],
+    ) -> Result<usize, usize>
+    where
+        Label: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        children_node_nums
+            .binary_search_by(|child_node_num| self.label(*child_node_num).borrow().cmp(query))
+    }
+
+    /// Return the child of `node_num` labeled `chr` without collecting the
+    /// children, which are numbered consecutively.
+    fn child_by_label(&self, node_num: LoudsNodeNum, chr: &Label) -> Option<LoudsNodeNum> {
+        let mut children = self.children_node_nums(node_num);
+        let first = children.next()?.0;
+        let last = children.next_back().map_or(first, |child| child.0);
+        let (mut lo, mut hi) = (first, last + 1);
+        while lo < hi {
+            let mid = lo + (hi - lo) / 2;
+            match self.label(LoudsNodeNum(mid)).cmp(chr) {
+                std::cmp::Ordering::Less => lo = mid + 1,
+                std::cmp::Ordering::Greater => hi = mid,
+                std::cmp::Ordering::Equal => return Some(LoudsNodeNum(mid)),
+            }
+        }
+        None
+    }
+
+    /// Return the greatest node number, which is the root's in an empty trie.
+    pub(crate) fn last_node_num(&self) -> LoudsNodeNum {
+        LoudsNodeNum(self.labels.len() as u64 + 1)
+    }
+
+    pub(crate) fn label(&self, node_num: LoudsNodeNum) -> &Label {
+        &self.labels[(node_num.0 - 2) as usize]
+    }
+
+    /// Return true if `node_num` ends an entry. This is a bit lookup and
+    /// takes _O(1)_.
+    pub(crate) fn is_terminal(&self, node_num: LoudsNodeNum) -> bool {
+        self.terminals[node_num.0 - 1]
+    }
+
+    /// Return the index into `values` of a terminal node.
+    fn value_index(&self, node_num: LoudsNodeNum) -> usize {
+        self.terminals.rank(node_num.0 - 1) as usize - 1
+    }
+
+    pub(crate) fn value(&self, node_num: LoudsNodeNum) -> Option<&Value> {
+        if self.is_terminal(node_num) {
+            Some(&self.values[self.value_index(node_num)])
+        } else {
+            None
+        }
+    }
+
+    /// Return the sequence number of a terminal node if insertion order is
+    /// kept.
+    pub(crate) fn order(&self, node_num: LoudsNodeNum) -> Option<u64> {
+        match &self.insertion_order {
+            Some(orders) if self.is_terminal(node_num) => Some(orders[self.value_index(node_num)]),
+            _ => None,
+        }
+    }
+
+    /// Return a terminal node's value to change, which drops any sampling
+    /// weights since they may no longer match.
+    pub(crate) fn value_mut(&mut self, node_num: LoudsNodeNum) -> Option<&mut Value> {
+        #[cfg(feature = "rand")]
+        {
+            self.weights = None;
+        }
+        if self.is_terminal(node_num) {
+            let i = self.value_index(node_num);
+            Some(&mut self.values[i])
+        } else {
+            None
+        }
+    }
+
+    /// Return the key that leads to `node_num`.
+    pub(crate) fn node_key<C, M>(&self, node_num: LoudsNodeNum) -> C
+    where
+        C: TryFromIterator<Label, M>,
+        Label: Clone,
+    {
+        let mut key: Vec<&Label> = self
+            .child_to_ancestors(node_num)
+            .map(|node| self.label(node))
+            .collect();
+        key.reverse();
+        key.into_iter()
+            .cloned()
+            .try_collect()
+            .expect("Could not collect")
+    }
+
+    pub(crate) fn child_to_ancestors(&self, node_num: LoudsNodeNum) -> AncestorNodeIter<'_> {
+        self.louds.child_to_ancestors(node_num)
+    }
+
+    /// Return the parent of `node_num` or `None` for the root.
+    pub(crate) fn parent(&self, node_num: LoudsNodeNum) -> Option<LoudsNodeNum> {
+        (node_num.0 > 1).then(|| {
+            self.louds
+                .child_to_parent(self.louds.node_num_to_index(node_num))
+        })
+    }
+}
+
+impl<Label, Value, C> FromIterator<(C, Value)> for Trie<Label, Value>
+where
+    C: AsRef<[Label]>,
+    Label: Ord + Clone,
+{
+    fn from_iter<T>(iter: T) -> Self
+    where
+        Self: Sized,
+        T: IntoIterator<Item = (C, Value)>,
+    {
+        let mut builder = super::TrieBuilder::new();
+        for (k, v) in iter {
+            builder.push(k, v)
+        }
+        builder.build()
+    }
+}
+
+/// Two tries are equal if they contain the same entries with equal values.
+/// Their internal representations may differ.
+impl<Label: Ord, Value: PartialEq> PartialEq for Trie<Label, Value> {
+    fn eq(&self, other: &Self) -> bool {
+        self.entry_refs().eq(other.entry_refs())
+    }
+}
+
+impl<Label: Ord, Value: Eq> Eq for Trie<Label, Value> {}
+
+#[cfg(test)]
+mod search_tests {
+    use crate::map::{Trie, TrieBuilder};
+    use std::collections::{BTreeMap, HashMap};
+    use std::iter::FromIterator;
+
+    fn build_trie() -> Trie<u8, u8> {
+        let mut builder = TrieBuilder::new();
+        builder.push("a", 0);
         builder.push("app", 1);
         builder.push("apple", 2);
         builder.push("better", 3);
@@ -283,62 +1569,713 @@ mod search_tests {
     }
 
     #[test]
-    fn sanity_check() {
-        let trie = build_trie();
-        let v: Vec<(String, &u8)> = trie.predictive_search("apple").collect();
-        assert_eq!(v, vec![("apple".to_string(), &2)]);
+    fn sanity_check() {
+        let trie = build_trie();
+        let v: Vec<(String, &u8)> = trie.predictive_search("apple").collect();
+        assert_eq!(v, vec![("apple".to_string(), &2)]);
+    }
+
+    #[test]
+    fn clone() {
+        let trie = build_trie();
+        let _c: Trie<u8, u8> = trie.clone();
+    }
+
+    #[test]
+    fn clone_iterators() {
+        // Value is deliberately not `Clone`.
+        struct NoClone(u8);
+        let trie = Trie::<u8, NoClone>::from_iter([
+            ("a", NoClone(0)),
+            ("app", NoClone(1)),
+            ("apple", NoClone(2)),
+        ]);
+
+        let mut search = trie.predictive_search::<String, _>("a");
+        assert_eq!(search.next().map(|(k, v)| (k, v.0)), Some(("a".into(), 0)));
+        let tee = search.clone();
+        let rest: Vec<u8> = search.map(|(_, v)| v.0).collect();
+        let rest_tee: Vec<u8> = tee.map(|(_, v)| v.0).collect();
+        assert_eq!(rest, vec![1, 2]);
+        assert_eq!(rest, rest_tee);
+
+        let mut postfix = trie.postfix_search::<String, _>("a");
+        assert_eq!(postfix.next().map(|(k, _)| k), Some("pp".into()));
+        let tee = postfix.clone();
+        assert_eq!(
+            postfix.map(|(k, _)| k).collect::<Vec<_>>(),
+            tee.map(|(k, _)| k).collect::<Vec<_>>()
+        );
+
+        let mut prefix = trie.common_prefix_search::<String, _>("apple");
+        assert_eq!(prefix.next().map(|(k, _)| k), Some("a".into()));
+        let tee = prefix.clone();
+        assert_eq!(
+            prefix.map(|(k, _)| k).collect::<Vec<_>>(),
+            tee.map(|(k, _)| k).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn eq() {
+        let trie = build_trie();
+        assert_eq!(trie, trie.clone());
+        let reordered = Trie::<u8, u8>::from_iter([
+            ("アップル🍎", 5),
+            ("application", 4),
+            ("better", 3),
+            ("apple", 2),
+            ("app", 1),
+            ("a", 0),
+        ]);
+        assert_eq!(trie, reordered);
+
+        let mut changed = trie.clone();
+        *changed.exact_match_mut("apple").unwrap() = 9;
+        assert_ne!(trie, changed);
+
+        let fewer = Trie::<u8, u8>::from_iter([("a", 0), ("app", 1)]);
+        assert_ne!(trie, fewer);
+        assert_ne!(fewer, trie);
+    }
+
+    #[test]
+    fn eq_is_semantic() {
+        let a = Trie::<u8, u8>::from_iter([("a", 0)]);
+        // Leave "b" as a node without a value.
+        let mut ab = Trie::<u8, u8>::from_iter([("a", 0), ("b", 1)]);
+        ab.terminals = fid_rs::Fid::from(&[false, true, false][..]);
+        ab.values.pop();
+        assert_eq!(a, ab);
+    }
+
+    #[test]
+    fn value_mut() {
+        let mut trie = build_trie();
+        assert_eq!(trie.exact_match("apple"), Some(&2));
+        let v = trie.exact_match_mut("apple").unwrap();
+        *v = 10;
+        assert_eq!(trie.exact_match("apple"), Some(&10));
+    }
+
+    #[test]
+    fn predictive_search_indices() {
+        use crate::iter::ValuesExt;
+        let mut trie = build_trie();
+        for query in ["", "a", "app", "appl", "b", "c"] {
+            let ids = trie.predictive_search_indices(query);
+            let values: Vec<&u8> = ids.iter().map(|id| trie.value_by_id(*id)).collect();
+            let expected: Vec<&u8> = trie
+                .predictive_search::<String, _>(query)
+                .values()
+                .collect();
+            assert_eq!(values, expected);
+        }
+        for id in trie.predictive_search_indices("app") {
+            *trie.value_by_id_mut(id) += 100;
+        }
+        let values: Vec<&u8> = trie.iter::<String, _>().values().collect();
+        assert_eq!(values, [&0, &101, &102, &104, &3, &5]);
+    }
+
+    #[test]
+    #[should_panic(expected = "not an entry")]
+    fn value_by_id_not_an_entry() {
+        let trie = build_trie();
+        let other = Trie::from_iter([("bar", 0u8), ("b", 1)]);
+        // The node of "bar" in `other` is not terminal in `trie`.
+        let id = other.predictive_search_indices("bar")[0];
+        trie.value_by_id(id);
+    }
+
+    #[test]
+    fn trie_from_iter() {
+        let trie = Trie::<u8, u8>::from_iter([
+            ("a", 0),
+            ("app", 1),
+            ("apple", 2),
+            ("better", 3),
+            ("application", 4),
+        ]);
+        assert_eq!(trie.exact_match("application"), Some(&4));
+    }
+
+    #[test]
+    fn collect_a_trie() {
+        // Does not work with arrays in rust 2018 because into_iter() returns references instead of owned types.
+        // let trie: Trie<u8, u8> = [("a", 0), ("app", 1), ("apple", 2), ("better", 3), ("application", 4)].into_iter().collect();
+        let trie: Trie<u8, u8> = vec![
+            ("a", 0),
+            ("app", 1),
+            ("apple", 2),
+            ("better", 3),
+            ("application", 4),
+        ]
+        .into_iter()
+        .collect();
+        assert_eq!(trie.exact_match("application"), Some(&4));
+    }
+
+    #[test]
+    fn use_empty_queries() {
+        let trie = build_trie();
+        assert!(trie.exact_match("").is_none());
+        let _ = trie.predictive_search::<String, _>("").next();
+        let _ = trie.postfix_search::<String, _>("").next();
+        let _ = trie.common_prefix_search::<String, _>("").next();
+    }
+
+    #[test]
+    fn into_set() {
+        let trie = build_trie();
+        let keys: Vec<String> = trie.iter().map(|(key, _)| key).collect();
+        let set = trie.clone().into_set();
+        assert_eq!(set.iter().collect::<Vec<String>>(), keys);
+        for query in [
+            "",
+            "a",
+            "ap",
+            "app",
+            "apple",
+            "applez",
+            "better",
+            "アップル🍎",
+        ] {
+            assert_eq!(set.exact_match(query), trie.exact_match(query).is_some());
+            assert_eq!(set.is_prefix(query), trie.is_prefix(query));
+        }
+        assert_eq!(set, keys.iter().collect());
+    }
+
+    #[test]
+    fn extend_from() {
+        let mut trie = build_trie();
+        let other = Trie::from_iter([("app", 10), ("apricot", 6), ("better", 30), ("b", 7)]);
+        let mut conflicts = vec![];
+        trie.extend_from(&other, |ours, theirs| {
+            conflicts.push((*ours, *theirs));
+            *ours = *theirs;
+        });
+        assert_eq!(conflicts, [(1, 10), (3, 30)]);
+        let results: Vec<(String, &u8)> = trie.iter().collect();
+        let expected: Vec<(String, &u8)> = [
+            ("a", &0),
+            ("app", &10),
+            ("apple", &2),
+            ("application", &4),
+            ("apricot", &6),
+            ("b", &7),
+            ("better", &30),
+            ("アップル🍎", &5),
+        ]
+        .into_iter()
+        .map(|(key, value)| (key.to_string(), value))
+        .collect();
+        assert_eq!(results, expected);
+
+        // Only conflicts: nothing is rebuilt.
+        let before = trie.clone();
+        trie.extend_from(&Trie::from_iter([("b", 0)]), |_, _| {});
+        assert_eq!(trie, before);
+    }
+
+    #[test]
+    fn extend_from_insertion_order() {
+        let mut builder = TrieBuilder::new().with_insertion_order();
+        builder.push("b", 0u8);
+        builder.push("a", 1);
+        let mut trie = builder.build();
+        trie.extend_from(&Trie::from_iter([("c", 2), ("a", 3)]), |_, _| {});
+        assert_eq!(trie.insertion_index("b"), Some(0));
+        assert_eq!(trie.insertion_index("a"), Some(1));
+        assert_eq!(trie.insertion_index("c"), Some(2));
+    }
+
+    #[test]
+    fn depth_of_and_is_leaf() {
+        let trie = build_trie();
+        for (key, depth, is_leaf) in [
+            ("a", Some(1), false),
+            ("ap", Some(2), false),
+            ("app", Some(3), false),
+            ("apple", Some(5), true),
+            ("application", Some(11), true),
+            ("applez", None, false),
+            ("b", Some(1), false),
+            ("better", Some(6), true),
+            ("c", None, false),
+            ("", Some(0), false),
+        ] {
+            assert_eq!(trie.depth_of(key), depth, "{:?}", key);
+            assert_eq!(trie.is_leaf(key), is_leaf, "{:?}", key);
+        }
+        let empty: Trie<u8, u8> = TrieBuilder::new().build();
+        assert_eq!(empty.depth_of(""), None);
+        assert!(!empty.is_leaf(""));
+    }
+
+    #[test]
+    fn lcp_node() {
+        let trie = build_trie();
+        for (a, b, len) in [
+            ("apple", "application", Some(4)),
+            ("application", "apple", Some(4)),
+            ("a", "apple", Some(1)),
+            ("app", "app", Some(3)),
+            ("apple", "better", Some(0)),
+            ("アップル🍎", "better", Some(0)),
+            ("apple", "appl", None),
+            ("ap", "apple", None),
+            ("apple", "c", None),
+        ] {
+            assert_eq!(trie.common_prefix_len(a, b), len, "{:?} {:?}", a, b);
+            let node = trie.lcp_node(a, b);
+            assert_eq!(node.is_some(), len.is_some());
+            if let (Some(node), Some(len)) = (node, len) {
+                assert_eq!(Some(node), trie.prefix_node(&a.as_bytes()[..len]));
+            }
+        }
+    }
+
+    #[test]
+    fn empty_query_semantics() {
+        let trie = build_trie();
+        let all: Vec<(String, &u8)> = trie.iter().collect();
+        assert_eq!(all.len(), 6);
+        assert_eq!(trie.exact_match(""), None);
+        let results: Vec<(String, &u8)> = trie.predictive_search("").collect();
+        assert_eq!(results, all);
+        let results: Vec<(String, &u8)> = trie.postfix_search("").collect();
+        assert_eq!(results, all);
+        let results: Vec<(String, &u8)> = trie.common_prefix_search("").collect();
+        assert_eq!(results, []);
+
+        let empty: Trie<u8, u8> = TrieBuilder::new().build();
+        assert_eq!(empty.exact_match(""), None);
+        assert_eq!(empty.predictive_search::<String, _>("").count(), 0);
+        assert_eq!(empty.postfix_search::<String, _>("").count(), 0);
+        assert_eq!(empty.common_prefix_search::<String, _>("").count(), 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "empty entry")]
+    fn push_empty_entry() {
+        let mut builder = TrieBuilder::new();
+        builder.push("", 0u8);
+    }
+
+    #[test]
+    #[should_panic(expected = "empty entry")]
+    fn from_map_empty_key() {
+        let map = std::collections::BTreeMap::from([(vec![], 0u8), (b"a".to_vec(), 1)]);
+        Trie::from_map(map);
+    }
+
+    #[test]
+    #[should_panic(expected = "empty entry")]
+    fn from_hash_map_empty_key() {
+        let map = std::collections::HashMap::from([(b"a".to_vec(), 1u8), (vec![], 0)]);
+        Trie::from_hash_map(map);
+    }
+
+    mod common_prefix_search_with_rest_tests {
+        macro_rules! parameterized_tests {
+            ($($name:ident: $value:expr,)*) => {
+            $(
+                #[test]
+                fn $name() {
+                    let (query, expected_results, expected_consumed) = $value;
+                    let trie = super::build_trie();
+                    let (iter, consumed) = trie.common_prefix_search_with_rest(query);
+                    let results: Vec<(String, &u8)> = iter.collect();
+                    let expected_results: Vec<(String, &u8)> = expected_results.iter().map(|s| (s.0.to_string(), &s.1)).collect();
+                    assert_eq!(results, expected_results);
+                    assert_eq!(consumed, expected_consumed);
+                }
+            )*
+            }
+        }
+
+        parameterized_tests! {
+            t1: ("apple", vec![("a", 0), ("app", 1), ("apple", 2)], 5),
+            t2: ("applesauce", vec![("a", 0), ("app", 1), ("apple", 2)], 5),
+            t3: ("appl", vec![("a", 0), ("app", 1)], 3),
+            t4: ("b", Vec::<(&str, u8)>::new(), 0),
+            t5: ("zoo", Vec::<(&str, u8)>::new(), 0),
+            t6: ("", Vec::<(&str, u8)>::new(), 0),
+        }
+    }
+
+    #[test]
+    fn exact_match_many() {
+        let trie = build_trie();
+        let queries = vec![
+            "a".to_string(),
+            "appl".into(),
+            "better".into(),
+            "".into(),
+            "z".into(),
+        ];
+        let results: Vec<Option<&u8>> = trie.exact_match_many(&queries).collect();
+        assert_eq!(results, [Some(&0), None, Some(&3), None, None]);
+        assert_eq!(trie.exact_match_many(Vec::<&str>::new()).count(), 0);
+    }
+
+    #[test]
+    fn filter_map_values() {
+        let trie = build_trie();
+        let odd = trie
+            .clone()
+            .filter_map_values(|v| (v % 2 == 1).then(|| v.to_string()));
+        let expected: Vec<(String, String)> = trie
+            .iter::<String, _>()
+            .filter(|(_, v)| *v % 2 == 1)
+            .map(|(k, v)| (k, v.to_string()))
+            .collect();
+        let results: Vec<(String, String)> = odd
+            .iter()
+            .map(|(k, v): (String, &String)| (k, v.clone()))
+            .collect();
+        assert_eq!(results, expected);
+        assert_eq!(trie.clone().filter_map_values(Some), trie);
+        let none = trie.filter_map_values(|_| None::<u8>);
+        assert_eq!(none.stats().nodes, 0);
+    }
+
+    mod retain_prefix_tests {
+        macro_rules! parameterized_tests {
+            ($($name:ident: $value:expr,)*) => {
+            $(
+                #[test]
+                fn $name() {
+                    let (prefix, expected): (&str, &[(&str, u8)]) = $value;
+                    let trie = super::build_trie();
+                    let retained = trie.retain_prefix(prefix);
+                    let results: Vec<(String, &u8)> = retained.iter().collect();
+                    let expected: Vec<(String, &u8)> =
+                        expected.iter().map(|(k, v)| (k.to_string(), v)).collect();
+                    assert_eq!(results, expected);
+                }
+            )*
+            }
+        }
+
+        parameterized_tests! {
+            t1: ("app", &[("app", 1), ("apple", 2), ("application", 4)]),
+            t2: ("appl", &[("apple", 2), ("application", 4)]),
+            t3: ("better", &[("better", 3)]),
+            t4: ("z", &[]),
+            t5: ("applesauce", &[]),
+        }
+    }
+
+    #[test]
+    fn from_map() {
+        let trie = build_trie();
+        let entries: Vec<(Vec<u8>, u8)> =
+            trie.iter().map(|(k, v): (Vec<u8>, &u8)| (k, *v)).collect();
+        let btree: BTreeMap<Vec<u8>, u8> = entries.iter().cloned().collect();
+        let hash: HashMap<Vec<u8>, u8> = entries.into_iter().collect();
+        assert_eq!(Trie::from_map(btree), trie);
+        assert_eq!(Trie::from_hash_map(hash), trie);
+        assert_eq!(
+            Trie::<u8, u8>::from_map(BTreeMap::new()).stats().terminals,
+            0
+        );
+    }
+
+    #[test]
+    fn from_map_moves_values() {
+        struct NoClone(u8);
+        let map = HashMap::from([(b"b".to_vec(), NoClone(1)), (b"a".to_vec(), NoClone(0))]);
+        let trie = Trie::from_hash_map(map);
+        assert_eq!(trie.exact_match("b").map(|v| v.0), Some(1));
+    }
+
+    mod children_labels_at_tests {
+        macro_rules! parameterized_tests {
+            ($($name:ident: $value:expr,)*) => {
+            $(
+                #[test]
+                fn $name() {
+                    let (key, expected): (&str, &[u8]) = $value;
+                    let trie = super::build_trie();
+                    let labels: Vec<u8> = trie.children_labels_at(key).copied().collect();
+                    assert_eq!(labels, expected);
+                }
+            )*
+            }
+        }
+
+        parameterized_tests! {
+            t1: ("", &[b'a', b'b', 0xe3]),
+            t2: ("a", b"p"),
+            t3: ("appl", b"ei"),
+            t4: ("apple", b""),
+            t5: ("z", b""),
+        }
+    }
+
+    mod children_summary_tests {
+        macro_rules! parameterized_tests {
+            ($($name:ident: $value:expr,)*) => {
+            $(
+                #[test]
+                fn $name() {
+                    let (prefix, expected): (&str, &[(u8, bool, usize)]) = $value;
+                    let trie = super::build_trie();
+                    let results: Vec<(u8, bool, usize)> = trie
+                        .children_summary(prefix)
+                        .map(|(label, is_terminal, count)| (*label, is_terminal, count))
+                        .collect();
+                    assert_eq!(results, expected);
+                }
+            )*
+            }
+        }
+
+        parameterized_tests! {
+            t1: ("", &[(b'a', true, 1), (b'b', false, 1), (0xe3, false, 1)]),
+            t2: ("ap", &[(b'p', true, 1)]),
+            t3: ("appl", &[(b'e', true, 0), (b'i', false, 1)]),
+            t4: ("apple", &[]),
+            t5: ("z", &[]),
+        }
+    }
+
+    #[test]
+    fn contains_all_any() {
+        let trie = build_trie();
+        assert!(trie.contains_all(["a", "app", "アップル🍎"]));
+        assert!(!trie.contains_all(["a", "ap", "app"]));
+        assert!(trie.contains_all(Vec::<&str>::new()));
+        assert!(trie.contains_any(["ap", "b", "better"]));
+        assert!(!trie.contains_any(["ap", "b", "applesauce"]));
+        assert!(!trie.contains_any(Vec::<&str>::new()));
+    }
+
+    #[test]
+    fn contains_all_any_short_circuit() {
+        let trie = build_trie();
+        let mut seen = 0;
+        assert!(!trie.contains_all(["a", "ap", "app"].iter().inspect(|_| seen += 1)));
+        assert_eq!(seen, 2);
+        seen = 0;
+        assert!(trie.contains_any(["ap", "a", "app"].iter().inspect(|_| seen += 1)));
+        assert_eq!(seen, 2);
+    }
+
+    mod predictive_search_cursor_tests {
+        macro_rules! parameterized_tests {
+            ($($name:ident: $value:expr,)*) => {
+            $(
+                #[test]
+                fn $name() {
+                    let query = $value;
+                    let trie = super::build_trie();
+                    let mut cursor = trie.predictive_search_cursor(query);
+                    let mut results: Vec<(Vec<u8>, &u8)> = vec![];
+                    while let Some((key, value)) = cursor.next_entry() {
+                        results.push((key.to_vec(), value));
+                    }
+                    let expected_results: Vec<(Vec<u8>, &u8)> = trie.predictive_search(query).collect();
+                    assert_eq!(results, expected_results);
+                }
+            )*
+            }
+        }
+
+        parameterized_tests! {
+            t1: "",
+            t2: "a",
+            t3: "app",
+            t4: "apple",
+            t5: "b",
+            t6: "c",
+            t7: "アップ",
+            t8: "applesauce",
+        }
+    }
+
+    mod prefixes_of_tests {
+        macro_rules! parameterized_tests {
+            ($($name:ident: $value:expr,)*) => {
+            $(
+                #[test]
+                fn $name() {
+                    let (key, expected_results) = $value;
+                    let trie = super::build_trie();
+                    let results: Vec<(String, &u8)> = trie.prefixes_of(key).collect();
+                    let expected_results: Vec<(String, &u8)> = expected_results.iter().map(|s| (s.0.to_string(), &s.1)).collect();
+                    assert_eq!(results, expected_results);
+                }
+            )*
+            }
+        }
+
+        parameterized_tests! {
+            t1: ("application", vec![("a", 0), ("app", 1)]),
+            t2: ("apple", vec![("a", 0), ("app", 1)]),
+            t3: ("app", vec![("a", 0)]),
+            t4: ("a", Vec::<(&str, u8)>::new()),
+            t5: ("better", Vec::<(&str, u8)>::new()),
+            // Not an entry.
+            t6: ("appl", Vec::<(&str, u8)>::new()),
+            t7: ("applesauce", Vec::<(&str, u8)>::new()),
+            t8: ("", Vec::<(&str, u8)>::new()),
+        }
+    }
+
+    #[test]
+    fn is_prefix_free() {
+        assert!(!build_trie().is_prefix_free());
+        assert!(!Trie::<u8, u8>::from_iter([("a", 0), ("app", 1)]).is_prefix_free());
+        assert!(Trie::<u8, u8>::from_iter([("app", 0), ("bat", 1)]).is_prefix_free());
+        assert!(Trie::<u8, u8>::from_iter([("ab", 0), ("ac", 1), ("b", 2)]).is_prefix_free());
+        assert!(TrieBuilder::<u8, u8>::new().build().is_prefix_free());
     }
 
     #[test]
-    fn clone() {
+    fn first_and_last_key() {
         let trie = build_trie();
-        let _c: Trie<u8, u8> = trie.clone();
+        assert_eq!(trie.first_key(), Some(("a".to_string(), &0)));
+        assert_eq!(trie.last_key(), Some(("アップル🍎".to_string(), &5)));
+        let trie = Trie::<u8, u8>::from_iter([("ba", 0), ("b", 1), ("abc", 2)]);
+        assert_eq!(trie.first_key(), Some((b"abc".to_vec(), &2)));
+        assert_eq!(trie.last_key(), Some((b"ba".to_vec(), &0)));
+        let empty: Trie<u8, u8> = TrieBuilder::new().build();
+        assert_eq!(empty.first_key::<String, _>(), None);
+        assert_eq!(empty.last_key::<String, _>(), None);
     }
 
-    #[test]
-    fn value_mut() {
-        let mut trie = build_trie();
-        assert_eq!(trie.exact_match("apple"), Some(&2));
-        let v = trie.exact_match_mut("apple").unwrap();
-        *v = 10;
-        assert_eq!(trie.exact_match("apple"), Some(&10));
+    mod common_prefix_search_annotated_tests {
+        macro_rules! parameterized_tests {
+            ($($name:ident: $value:expr,)*) => {
+            $(
+                #[test]
+                fn $name() {
+                    let (query, expected_results) = $value;
+                    let trie = super::build_trie();
+                    let results: Vec<(String, &u8, bool)> = trie.common_prefix_search_annotated(query).collect();
+                    let expected_results: Vec<(String, &u8, bool)> = expected_results.iter().map(|s| (s.0.to_string(), &s.1, s.2)).collect();
+                    assert_eq!(results, expected_results);
+                }
+            )*
+            }
+        }
+
+        parameterized_tests! {
+            t1: ("application", vec![("a", 0, true), ("app", 1, true), ("application", 4, false)]),
+            t2: ("apple", vec![("a", 0, true), ("app", 1, true), ("apple", 2, false)]),
+            t3: ("betterment", vec![("better", 3, false)]),
+            t4: ("appl", vec![("a", 0, true), ("app", 1, true)]),
+            t5: ("zoo", Vec::<(&str, u8, bool)>::new()),
+            t6: ("", Vec::<(&str, u8, bool)>::new()),
+        }
+    }
+
+    mod has_common_prefix_tests {
+        macro_rules! parameterized_tests {
+            ($($name:ident: $value:expr,)*) => {
+            $(
+                #[test]
+                fn $name() {
+                    let (query, expected) = $value;
+                    let trie = super::build_trie();
+                    assert_eq!(trie.has_common_prefix(query), expected);
+                    assert_eq!(trie.has_common_prefix(query), trie.common_prefix_search::<Vec<u8>, _>(query).next().is_some());
+                }
+            )*
+            }
+        }
+
+        parameterized_tests! {
+            t1: ("a", true),
+            t2: ("applesauce", true),
+            t3: ("bet", false),
+            t4: ("betterment", true),
+            t5: ("", false),
+            t6: ("z", false),
+        }
+    }
+
+    mod has_completion_tests {
+        macro_rules! parameterized_tests {
+            ($($name:ident: $value:expr,)*) => {
+            $(
+                #[test]
+                fn $name() {
+                    let (query, expected) = $value;
+                    let trie = super::build_trie();
+                    assert_eq!(trie.has_completion(query), expected);
+                    assert_eq!(trie.has_completion(query), trie.predictive_search::<Vec<u8>, _>(query).next().is_some());
+                }
+            )*
+            }
+        }
+
+        parameterized_tests! {
+            t1: ("a", true),
+            t2: ("appl", true),
+            t3: ("apple", true),
+            t4: ("applesauce", false),
+            t5: ("", true),
+            t6: ("z", false),
+        }
     }
 
     #[test]
-    fn trie_from_iter() {
-        let trie = Trie::<u8, u8>::from_iter([
-            ("a", 0),
-            ("app", 1),
-            ("apple", 2),
-            ("better", 3),
-            ("application", 4),
-        ]);
-        assert_eq!(trie.exact_match("application"), Some(&4));
+    fn has_completion_empty() {
+        let trie: Trie<u8, u8> = TrieBuilder::new().build();
+        assert!(!trie.has_completion(""));
+        assert!(!trie.has_common_prefix(""));
     }
 
     #[test]
-    fn collect_a_trie() {
-        // Does not work with arrays in rust 2018 because into_iter() returns references instead of owned types.
-        // let trie: Trie<u8, u8> = [("a", 0), ("app", 1), ("apple", 2), ("better", 3), ("application", 4)].into_iter().collect();
-        let trie: Trie<u8, u8> = vec![
-            ("a", 0),
-            ("app", 1),
-            ("apple", 2),
-            ("better", 3),
-            ("application", 4),
-        ]
-        .into_iter()
-        .collect();
-        assert_eq!(trie.exact_match("application"), Some(&4));
+    fn values() {
+        use crate::iter::{KeysExt, ValuesExt};
+        let trie = build_trie();
+        let values: Vec<&u8> = trie
+            .predictive_search::<String, _>("app")
+            .values()
+            .collect();
+        assert_eq!(values, [&1, &2, &4]);
+        let keys: Vec<String> = trie.predictive_search("app").entries().keys().collect();
+        assert_eq!(keys, ["app", "apple", "application"]);
     }
 
     #[test]
-    fn use_empty_queries() {
+    fn bf_and_df_nodes() {
         let trie = build_trie();
-        assert!(trie.exact_match("").is_none());
-        let _ = trie.predictive_search::<String, _>("").next();
-        let _ = trie.postfix_search::<String, _>("").next();
-        let _ = trie.common_prefix_search::<String, _>("").next();
+        let nodes = trie.stats().nodes;
+        let bf: Vec<_> = trie.bf_nodes().collect();
+        let df: Vec<_> = trie.df_nodes().collect();
+        assert_eq!(trie.bf_nodes().len(), nodes);
+        assert_eq!((bf.len(), df.len()), (nodes, nodes));
+        assert!(bf.windows(2).all(|w| w[0].depth() <= w[1].depth()));
+        assert_eq!(bf.iter().map(|n| n.depth()).max(), Some(16));
+        assert_eq!(df.iter().map(|n| n.depth()).max(), Some(16));
+        let mut bf_depths: Vec<(usize, u8)> = bf.iter().map(|n| (n.depth(), *n.label())).collect();
+        let mut df_depths: Vec<(usize, u8)> = df.iter().map(|n| (n.depth(), *n.label())).collect();
+        bf_depths.sort();
+        df_depths.sort();
+        assert_eq!(bf_depths, df_depths);
+        let terminals = |nodes: &[crate::iter::NodeRef<'_, u8, u8>]| {
+            let mut values: Vec<u8> = nodes.iter().filter_map(|n| n.value()).copied().collect();
+            values.sort();
+            values
+        };
+        assert_eq!(terminals(&bf), [0, 1, 2, 3, 4, 5]);
+        assert_eq!(terminals(&df), [0, 1, 2, 3, 4, 5]);
+        let prefix: Vec<(u8, usize)> = df.iter().take(3).map(|n| (*n.label(), n.depth())).collect();
+        assert_eq!(prefix, [(b'a', 1), (b'p', 2), (b'p', 3)]);
+    }
+
+    #[test]
+    fn bf_and_df_nodes_empty() {
+        let trie: Trie<u8, u8> = TrieBuilder::new().build();
+        assert_eq!(trie.bf_nodes().next(), None);
+        assert_eq!(trie.df_nodes().next(), None);
     }
 
     #[test]
@@ -453,6 +2390,216 @@ mod search_tests {
         }
     }
 
+    #[test]
+    fn into_keys_and_values() {
+        let trie = build_trie();
+        let entries: Vec<(String, u8)> = trie.iter().map(|(k, v)| (k, *v)).collect();
+        let keys: Vec<String> = trie.clone().into_keys().collect();
+        let values: Vec<u8> = trie.into_values().collect();
+        assert_eq!(
+            keys,
+            entries.iter().map(|(k, _)| k.clone()).collect::<Vec<_>>()
+        );
+        assert_eq!(values, entries.iter().map(|(_, v)| *v).collect::<Vec<_>>());
+
+        // Values need not be `Clone`.
+        struct Token(u8);
+        let trie = Trie::from_iter([("b", Token(0)), ("a", Token(1)), ("ab", Token(2))]);
+        let values: Vec<u8> = trie.into_values().map(|Token(n)| n).collect();
+        assert_eq!(values, [1, 2, 0]);
+    }
+
+    #[test]
+    fn exact_match_len() {
+        let trie = build_trie();
+        assert_eq!(trie.exact_match_len("a"), Some(1));
+        assert_eq!(trie.exact_match_len("application"), Some(11));
+        assert_eq!(trie.exact_match_len("アップル🍎"), Some(16));
+        assert_eq!(trie.exact_match_len("appl"), None);
+        assert_eq!(trie.exact_match_len("applez"), None);
+        assert_eq!(trie.exact_match_len(""), None);
+    }
+
+    #[test]
+    fn get_key_value() {
+        /// A letter that compares without regard to ASCII case.
+        #[derive(Debug, Clone, Copy)]
+        struct Caseless(u8);
+        impl PartialEq for Caseless {
+            fn eq(&self, other: &Self) -> bool {
+                self.0.eq_ignore_ascii_case(&other.0)
+            }
+        }
+        impl Eq for Caseless {}
+        impl PartialOrd for Caseless {
+            fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+                Some(self.cmp(other))
+            }
+        }
+        impl Ord for Caseless {
+            fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+                self.0
+                    .to_ascii_lowercase()
+                    .cmp(&other.0.to_ascii_lowercase())
+            }
+        }
+        let caseless = |s: &str| s.bytes().map(Caseless).collect::<Vec<_>>();
+        let stored = |key: Vec<Caseless>| key.into_iter().map(|c| c.0 as char).collect::<String>();
+
+        let trie = Trie::from_iter([(caseless("Apple"), 0), (caseless("NASA"), 1)]);
+        let (key, value) = trie.get_key_value::<Vec<_>, _>(caseless("aPPLE")).unwrap();
+        assert_eq!((stored(key), value), ("Apple".to_string(), &0));
+        let (key, value) = trie.get_key_value::<Vec<_>, _>(caseless("nasa")).unwrap();
+        assert_eq!((stored(key), value), ("NASA".to_string(), &1));
+        assert!(trie.get_key_value::<Vec<_>, _>(caseless("appl")).is_none());
+        assert!(trie.get_key_value::<Vec<_>, _>(caseless("")).is_none());
+    }
+
+    #[test]
+    fn find_in() {
+        use crate::iter::Match;
+        let trie = Trie::from_iter([("a", 0), ("app", 1), ("apple", 2)]);
+        let spans = |non_overlapping| -> Vec<(usize, usize, u8)> {
+            trie.find_in(b"applet", non_overlapping)
+                .map(|Match { start, end, value }| (start, end, *value))
+                .collect()
+        };
+        assert_eq!(spans(false), [(0, 1, 0), (0, 3, 1), (0, 5, 2)]);
+        assert_eq!(spans(true), [(0, 5, 2)]);
+
+        let text = b"a map, an apple";
+        let found: Vec<_> = trie.find_in(text, true).collect();
+        let words: Vec<&[u8]> = found.iter().map(|m| &text[m.start..m.end]).collect();
+        assert_eq!(words, [&b"a"[..], b"a", b"a", b"apple"]);
+        assert_eq!(trie.find_in(text, false).count(), 6);
+        assert_eq!(trie.find_in(b"", false).count(), 0);
+    }
+
+    #[test]
+    fn borrowed_queries() {
+        let words = |s: &str| s.split(' ').map(String::from).collect::<Vec<_>>();
+        let trie = Trie::from_iter([
+            (words("new york"), 0),
+            (words("new york city"), 1),
+            (words("new jersey"), 2),
+        ]);
+        assert_eq!(trie.exact_match_borrowed::<str>(&["new", "york"]), Some(&0));
+        assert_eq!(
+            trie.exact_match_borrowed::<str>(&["new", "york", "city"]),
+            Some(&1)
+        );
+        assert_eq!(trie.exact_match_borrowed::<str>(&["new"]), None);
+        assert_eq!(trie.exact_match_borrowed::<str>(&["old"]), None);
+        assert_eq!(trie.exact_match_borrowed::<str>(&[]), None);
+        assert!(trie.is_prefix_borrowed::<str>(&["new"]));
+        assert!(trie.is_prefix_borrowed::<str>(&["new", "york"]));
+        assert!(!trie.is_prefix_borrowed::<str>(&["new", "jersey"]));
+        for (key, value) in trie.iter::<Vec<String>, _>() {
+            let key: Vec<&str> = key.iter().map(String::as_str).collect();
+            assert_eq!(trie.exact_match_borrowed(&key), Some(value));
+        }
+    }
+
+    #[test]
+    fn group_by_first() {
+        let trie = build_trie();
+        let mut groups = trie.group_by_first::<String, _>();
+        assert_eq!(groups.size_hint(), (3, Some(3)));
+        let labels: Vec<&u8> = groups.clone().map(|(label, _)| label).collect();
+        assert_eq!(labels, [&b'a', &b'b', &0xE3]);
+        // Skipping a group does not disturb the others.
+        let _ = groups.next();
+        let (_, better) = groups.next().unwrap();
+        assert_eq!(
+            better.map(|(k, v)| (k, *v)).collect::<Vec<_>>(),
+            [("better".to_string(), 3)]
+        );
+        let all: Vec<(String, &u8)> = trie.group_by_first().flat_map(|(_, group)| group).collect();
+        assert_eq!(all, trie.iter().collect::<Vec<_>>());
+        let empty: Trie<u8, u8> = TrieBuilder::new().build();
+        assert_eq!(empty.group_by_first::<String, _>().count(), 0);
+    }
+
+    #[test]
+    fn bump_prefixes() {
+        let mut trie = build_trie();
+        let mut seen = vec![];
+        trie.bump_prefixes("applications", |value| {
+            seen.push(*value);
+            *value += 10;
+        });
+        assert_eq!(seen, [0, 1, 4]);
+        assert_eq!(trie.exact_match("a"), Some(&10));
+        assert_eq!(trie.exact_match("app"), Some(&11));
+        assert_eq!(trie.exact_match("application"), Some(&14));
+        assert_eq!(trie.exact_match("apple"), Some(&2));
+
+        let before = trie.clone();
+        trie.bump_prefixes("", |value| *value += 1);
+        trie.bump_prefixes("b", |value| *value += 1);
+        trie.bump_prefixes("zzz", |value| *value += 1);
+        assert_eq!(trie, before);
+        trie.bump_prefixes("better", |value| *value += 1);
+        assert_eq!(trie.exact_match("better"), Some(&4));
+    }
+
+    mod longest_common_prefix_tests {
+        macro_rules! parameterized_tests {
+            ($($name:ident: $value:expr,)*) => {
+            $(
+                #[test]
+                fn $name() {
+                    let (query, expected_match) = $value;
+                    let trie = super::build_trie();
+                    let result: Option<(String, &u8)> = trie.longest_common_prefix(query);
+                    let expected_match = expected_match.map(|(s, v): (&str, u8)| (s.to_string(), v));
+                    assert_eq!(result.map(|(s, v)| (s, *v)), expected_match);
+                }
+            )*
+            }
+        }
+
+        parameterized_tests! {
+            t1: ("a", Some(("a", 0))),
+            t2: ("ap", Some(("a", 0))),
+            t3: ("applx", Some(("app", 1))),
+            t4: ("appl", Some(("app", 1))),
+            t5: ("applications", Some(("application", 4))),
+            t6: ("application", Some(("application", 4))),
+            t7: ("applicatio", Some(("app", 1))),
+            t8: ("better", Some(("better", 3))),
+            t9: ("bet", None),
+            t10: ("", None),
+            t11: ("アップル🍎🍏", Some(("アップル🍎", 5))),
+        }
+    }
+
+    mod predictive_search_strict_tests {
+        macro_rules! parameterized_tests {
+            ($($name:ident: $value:expr,)*) => {
+            $(
+                #[test]
+                fn $name() {
+                    let (query, expected_results) = $value;
+                    let trie = super::build_trie();
+                    let results: Vec<(String, &u8)> = trie.predictive_search_strict(query).collect();
+                    let expected_results: Vec<(String, &u8)> = expected_results.iter().map(|s| (s.0.to_string(), &s.1)).collect();
+                    assert_eq!(results, expected_results);
+                }
+            )*
+            }
+        }
+
+        parameterized_tests! {
+            t1: ("a", vec![("app", 1), ("apple", 2), ("application", 4)]),
+            t2: ("app", vec![("apple", 2), ("application", 4)]),
+            t3: ("appl", vec![("apple", 2), ("application", 4)]),
+            t4: ("apple", Vec::<(&str, u8)>::new()),
+            t5: ("", vec![("a", 0), ("app", 1), ("apple", 2), ("application", 4), ("better", 3), ("アップル🍎", 5)]),
+            t6: ("c", Vec::<(&str, u8)>::new()),
+        }
+    }
+
     mod predictive_search_tests {
         macro_rules! parameterized_tests {
             ($($name:ident: $value:expr,)*) => {
@@ -480,6 +2627,126 @@ mod search_tests {
         }
     }
 
+    mod predictive_search_ordered_tests {
+        use crate::iter::SearchOrder;
+
+        macro_rules! parameterized_tests {
+            ($($name:ident: $value:expr,)*) => {
+            $(
+                #[test]
+                fn $name() {
+                    let (query, expected_results) = $value;
+                    let trie = super::build_trie();
+                    let results: Vec<(String, &u8)> = trie.predictive_search_ordered(query, SearchOrder::ByLength).collect();
+                    let expected_results: Vec<(String, &u8)> = expected_results.iter().map(|s| (s.0.to_string(), &s.1)).collect();
+                    assert_eq!(results, expected_results);
+                    let results: Vec<(String, &u8)> = trie.predictive_search_ordered(query, SearchOrder::default()).collect();
+                    let expected_results: Vec<(String, &u8)> = trie.predictive_search(query).collect();
+                    assert_eq!(results, expected_results);
+                }
+            )*
+            }
+        }
+
+        parameterized_tests! {
+            t1: ("", vec![("a", 0), ("app", 1), ("apple", 2), ("better", 3), ("application", 4), ("アップル🍎", 5)]),
+            t2: ("a", vec![("a", 0), ("app", 1), ("apple", 2), ("application", 4)]),
+            t3: ("appl", vec![("apple", 2), ("application", 4)]),
+            t4: ("c", Vec::<(&str, u8)>::new()),
+        }
+    }
+
+    #[test]
+    fn predictive_search_with_depth() {
+        let trie = build_trie();
+        for query in ["", "a", "app", "appl", "apple", "b", "c", "アップ"] {
+            let results: Vec<(Vec<u8>, &u8, usize)> =
+                trie.predictive_search(query).with_depth().collect();
+            for (key, _, depth) in &results {
+                assert_eq!(key.len(), *depth);
+            }
+            let entries: Vec<(Vec<u8>, &u8)> = results
+                .into_iter()
+                .map(|(key, value, _)| (key, value))
+                .collect();
+            let expected: Vec<(Vec<u8>, &u8)> = trie.predictive_search(query).collect();
+            assert_eq!(entries, expected);
+        }
+    }
+
+    mod predictive_search_bounded_tests {
+        macro_rules! parameterized_tests {
+            ($($name:ident: $value:expr,)*) => {
+            $(
+                #[test]
+                fn $name() {
+                    let (query, max_frontier, expected_results, expected_truncated) = $value;
+                    let trie = super::build_trie();
+                    let mut iter = trie.predictive_search_bounded(query, max_frontier);
+                    let results: Vec<(String, &u8)> = iter.by_ref().collect();
+                    let expected_results: Vec<(String, &u8)> = expected_results.iter().map(|s| (s.0.to_string(), &s.1)).collect();
+                    assert_eq!(results, expected_results);
+                    assert_eq!(iter.is_truncated(), expected_truncated);
+                }
+            )*
+            }
+        }
+
+        parameterized_tests! {
+            t1: ("a", 100, vec![("a", 0), ("app", 1), ("apple", 2), ("application", 4)], false),
+            t2: ("a", 4, vec![("a", 0), ("app", 1), ("apple", 2)], true),
+            t3: ("a", 2, vec![("a", 0), ("app", 1)], true),
+            t4: ("a", 0, vec![("a", 0)], true),
+            t5: ("apple", 0, vec![("apple", 2)], false),
+            t6: ("c", 0, Vec::<(&str, u8)>::new(), false),
+            t7: ("", 6, vec![("a", 0), ("app", 1), ("apple", 2), ("better", 3)], true),
+        }
+    }
+
+    mod keys_of_len_tests {
+        macro_rules! parameterized_tests {
+            ($($name:ident: $value:expr,)*) => {
+            $(
+                #[test]
+                fn $name() {
+                    let (len, expected_results) = $value;
+                    let trie = super::build_trie();
+                    let results: Vec<(String, &u8)> = trie.keys_of_len(len).collect();
+                    let expected_results: Vec<(String, &u8)> = expected_results.iter().map(|s| (s.0.to_string(), &s.1)).collect();
+                    assert_eq!(results, expected_results);
+                }
+            )*
+            }
+        }
+
+        parameterized_tests! {
+            t1: (0, Vec::<(&str, u8)>::new()),
+            t2: (1, vec![("a", 0)]),
+            t3: (3, vec![("app", 1)]),
+            t4: (5, vec![("apple", 2)]),
+            t5: (6, vec![("better", 3)]),
+            t6: (11, vec![("application", 4)]),
+            t7: (16, vec![("アップル🍎", 5)]),
+            t8: (4, Vec::<(&str, u8)>::new()),
+        }
+    }
+
+    #[test]
+    fn keys_in_len_range() {
+        let trie = build_trie();
+        let results: Vec<(String, &u8)> = trie.keys_in_len_range(3, 6).collect();
+        assert_eq!(
+            results,
+            [
+                ("app".to_string(), &1),
+                ("apple".to_string(), &2),
+                ("better".to_string(), &3)
+            ]
+        );
+        assert_eq!(trie.keys_in_len_range::<String, _>(6, 3).next(), None);
+        assert_eq!(trie.keys_in_len_range::<Vec<u8>, _>(0, 100).count(), 6);
+    }
+
     mod common_prefix_search_tests {
         macro_rules! parameterized_tests {
             ($($name:ident: $value:expr,)*) => {
@@ -536,6 +2803,19 @@ mod search_tests {
         }
     }
 
+    #[test]
+    fn postfix_values_match_postfix_search() {
+        use crate::iter::ValuesExt;
+        let trie = build_trie();
+        for query in [
+            "", "a", "ap", "app", "appl", "appler", "bette", "better", "c",
+        ] {
+            let values: Vec<&u8> = trie.postfix_values(query).collect();
+            let expected: Vec<&u8> = trie.postfix_search::<String, _>(query).values().collect();
+            assert_eq!(values, expected, "query {:?}", query);
+        }
+    }
+
     mod postfix_search_char_tests {
         macro_rules! parameterized_tests {
             ($($name:ident: $value:expr,)*) => {
@@ -564,4 +2844,40 @@ mod search_tests {
             t8: ("アップル🍎🍏", Vec::<(&str, u8)>::new()),
         }
     }
+
+    mod predictive_search_order_tests {
+        use crate::map::Trie;
+        use proptest::prelude::*;
+        use std::collections::{BTreeMap, BTreeSet};
+
+        proptest! {
+            #[test]
+            fn matches_btree_map(
+                entries in prop::collection::vec(
+                    (prop::collection::vec(0u8..4, 1..6), any::<u8>()), 0..40),
+                query in prop::collection::vec(0u8..4, 0..3),
+            ) {
+                let trie: Trie<u8, u8> = entries.iter().cloned().collect();
+                let expected: Vec<(Vec<u8>, u8)> = entries
+                    .into_iter()
+                    .collect::<BTreeMap<_, _>>()
+                    .into_iter()
+                    .filter(|(key, _)| key.starts_with(&query))
+                    .collect();
+                let results: Vec<(Vec<u8>, u8)> = trie
+                    .predictive_search(&query)
+                    .map(|(key, value)| (key, *value))
+                    .collect();
+                prop_assert_eq!(results, expected);
+            }
+
+            #[test]
+            fn strings_match_btree_set(keys in prop::collection::vec("\\PC{1,4}", 1..30)) {
+                let trie: Trie<u8, ()> = keys.iter().map(|key| (key, ())).collect();
+                let expected: Vec<String> = keys.into_iter().collect::<BTreeSet<_>>().into_iter().collect();
+                let results: Vec<String> = trie.predictive_search("").map(|(key, _)| key).collect();
+                prop_assert_eq!(results, expected);
+            }
+        }
+    }
 }
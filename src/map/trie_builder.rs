@@ -1,7 +1,43 @@
-use crate::internal_data_structure::naive_trie::NaiveTrie;
-use crate::map::TrieLabel;
-use crate::map::{Trie, TrieBuilder};
-use louds_rs::Louds;
+use crate::internal_data_structure::naive_trie::{NaiveTrie, NaiveTrieIntermOrLeaf};
+use crate::map::{Dawg, SuffixTrie, Trie, TrieBuilder};
+use fid_rs::Fid;
+use louds_rs::{Louds, LoudsNodeNum};
+use std::fmt;
+use std::hash::Hash;
+
+/// Most labels to reserve for the root's children. Enough to cover any byte.
+const ROOT_CAPACITY: usize = 256;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "mem_dbg", derive(mem_dbg::MemDbg, mem_dbg::MemSize))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// What a [TrieBuilder] does when an entry is pushed again. See
+/// [TrieBuilder::on_duplicate].
+pub enum DuplicatePolicy {
+    /// The last value pushed wins.
+    #[default]
+    Overwrite,
+    /// The first value pushed wins.
+    KeepFirst,
+    /// Building fails; see [TrieBuilder::try_build].
+    Error,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// Duplicate entries were pushed to a [TrieBuilder] under
+/// [DuplicatePolicy::Error].
+pub struct DuplicateKeyError {
+    /// Number of pushes whose entry was already present.
+    pub count: usize,
+}
+
+impl fmt::Display for DuplicateKeyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} duplicate entries pushed", self.count)
+    }
+}
+
+impl std::error::Error for DuplicateKeyError {}
 
 impl<Label: Ord, Value> Default for TrieBuilder<Label, Value> {
     fn default() -> Self {
@@ -13,43 +49,964 @@ impl<Label: Ord, Value> TrieBuilder<Label, Value> {
     /// Return a [TrieBuilder].
     pub fn new() -> Self {
         let naive_trie = NaiveTrie::make_root();
-        Self { naive_trie }
+        Self {
+            naive_trie,
+            capacity: 0,
+            insertion_order: None,
+            on_duplicate: DuplicatePolicy::Overwrite,
+            duplicates: 0,
+        }
+    }
+
+    /// Return a [TrieBuilder] that expects about `keys` entries.
+    ///
+    /// This reserves room for the root's children and, when building, for at
+    /// least one node per entry, which reduces reallocations for large tries.
+    pub fn with_capacity(keys: usize) -> Self {
+        let naive_trie = NaiveTrie::make_root_with_capacity(keys.min(ROOT_CAPACITY));
+        Self {
+            naive_trie,
+            capacity: keys,
+            insertion_order: None,
+            on_duplicate: DuplicatePolicy::Overwrite,
+            duplicates: 0,
+        }
+    }
+
+    /// Return a [TrieBuilder] holding every entry of `trie`, e.g., to add or
+    /// remove entries and build again without keeping the original input.
+    ///
+    /// If `trie` was built with [TrieBuilder::with_insertion_order], so is
+    /// the builder, and entries keep their sequence numbers.
+    ///
+    /// ```rust
+    /// use trie_rs::map::{Trie, TrieBuilder};
+    ///
+    /// let trie = Trie::from_iter([("a", 0), ("app", 1)]);
+    /// let mut builder = TrieBuilder::from_trie(&trie);
+    /// builder.push("apple", 2);
+    /// assert_eq!(builder.remove("a"), Some(0));
+    /// let trie = builder.build();
+    /// assert_eq!(trie, Trie::from_iter([("app", 1), ("apple", 2)]));
+    /// ```
+    pub fn from_trie(trie: &Trie<Label, Value>) -> Self
+    where
+        Label: Clone,
+        Value: Clone,
+    {
+        let mut builder = Self::with_capacity(trie.values.len());
+        if let Some(orders) = &trie.insertion_order {
+            builder.insertion_order = Some(orders.iter().max().map_or(0, |order| order + 1));
+        }
+        let mut key = vec![];
+        let mut stack = vec![(LoudsNodeNum(1), 0)];
+        while let Some((node_num, depth)) = stack.pop() {
+            if depth > 0 {
+                key.truncate(depth - 1);
+                key.push(trie.label(node_num).clone());
+            }
+            if let Some(value) = trie.value(node_num) {
+                let order = trie.order(node_num).unwrap_or(0);
+                builder
+                    .naive_trie
+                    .push_ordered(key.iter().cloned(), value.clone(), order);
+            }
+            stack.extend(
+                trie.children_node_nums(node_num)
+                    .rev()
+                    .map(|child| (child, depth + 1)),
+            );
+        }
+        builder
+    }
+
+    /// Keep the order in which entries are pushed, retrievable with
+    /// [Trie::insertion_index], e.g., to break ties by recency.
+    ///
+    /// Every push, insert, or replacement takes the next sequence number
+    /// starting at 0. The built trie stores one `u64`, 8 bytes, per entry.
+    ///
+    /// ```rust
+    /// use trie_rs::map::TrieBuilder;
+    ///
+    /// let mut builder = TrieBuilder::new().with_insertion_order();
+    /// builder.push("b", 0);
+    /// builder.push("a", 1);
+    /// builder.push("b", 2);
+    /// let trie = builder.build();
+    /// assert_eq!(trie.insertion_index("a"), Some(1));
+    /// assert_eq!(trie.insertion_index("b"), Some(2));
+    /// ```
+    pub fn with_insertion_order(mut self) -> Self {
+        self.insertion_order.get_or_insert(0);
+        self
+    }
+
+    /// Set what happens when an entry is pushed again. By default, the last
+    /// value pushed wins.
+    ///
+    /// With [DuplicatePolicy::KeepFirst] or [DuplicatePolicy::Error], a
+    /// duplicate push leaves the entry as it was, and
+    /// [TrieBuilder::push_replace] returns `None`. With
+    /// [DuplicatePolicy::Error], [TrieBuilder::try_build] fails and
+    /// [TrieBuilder::build] panics. [TrieBuilder::append] follows the policy
+    /// for entries both builders have.
+    ///
+    /// # Errors
+    /// Duplicates under [DuplicatePolicy::Error] are reported when building,
+    /// not here. [TrieBuilder::build] keeps returning a [Trie] so that
+    /// existing callers are unaffected, and panics on them, so build with
+    /// [TrieBuilder::try_build] to get a [DuplicateKeyError] instead.
+    ///
+    /// ```rust
+    /// use trie_rs::map::{DuplicateKeyError, DuplicatePolicy, TrieBuilder};
+    ///
+    /// let mut builder = TrieBuilder::new().on_duplicate(DuplicatePolicy::KeepFirst);
+    /// builder.push("app", 0);
+    /// builder.push("app", 1);
+    /// assert_eq!(builder.build().exact_match("app"), Some(&0));
+    ///
+    /// let mut builder = TrieBuilder::new().on_duplicate(DuplicatePolicy::Error);
+    /// builder.push("app", 0);
+    /// builder.push("app", 1);
+    /// assert_eq!(builder.try_build(), Err(DuplicateKeyError { count: 1 }));
+    /// ```
+    pub fn on_duplicate(mut self, policy: DuplicatePolicy) -> Self {
+        self.on_duplicate = policy;
+        self
+    }
+
+    /// Set the value of `entry` as the duplicate policy allows. Return the
+    /// value it replaced if any.
+    fn set(&mut self, entry: impl Iterator<Item = Label>, value: Value) -> Option<Value> {
+        let order = self.next_order();
+        let node = self.naive_trie.node_mut(entry);
+        set_value(node, value, order, self.on_duplicate, &mut self.duplicates)
+    }
+
+    /// Return the sequence number for the next push.
+    fn next_order(&mut self) -> u64 {
+        match &mut self.insertion_order {
+            Some(next) => {
+                *next += 1;
+                *next - 1
+            }
+            None => 0,
+        }
     }
 
     /// Add a cloneable entry and value.
+    ///
+    /// # Panics
+    /// If `entry` is empty. The same holds for every way of adding an entry.
     pub fn push<Arr: AsRef<[Label]>>(&mut self, entry: Arr, value: Value)
     where
         Label: Clone,
     {
-        self.naive_trie.push(entry.as_ref().iter().cloned(), value);
+        self.set(entry.as_ref().iter().cloned(), value);
+    }
+
+    /// Add a cloneable entry and a clone of `value`.
+    ///
+    /// ```rust
+    /// use trie_rs::map::TrieBuilder;
+    ///
+    /// let tags = vec!["fruit".to_string()];
+    /// let mut builder = TrieBuilder::new();
+    /// builder.push_ref("apple", &tags);
+    /// builder.push_ref("pear", &tags);
+    /// assert_eq!(builder.build().exact_match("pear"), Some(&tags));
+    /// ```
+    pub fn push_ref<Arr: AsRef<[Label]>>(&mut self, entry: Arr, value: &Value)
+    where
+        Label: Clone,
+        Value: Clone,
+    {
+        self.push(entry, value.clone());
     }
 
     /// Add an entry and value.
     pub fn insert<Arr: IntoIterator<Item = Label>>(&mut self, entry: Arr, value: Value) {
-        self.naive_trie.push(entry.into_iter(), value);
+        self.set(entry.into_iter(), value);
+    }
+
+    /// Add a cloneable entry and value. Return the entry's previous value if
+    /// it was already present.
+    pub fn push_replace<Arr: AsRef<[Label]>>(&mut self, entry: Arr, value: Value) -> Option<Value>
+    where
+        Label: Clone,
+    {
+        self.set(entry.as_ref().iter().cloned(), value)
+    }
+
+    /// Add an entry and value. Return the entry's previous value if it was
+    /// already present.
+    ///
+    /// ```rust
+    /// use trie_rs::map::TrieBuilder;
+    ///
+    /// let mut builder = TrieBuilder::new();
+    /// assert_eq!(builder.insert_replace("app".bytes(), 0), None);
+    /// assert_eq!(builder.insert_replace("app".bytes(), 1), Some(0));
+    /// assert_eq!(builder.build().exact_match("app"), Some(&1));
+    /// ```
+    pub fn insert_replace<Arr: IntoIterator<Item = Label>>(
+        &mut self,
+        entry: Arr,
+        value: Value,
+    ) -> Option<Value> {
+        self.set(entry.into_iter(), value)
+    }
+
+    /// Remove an entry and return its value if it was present.
+    pub fn remove(&mut self, entry: impl AsRef<[Label]>) -> Option<Value> {
+        self.naive_trie.remove(entry.as_ref())
+    }
+
+    /// Move every entry of `other` into this builder. Where both have the same
+    /// entry, `other`'s value wins as though it were pushed last, unless this
+    /// builder's [DuplicatePolicy] says otherwise.
+    ///
+    /// ```rust
+    /// use trie_rs::map::TrieBuilder;
+    ///
+    /// let mut builder = TrieBuilder::new();
+    /// builder.push("app", 0);
+    /// let mut other = TrieBuilder::new();
+    /// other.push("app", 1);
+    /// other.push("apple", 2);
+    /// builder.append(other);
+    /// let trie = builder.build();
+    /// assert_eq!(trie.exact_match("app"), Some(&1));
+    /// assert_eq!(trie.exact_match("apple"), Some(&2));
+    /// ```
+    ///
+    /// With [TrieBuilder::with_insertion_order], `other`'s entries keep their
+    /// order after this builder's. If `other` does not keep insertion order,
+    /// its entries share one sequence number.
+    pub fn append(&mut self, mut other: TrieBuilder<Label, Value>) {
+        self.capacity = self.capacity.max(other.capacity);
+        if let Some(next) = self.insertion_order {
+            other.naive_trie.offset_orders(next);
+            self.insertion_order = Some(next + other.insertion_order.unwrap_or(1));
+        }
+        let keep_ours = self.on_duplicate != DuplicatePolicy::Overwrite;
+        let duplicates = self.naive_trie.append(other.naive_trie, keep_ours);
+        if self.on_duplicate == DuplicatePolicy::Error {
+            self.duplicates += other.duplicates + duplicates;
+        }
+    }
+
+    /// Add a batch of entries that are sorted among themselves, though not
+    /// necessarily relative to the entries already added, e.g., sorted
+    /// chunks streamed from several sources.
+    ///
+    /// The batch is built by appending to the last path made rather than
+    /// searching from the root for each entry, then merged in like
+    /// [TrieBuilder::append]. Entries within the batch follow the duplicate
+    /// policy and come after this builder's in insertion order.
+    ///
+    /// In debug builds, panics if the batch is not sorted. An unsorted batch
+    /// is still added correctly in release builds, only more slowly.
+    ///
+    /// ```rust
+    /// use trie_rs::map::TrieBuilder;
+    ///
+    /// let mut builder = TrieBuilder::new();
+    /// builder.push("banana", 0);
+    /// builder.push_sorted_batch([("apple".into(), 1), ("cherry".into(), 2)]);
+    /// builder.push_sorted_batch([("avocado".into(), 3), ("blueberry".into(), 4)]);
+    /// let trie = builder.build();
+    /// let keys: Vec<String> = trie.iter().map(|(key, _): (String, &u8)| key).collect();
+    /// assert_eq!(keys, ["apple", "avocado", "banana", "blueberry", "cherry"]);
+    /// ```
+    pub fn push_sorted_batch(&mut self, batch: impl IntoIterator<Item = (Vec<Label>, Value)>) {
+        let mut other = TrieBuilder {
+            naive_trie: NaiveTrie::make_root(),
+            capacity: 0,
+            insertion_order: self.insertion_order.map(|_| 0),
+            on_duplicate: self.on_duplicate,
+            duplicates: 0,
+        };
+        for (key, value) in batch {
+            let order = other.next_order();
+            let (node, in_order) = other.naive_trie.node_mut_sorted(key.into_iter());
+            debug_assert!(in_order, "batch is not sorted");
+            set_value(
+                node,
+                value,
+                order,
+                other.on_duplicate,
+                &mut other.duplicates,
+            );
+        }
+        self.append(other);
+    }
+
+    /// Return true if no entry has been added.
+    pub fn is_empty(&self) -> bool {
+        self.naive_trie.children().is_empty()
+    }
+
+    /// Remove all entries so the builder may be reused.
+    pub fn clear(&mut self) {
+        self.naive_trie = NaiveTrie::make_root_with_capacity(self.capacity.min(ROOT_CAPACITY));
+        if let Some(next) = &mut self.insertion_order {
+            *next = 0;
+        }
+        self.duplicates = 0;
+    }
+
+    /// Build a [Trie], or return an error if duplicate entries were pushed
+    /// under [DuplicatePolicy::Error].
+    pub fn try_build(self) -> Result<Trie<Label, Value>, DuplicateKeyError> {
+        match self.duplicates {
+            0 => Ok(self.build()),
+            count => Err(DuplicateKeyError { count }),
+        }
     }
 
     /// Build a [Trie].
+    ///
+    /// Every key gets its own path of nodes. See [TrieBuilder::build_minimized]
+    /// to share the nodes of common suffixes.
+    ///
+    /// # Panics
+    /// If duplicate entries were pushed under [DuplicatePolicy::Error]; see
+    /// [TrieBuilder::try_build]. Also if there are more than `u32::MAX`
+    /// entries with the "counts" crate feature.
     pub fn build(self) -> Trie<Label, Value> {
-        let mut louds_bits: Vec<bool> = vec![true, false];
-        let mut trie_labels: Vec<TrieLabel<Label, Value>> = vec![];
+        if self.duplicates > 0 {
+            panic!(
+                "{}",
+                DuplicateKeyError {
+                    count: self.duplicates
+                }
+            );
+        }
+        // A trie has at least one node per entry.
+        let mut louds_bits: Vec<bool> = Vec::with_capacity(2 * self.capacity + 3);
+        louds_bits.extend([true, false]);
+        let mut labels: Vec<Label> = Vec::with_capacity(self.capacity);
+        // The root is never terminal.
+        let mut terminals: Vec<bool> = Vec::with_capacity(self.capacity + 1);
+        terminals.push(false);
+        let mut values: Vec<Value> = Vec::with_capacity(self.capacity);
+        let mut insertion_order = self
+            .insertion_order
+            .map(|_| Vec::with_capacity(self.capacity));
         for node in self.naive_trie.into_iter() {
             match node {
                 NaiveTrie::Root(_) => {}
                 NaiveTrie::IntermOrLeaf(n) => {
                     louds_bits.push(true);
-                    trie_labels.push(TrieLabel {
-                        label: n.label,
-                        value: n.value,
-                    });
+                    labels.push(n.label);
+                    terminals.push(n.value.is_some());
+                    if let (Some(orders), Some(_)) = (&mut insertion_order, &n.value) {
+                        orders.push(n.order);
+                    }
+                    values.extend(n.value);
                 }
                 NaiveTrie::PhantomSibling => {
                     louds_bits.push(false);
                 }
             }
         }
-        let louds = Louds::from(&louds_bits[..]);
+        assemble(&louds_bits, labels, &terminals, values, insertion_order)
+    }
+
+    /// Build a [Trie] directly from entries that are already strictly sorted
+    /// by key with no duplicates, skipping the intermediate tree that
+    /// [TrieBuilder::build] uses.
+    ///
+    /// The bit vectors are laid out one level at a time from the labels each
+    /// key shares with its predecessor, which is much faster for large inputs.
+    ///
+    /// # Unchecked
+    ///
+    /// **The order of `entries` is not checked in release builds.** If they
+    /// are not strictly sorted or contain duplicates, the result is not
+    /// undefined behavior and does not panic, but it is a garbage trie that
+    /// answers queries incorrectly. Debug builds assert the order. Empty keys
+    /// are not allowed either.
+    ///
+    /// ```rust
+    /// use trie_rs::map::{Trie, TrieBuilder};
+    ///
+    /// let entries = vec![(b"a".to_vec(), 0), (b"app".to_vec(), 1), (b"apple".to_vec(), 2)];
+    /// let trie = TrieBuilder::build_from_sorted_unchecked(entries.clone());
+    /// assert_eq!(trie, Trie::from_iter(entries));
+    /// ```
+    pub fn build_from_sorted_unchecked(
+        entries: impl IntoIterator<Item = (Vec<Label>, Value)>,
+    ) -> Trie<Label, Value> {
+        let mut keys: Vec<std::vec::IntoIter<Label>> = Vec::new();
+        let mut lens: Vec<usize> = Vec::new();
+        let mut slots: Vec<Option<Value>> = Vec::new();
+        // Number of labels each key shares with the one before it. Key `i` has
+        // a node of its own at every depth past `shared[i]`.
+        let mut shared: Vec<usize> = Vec::new();
+        for (key, value) in entries {
+            let prev = keys.last().map_or(&[][..], |prev| prev.as_slice());
+            debug_assert!(!key.is_empty(), "empty key");
+            debug_assert!(
+                keys.is_empty() || prev.cmp(&key[..]).is_lt(),
+                "entries are not strictly sorted"
+            );
+            shared.push(prev.iter().zip(&key).take_while(|(a, b)| a == b).count());
+            lens.push(key.len());
+            slots.push(Some(value));
+            keys.push(key.into_iter());
+        }
+
+        let mut louds_bits = vec![true, false];
+        let mut labels = Vec::with_capacity(keys.len());
+        let mut terminals = Vec::with_capacity(keys.len() + 1);
+        terminals.push(false);
+        let mut values = Vec::with_capacity(keys.len());
+        // Nodes of the previous level by the index of the key that made them;
+        // every key descends from the root.
+        let mut parents = vec![0];
+        let mut active: Vec<usize> = (0..keys.len()).filter(|&i| lens[i] > 0).collect();
+        let mut depth = 0;
+        while !parents.is_empty() {
+            depth += 1;
+            let mut children = Vec::new();
+            for &i in &active {
+                // Each active key yields its label at this depth, which only
+                // becomes a node if it is not shared with the previous key.
+                let label = keys[i].next().expect("key is long enough");
+                if shared[i] < depth {
+                    children.push(i);
+                    labels.push(label);
+                    let is_terminal = lens[i] == depth;
+                    terminals.push(is_terminal);
+                    if is_terminal {
+                        values.extend(slots[i].take());
+                    }
+                }
+            }
+            // A child belongs to the last parent made by the same or an
+            // earlier key.
+            let mut next = 0;
+            for j in 0..parents.len() {
+                let end = parents.get(j + 1).copied().unwrap_or(usize::MAX);
+                while next < children.len() && children[next] < end {
+                    louds_bits.push(true);
+                    next += 1;
+                }
+                louds_bits.push(false);
+            }
+            active.retain(|&i| lens[i] > depth);
+            parents = children;
+        }
+        assemble(&louds_bits, labels, &terminals, values, None)
+    }
+
+    /// Build a [Dawg], which merges subtrees with the same labels and values
+    /// so that keys ending alike share their nodes.
+    ///
+    /// Requires `Value: Eq + Hash` to find equal values, and `Label: Hash`.
+    /// The insertion order is not kept.
+    ///
+    /// # Panics
+    /// If duplicate entries were pushed under [DuplicatePolicy::Error]; see
+    /// [TrieBuilder::try_build].
+    ///
+    /// ```rust
+    /// use trie_rs::map::TrieBuilder;
+    ///
+    /// let mut builder = TrieBuilder::new();
+    /// for stem in ["talk", "walk", "jump"] {
+    ///     for suffix in ["", "s", "ed", "ing"] {
+    ///         builder.push(format!("{}{}", stem, suffix), ());
+    ///     }
+    /// }
+    /// let dawg = builder.build_minimized();
+    /// assert_eq!(dawg.len(), 12);
+    /// // "talk", "walk", and "jump" share the nodes after them.
+    /// assert_eq!(dawg.node_count(), 11);
+    /// assert_eq!(dawg.exact_match("walked"), Some(&()));
+    /// assert_eq!(dawg.exact_match("walke"), None);
+    /// ```
+    pub fn build_minimized(self) -> Dawg<Label, Value>
+    where
+        Label: Hash,
+        Value: Eq + Hash,
+    {
+        if self.duplicates > 0 {
+            panic!(
+                "{}",
+                DuplicateKeyError {
+                    count: self.duplicates
+                }
+            );
+        }
+        Dawg::minimize(self.naive_trie)
+    }
+
+    /// Build a [SuffixTrie] which stores every key reversed.
+    pub fn build_reversed(self) -> SuffixTrie<Label, Value>
+    where
+        Label: Clone,
+    {
+        let mut builder = TrieBuilder::new();
+        for (mut key, value) in self.naive_trie.into_entries() {
+            key.reverse();
+            builder.insert(key, value);
+        }
+        SuffixTrie(builder.build())
+    }
+}
+
+/// Set the value of `node` as `policy` allows, counting a duplicate in
+/// `duplicates` under [DuplicatePolicy::Error]. Return the value it replaced
+/// if any.
+fn set_value<Label, Value>(
+    node: &mut NaiveTrieIntermOrLeaf<Label, Value>,
+    value: Value,
+    order: u64,
+    policy: DuplicatePolicy,
+    duplicates: &mut usize,
+) -> Option<Value> {
+    if node.value.is_some() {
+        match policy {
+            DuplicatePolicy::Overwrite => {}
+            DuplicatePolicy::KeepFirst => return None,
+            DuplicatePolicy::Error => {
+                *duplicates += 1;
+                return None;
+            }
+        }
+    }
+    node.order = order;
+    node.value.replace(value)
+}
+
+/// Make a [Trie] from its LOUDS bits, labels, values, and insertion order in
+/// breadth-first order.
+pub(super) fn assemble<Label: Ord, Value>(
+    louds_bits: &[bool],
+    labels: Vec<Label>,
+    terminals: &[bool],
+    values: Vec<Value>,
+    insertion_order: Option<Vec<u64>>,
+) -> Trie<Label, Value> {
+    assemble_parts(
+        Louds::from(louds_bits),
+        labels,
+        Fid::from(terminals),
+        values,
+        insertion_order,
+    )
+}
+
+/// Make a [Trie] like [assemble] from already built bit vectors.
+pub(super) fn assemble_parts<Label: Ord, Value>(
+    louds: Louds,
+    labels: Vec<Label>,
+    terminals: Fid,
+    values: Vec<Value>,
+    insertion_order: Option<Vec<u64>>,
+) -> Trie<Label, Value> {
+    #[allow(unused_mut)]
+    let mut trie = Trie {
+        louds,
+        labels,
+        terminals,
+        values,
+        #[cfg(feature = "counts")]
+        counts: Vec::new(),
+        #[cfg(feature = "rand")]
+        weights: None,
+        insertion_order,
+    };
+    #[cfg(feature = "counts")]
+    trie.count_subtrees();
+    trie
+}
+
+#[cfg(test)]
+mod builder_tests {
+    use crate::map::{DuplicateKeyError, DuplicatePolicy, Trie, TrieBuilder};
+    use proptest::prelude::*;
+    use std::collections::BTreeMap;
+
+    proptest! {
+        #[test]
+        fn build_from_sorted_unchecked_matches_build(
+            entries in prop::collection::btree_map(
+                prop::collection::vec(0u8..4, 1..6), any::<u8>(), 0..40),
+        ) {
+            let mut builder = TrieBuilder::new();
+            for (key, value) in &entries {
+                builder.push(key, *value);
+            }
+            let expected = builder.build();
+            let trie = TrieBuilder::build_from_sorted_unchecked(entries);
+            // The layout must be identical, not only the entries.
+            prop_assert_eq!(format!("{:?}", trie), format!("{:?}", expected));
+            prop_assert!(trie.check().is_ok());
+        }
+    }
+
+    #[test]
+    fn build_from_sorted_unchecked() {
+        let entries: BTreeMap<Vec<u8>, u8> = [("a", 0), ("app", 1), ("apple", 2), ("better", 3)]
+            .into_iter()
+            .map(|(key, value)| (key.as_bytes().to_vec(), value))
+            .collect();
+        let trie = TrieBuilder::build_from_sorted_unchecked(entries.clone());
+        assert_eq!(trie, Trie::from_iter(entries));
+        assert_eq!(trie.exact_match("apple"), Some(&2));
+        assert_eq!(trie.exact_match("ap"), None);
+        let empty: Trie<u8, u8> = TrieBuilder::build_from_sorted_unchecked(vec![]);
+        assert_eq!(
+            format!("{:?}", empty),
+            format!("{:?}", TrieBuilder::<u8, u8>::new().build())
+        );
+    }
+
+    #[cfg(debug_assertions)]
+    #[test]
+    #[should_panic(expected = "entries are not strictly sorted")]
+    fn build_from_sorted_unchecked_unsorted() {
+        TrieBuilder::build_from_sorted_unchecked(vec![(b"b".to_vec(), 0), (b"a".to_vec(), 1)]);
+    }
+
+    /// A label that is not `Clone`.
+    #[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
+    struct Token(u8);
+
+    #[test]
+    fn duplicate_policy() {
+        let build = |policy| {
+            let mut builder = TrieBuilder::new().on_duplicate(policy);
+            assert_eq!(builder.push_replace("app", 0), None);
+            builder.push("apple", 1);
+            builder.insert("app".bytes(), 2);
+            let mut other = TrieBuilder::new();
+            other.push("apple", 3);
+            other.push("b", 4);
+            builder.append(other);
+            builder
+        };
+        let trie = build(DuplicatePolicy::Overwrite).build();
+        assert_eq!(trie.exact_match("app"), Some(&2));
+        assert_eq!(trie.exact_match("apple"), Some(&3));
+        let trie = build(DuplicatePolicy::KeepFirst).build();
+        assert_eq!(trie.exact_match("app"), Some(&0));
+        assert_eq!(trie.exact_match("apple"), Some(&1));
+        assert_eq!(trie.exact_match("b"), Some(&4));
+        assert_eq!(
+            build(DuplicatePolicy::Error).try_build(),
+            Err(DuplicateKeyError { count: 2 })
+        );
+
+        let mut builder = build(DuplicatePolicy::Error);
+        builder.clear();
+        builder.push("app", 0);
+        assert!(builder.try_build().is_ok());
+    }
+
+    #[test]
+    #[should_panic(expected = "1 duplicate entries pushed")]
+    fn duplicate_policy_error_panics() {
+        let mut builder = TrieBuilder::new().on_duplicate(DuplicatePolicy::Error);
+        builder.push("app", 0);
+        builder.push("app", 0);
+        builder.build();
+    }
+
+    #[test]
+    fn from_trie() {
+        let entries = [("a", 0), ("app", 1), ("apple", 2), ("better", 3), ("b", 4)];
+        let trie = Trie::<u8, u8>::from_iter(entries);
+        assert_eq!(TrieBuilder::from_trie(&trie).build(), trie);
+
+        let mut builder = TrieBuilder::from_trie(&trie);
+        assert_eq!(builder.remove("better"), Some(3));
+        assert_eq!(builder.remove("better"), None);
+        assert_eq!(builder.remove("ap"), None);
+        builder.push("best", 5);
+        let trie = builder.build();
+        assert_eq!(trie.check(), Ok(()));
+        assert_eq!(trie.exact_match("better"), None);
+        assert_eq!(trie.exact_match("best"), Some(&5));
+        assert_eq!(trie.exact_match("apple"), Some(&2));
+
+        let empty: Trie<u8, u8> = TrieBuilder::new().build();
+        assert!(TrieBuilder::from_trie(&empty).is_empty());
+    }
+
+    #[test]
+    fn from_trie_insertion_order() {
+        let mut builder = TrieBuilder::new().with_insertion_order();
+        builder.push("b", 0);
+        builder.push("a", 1);
+        let trie = builder.build();
+        let mut builder = TrieBuilder::from_trie(&trie);
+        builder.push("c", 2);
+        let trie = builder.build();
+        assert_eq!(trie.insertion_index("b"), Some(0));
+        assert_eq!(trie.insertion_index("a"), Some(1));
+        assert_eq!(trie.insertion_index("c"), Some(2));
+    }
+
+    /// A label with a total order whose `partial_cmp` must not be called.
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    struct Total(u8);
+
+    impl Ord for Total {
+        fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+            self.0.cmp(&other.0)
+        }
+    }
+
+    #[allow(clippy::non_canonical_partial_ord_impl)]
+    impl PartialOrd for Total {
+        fn partial_cmp(&self, _other: &Self) -> Option<std::cmp::Ordering> {
+            panic!("partial_cmp called")
+        }
+    }
+
+    #[test]
+    fn labels_compared_by_ord() {
+        let keys = [vec![Total(1), Total(2)], vec![Total(1)], vec![Total(0)]];
+        let mut builder = TrieBuilder::new();
+        for (i, key) in keys.iter().enumerate() {
+            builder.push(key, i);
+        }
+        let mut other = TrieBuilder::new();
+        other.push([Total(1), Total(3)], 3);
+        other.push([Total(0)], 4);
+        builder.append(other);
+        let trie = builder.build();
+        assert_eq!(trie.check(), Ok(()));
+        assert_eq!(trie.exact_match([Total(1), Total(2)]), Some(&0));
+        assert_eq!(trie.exact_match([Total(0)]), Some(&4));
+        let results: Vec<(Vec<Total>, &usize)> = trie.predictive_search([Total(1)]).collect();
+        assert_eq!(results.len(), 3);
+        let entries: Vec<(Vec<Total>, usize)> = trie.iter().map(|(k, v)| (k, *v)).collect();
+        assert_eq!(TrieBuilder::build_from_sorted_unchecked(entries), trie);
+    }
+
+    #[test]
+    fn labels_without_clone() {
+        let mut builder = TrieBuilder::new();
+        builder.insert([Token(1), Token(2)], 0);
+        builder.insert([Token(1)], 1);
+        builder.insert([Token(3)], 2);
+        let trie = builder.build();
+        assert_eq!(trie.exact_match([Token(1), Token(2)]), Some(&0));
+        assert!(trie.is_prefix([Token(1)]));
+        assert!(trie.has_completion([Token(3)]));
+        assert_eq!(trie.prefix_count([Token(1)]), 2);
+        assert_eq!(trie.aggregate_prefix([Token(1)], 0, |sum, v| sum + v), 1);
+        let mut search = trie.inc_search();
+        assert!(search.query(&Token(1)).is_some());
+        assert_eq!(search.value(), Some(&1));
+    }
+
+    #[test]
+    fn insertion_order() {
+        let mut builder = TrieBuilder::new().with_insertion_order();
+        builder.push("app", 0);
+        builder.insert("a".bytes(), 1);
+        builder.push("apple", 2);
+        assert_eq!(builder.push_replace("app", 3), Some(0));
+        let trie = builder.build();
+        assert_eq!(trie.insertion_index("a"), Some(1));
+        assert_eq!(trie.insertion_index("app"), Some(3));
+        assert_eq!(trie.insertion_index("apple"), Some(2));
+        assert_eq!(trie.insertion_index("ap"), None);
+        assert_eq!(trie.insertion_index("b"), None);
+    }
+
+    #[test]
+    fn insertion_order_off() {
+        let trie = Trie::<u8, u8>::from_iter([("a", 0), ("app", 1)]);
+        assert_eq!(trie.insertion_index("a"), None);
+        let mut builder = TrieBuilder::new();
+        builder.push("a", 0);
+        assert_eq!(builder.build().insertion_index("a"), None);
+    }
+
+    #[test]
+    fn insertion_order_append_and_clear() {
+        let mut builder = TrieBuilder::new().with_insertion_order();
+        builder.push("a", 0);
+        builder.push("b", 1);
+        let mut other = TrieBuilder::new().with_insertion_order();
+        other.push("b", 2);
+        other.push("c", 3);
+        builder.append(other);
+        builder.push("d", 4);
+        let trie = builder.clone().build();
+        let orders: Vec<Option<u64>> = ["a", "b", "c", "d"]
+            .iter()
+            .map(|k| trie.insertion_index(k))
+            .collect();
+        assert_eq!(orders, [Some(0), Some(2), Some(3), Some(4)]);
+        builder.clear();
+        builder.push("z", 5);
+        assert_eq!(builder.build().insertion_index("z"), Some(0));
+    }
+
+    #[test]
+    fn clear_and_reuse() {
+        let mut builder = TrieBuilder::default();
+        assert!(builder.is_empty());
+        builder.push("a", 0);
+        assert!(!builder.is_empty());
+        builder.clear();
+        assert!(builder.is_empty());
+        builder.push("b", 1);
+        let trie = builder.build();
+        assert_eq!(trie.exact_match("a"), None);
+        assert_eq!(trie.exact_match("b"), Some(&1));
+    }
+
+    #[test]
+    fn replace_returns_previous_value() {
+        let mut builder = TrieBuilder::new();
+        assert_eq!(builder.push_replace("app", 0), None);
+        assert_eq!(builder.push_replace("a", 1), None);
+        assert_eq!(builder.push_replace("app", 2), Some(0));
+        assert_eq!(builder.insert_replace("app".bytes(), 3), Some(2));
+        // An intermediate node has no value to replace.
+        assert_eq!(builder.push_replace("ap", 4), None);
+        let trie = builder.build();
+        assert_eq!(trie.exact_match("app"), Some(&3));
+        assert_eq!(trie.exact_match("ap"), Some(&4));
+    }
+
+    #[test]
+    fn push_sorted_batch() {
+        let batch = |keys: &[(&str, u8)]| -> Vec<(Vec<u8>, u8)> {
+            keys.iter()
+                .map(|(k, v)| (k.as_bytes().to_vec(), *v))
+                .collect()
+        };
+        let build = |policy| {
+            let mut builder = TrieBuilder::new()
+                .with_insertion_order()
+                .on_duplicate(policy);
+            builder.push("banana", 0);
+            builder.push("app", 1);
+            builder.push_sorted_batch(batch(&[
+                ("a", 2),
+                ("app", 3),
+                ("apple", 4),
+                ("apple", 5),
+                ("cherry", 6),
+            ]));
+            builder.push_sorted_batch(batch(&[("apricot", 7), ("b", 8)]));
+            builder
+        };
+
+        let trie = build(DuplicatePolicy::Overwrite).build();
+        assert_eq!(trie.check(), Ok(()));
+        let entries: Vec<(String, &u8)> = trie.iter().collect();
+        let expected = [
+            ("a", 2),
+            ("app", 3),
+            ("apple", 5),
+            ("apricot", 7),
+            ("b", 8),
+            ("banana", 0),
+            ("cherry", 6),
+        ];
+        assert_eq!(
+            entries,
+            expected
+                .iter()
+                .map(|(k, v)| (k.to_string(), v))
+                .collect::<Vec<_>>()
+        );
+        // Batches come after the builder's own entries in insertion order.
+        let order: Vec<Option<u64>> = ["banana", "a", "app", "apple", "cherry", "apricot", "b"]
+            .iter()
+            .map(|key| trie.insertion_index(key))
+            .collect();
+        assert_eq!(order, [0, 2, 3, 5, 6, 7, 8].map(Some));
+
+        let trie = build(DuplicatePolicy::KeepFirst).build();
+        assert_eq!(trie.exact_match("app"), Some(&1));
+        assert_eq!(trie.exact_match("apple"), Some(&4));
+
+        assert!(build(DuplicatePolicy::Error).try_build().is_err());
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "batch is not sorted")]
+    fn push_sorted_batch_unsorted() {
+        let mut builder = TrieBuilder::new();
+        builder.push_sorted_batch([(b"b".to_vec(), 0), (b"a".to_vec(), 1)]);
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "batch is not sorted")]
+    fn push_sorted_batch_prefix_after_longer() {
+        let mut builder = TrieBuilder::new();
+        builder.push_sorted_batch([(b"ab".to_vec(), 0), (b"a".to_vec(), 1)]);
+    }
+
+    #[test]
+    fn append() {
+        let entries = [
+            ("a", 0),
+            ("app", 1),
+            ("apple", 2),
+            ("better", 3),
+            ("application", 4),
+            ("アップル🍎", 5),
+            ("app", 6),
+            ("bet", 7),
+            ("a", 8),
+        ];
+        for split in 0..entries.len() {
+            let mut builder = TrieBuilder::new();
+            let mut other = TrieBuilder::new();
+            for (key, value) in &entries[..split] {
+                builder.push(key, *value);
+            }
+            for (key, value) in &entries[split..] {
+                other.push(key, *value);
+            }
+            builder.append(other);
+            let trie = builder.build();
+            assert_eq!(trie, Trie::from_iter(entries), "split at {}", split);
+            assert_eq!(trie.check(), Ok(()));
+        }
+    }
+
+    #[test]
+    fn append_duplicates_last_write_wins() {
+        let mut builder = TrieBuilder::new();
+        builder.push("app", 0);
+        builder.push("apple", 1);
+        let mut other = TrieBuilder::new();
+        other.push("apple", 2);
+        other.push("ap", 3);
+        builder.append(other);
+        let trie = builder.build();
+        assert_eq!(trie.exact_match("app"), Some(&0));
+        assert_eq!(trie.exact_match("apple"), Some(&2));
+        assert_eq!(trie.exact_match("ap"), Some(&3));
+    }
 
-        Trie { louds, trie_labels }
+    #[test]
+    fn with_capacity() {
+        let mut builder = TrieBuilder::with_capacity(3);
+        assert!(builder.is_empty());
+        builder.push("a", 0);
+        builder.push("app", 1);
+        builder.push("apple", 2);
+        let trie = builder.build();
+        assert_eq!(trie.exact_match("app"), Some(&1));
+        assert_eq!(trie, Trie::from_iter([("a", 0), ("app", 1), ("apple", 2)]));
     }
 }
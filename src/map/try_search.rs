@@ -0,0 +1,131 @@
+//! Searches that report a failure to collect a key instead of panicking. See
+//! [crate::try_collect] for which collections can fail.
+use super::Trie;
+use crate::iter::{PostfixIter, PrefixIter, SearchIter, TryCollectIter};
+use crate::try_collect::{Collect, TryFromIterator};
+
+impl<Label: Ord + Clone, Value> Trie<Label, Value> {
+    /// Return all entries and their values that match `query`, or an error for
+    /// each key that cannot be collected into `C`.
+    ///
+    /// ```rust
+    /// use trie_rs::map::Trie;
+    ///
+    /// let trie = Trie::from_iter([(&b"ok"[..], 0), (&b"o\xff"[..], 1)]);
+    /// let results: Vec<Result<(String, &u8), _>> = trie.try_predictive_search("o").collect();
+    /// assert_eq!(results[0].as_ref().unwrap(), &("ok".to_string(), &0));
+    /// assert!(results[1].is_err());
+    /// ```
+    pub fn try_predictive_search<C, M>(
+        &self,
+        query: impl AsRef<[Label]>,
+    ) -> TryCollectIter<SearchIter<'_, Label, Value, Vec<Label>, Collect>, C, M>
+    where
+        C: TryFromIterator<Label, M>,
+    {
+        TryCollectIter::new(self.predictive_search(query))
+    }
+
+    /// Return the common prefixes of `query` and their values, or an error
+    /// for each key that cannot be collected into `C`.
+    pub fn try_common_prefix_search<C, M>(
+        &self,
+        query: impl AsRef<[Label]>,
+    ) -> TryCollectIter<PrefixIter<'_, Label, Value, Vec<Label>, Collect>, C, M>
+    where
+        C: TryFromIterator<Label, M>,
+    {
+        TryCollectIter::new(self.common_prefix_search(query))
+    }
+
+    /// Return the postfixes and values of all entries that match `query`, or
+    /// an error for each postfix that cannot be collected into `C`.
+    pub fn try_postfix_search<C, M>(
+        &self,
+        query: impl AsRef<[Label]>,
+    ) -> TryCollectIter<PostfixIter<'_, Label, Value, Vec<Label>, Collect>, C, M>
+    where
+        C: TryFromIterator<Label, M>,
+    {
+        TryCollectIter::new(self.postfix_search(query))
+    }
+
+    /// Return every entry and its value, or an error for each key that cannot
+    /// be collected into `C`.
+    pub fn try_iter<C, M>(
+        &self,
+    ) -> TryCollectIter<PostfixIter<'_, Label, Value, Vec<Label>, Collect>, C, M>
+    where
+        C: TryFromIterator<Label, M>,
+    {
+        TryCollectIter::new(self.iter())
+    }
+}
+
+#[cfg(test)]
+mod try_search_tests {
+    use crate::map::{Trie, TrieBuilder};
+
+    fn build_trie() -> Trie<u8, u8> {
+        let mut builder = TrieBuilder::new();
+        builder.push("a", 0);
+        builder.push("app", 1);
+        builder.push("apple", 2);
+        builder.push("better", 3);
+        builder.push("application", 4);
+        builder.push("アップル🍎", 5);
+        // Not valid UTF-8.
+        builder.push(b"ap\xff", 6);
+        builder.build()
+    }
+
+    #[test]
+    fn try_predictive_search() {
+        let trie = build_trie();
+        let results: Vec<Result<(String, &u8), _>> = trie.try_predictive_search("ap").collect();
+        let errors = results.iter().filter(|r| r.is_err()).count();
+        let oks: Vec<(String, &u8)> = results.into_iter().filter_map(Result::ok).collect();
+        assert_eq!(errors, 1);
+        assert_eq!(
+            oks,
+            [
+                ("app".to_string(), &1),
+                ("apple".to_string(), &2),
+                ("application".to_string(), &4)
+            ]
+        );
+    }
+
+    #[test]
+    fn try_common_prefix_search() {
+        let trie = build_trie();
+        let results: Vec<Result<(String, &u8), _>> =
+            trie.try_common_prefix_search(b"ap\xff").collect();
+        assert_eq!(results[0].as_ref().unwrap(), &("a".to_string(), &0));
+        assert!(results[1].is_err());
+        assert_eq!(results.len(), 2);
+    }
+
+    #[test]
+    fn try_postfix_search() {
+        let trie = build_trie();
+        let results: Result<Vec<(String, &u8)>, _> = trie.try_postfix_search("アップ").collect();
+        assert_eq!(results.unwrap(), [("ル🍎".to_string(), &5)]);
+        // "アップ" split mid-character leaves an invalid postfix.
+        let results: Result<Vec<(String, &u8)>, _> =
+            trie.try_postfix_search(&"アップ".as_bytes()[..4]).collect();
+        assert!(results.is_err());
+    }
+
+    #[test]
+    fn try_iter() {
+        let trie = build_trie();
+        assert_eq!(trie.try_iter::<String, _>().count(), 7);
+        assert_eq!(
+            trie.try_iter::<String, _>().filter(Result::is_err).count(),
+            1
+        );
+        // Collecting into a Vec cannot fail.
+        assert!(trie.try_iter::<Vec<u8>, _>().all(|r| r.is_ok()));
+    }
+}
@@ -0,0 +1,105 @@
+//! Searches of byte tries whose keys are UTF-8 text.
+use super::Trie;
+use crate::try_collect::Collect;
+use std::str::Utf8Error;
+
+fn decode<Value>((key, value): (Vec<u8>, Value)) -> Result<(String, Value), Utf8Error> {
+    String::from_utf8(key)
+        .map(|key| (key, value))
+        .map_err(|e| e.utf8_error())
+}
+
+impl<Value> Trie<u8, Value> {
+    /// Return all entries and their values that match `query` with their keys
+    /// decoded as UTF-8.
+    ///
+    /// Unlike collecting into a `String`, which panics, a key that is not
+    /// valid UTF-8 is returned as an error.
+    ///
+    /// ```rust
+    /// use trie_rs::map::Trie;
+    ///
+    /// let trie = Trie::from_iter([("すし", 0), ("すしや", 1)]);
+    /// let results: Result<Vec<(String, &u8)>, _> = trie.predictive_search_str("すし").collect();
+    /// assert_eq!(results.unwrap(), [("すし".to_string(), &0), ("すしや".to_string(), &1)]);
+    /// ```
+    pub fn predictive_search_str(
+        &self,
+        query: &str,
+    ) -> impl Iterator<Item = Result<(String, &Value), Utf8Error>> + '_ {
+        self.predictive_search::<Vec<u8>, Collect>(query)
+            .map(decode)
+    }
+
+    /// Return the common prefixes of `query` and their values with their keys
+    /// decoded as UTF-8.
+    pub fn common_prefix_search_str(
+        &self,
+        query: &str,
+    ) -> impl Iterator<Item = Result<(String, &Value), Utf8Error>> + '_ {
+        self.common_prefix_search::<Vec<u8>, Collect>(query)
+            .map(decode)
+    }
+
+    /// Return the postfixes of all entries that match `query` and their values
+    /// decoded as UTF-8.
+    pub fn postfix_search_str(
+        &self,
+        query: &str,
+    ) -> impl Iterator<Item = Result<(String, &Value), Utf8Error>> + '_ {
+        self.postfix_search::<Vec<u8>, Collect>(query).map(decode)
+    }
+}
+
+#[cfg(test)]
+mod utf8_tests {
+    use crate::map::{Trie, TrieBuilder};
+
+    fn build_trie() -> Trie<u8, u8> {
+        let mut builder = TrieBuilder::new();
+        builder.push("a", 0);
+        builder.push("app", 1);
+        builder.push("apple", 2);
+        builder.push("アップル🍎", 5);
+        builder.push([b'a', 0xff], 6);
+        builder.build()
+    }
+
+    #[test]
+    fn predictive_search_str() {
+        let trie = build_trie();
+        let results: Vec<_> = trie.predictive_search_str("a").collect();
+        assert_eq!(results.len(), 4);
+        assert_eq!(results[0], Ok(("a".to_string(), &0)));
+        assert_eq!(results[2], Ok(("apple".to_string(), &2)));
+        assert!(results[3].is_err());
+        let results: Vec<_> = trie.predictive_search_str("アップ").collect();
+        assert_eq!(results, [Ok(("アップル🍎".to_string(), &5))]);
+    }
+
+    #[test]
+    fn common_prefix_search_str() {
+        let trie = build_trie();
+        let results: Vec<_> = trie.common_prefix_search_str("apples").collect();
+        assert_eq!(
+            results,
+            [
+                Ok(("a".to_string(), &0)),
+                Ok(("app".to_string(), &1)),
+                Ok(("apple".to_string(), &2))
+            ]
+        );
+    }
+
+    #[test]
+    fn postfix_search_str() {
+        let trie = build_trie();
+        let results: Vec<_> = trie.postfix_search_str("アップ").collect();
+        assert_eq!(results, [Ok(("ル🍎".to_string(), &5))]);
+        let results: Vec<_> = trie.postfix_search_str("ap").collect();
+        assert_eq!(
+            results,
+            [Ok(("p".to_string(), &1)), Ok(("ple".to_string(), &2))]
+        );
+    }
+}
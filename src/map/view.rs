@@ -0,0 +1,462 @@
+//! A read-only trie that borrows the binary format of [Trie::write_to_with]
+//! with fixed-width labels.
+use super::binary::{invalid_data, MAGIC, VARINT_LABELS, VERSION};
+#[cfg(doc)]
+use super::Trie;
+use crate::try_collect::{TryCollect, TryFromIterator};
+use bytemuck::Pod;
+use std::io;
+use std::mem::size_of;
+use std::ops::Range;
+
+/// Bits per block of the rank directory.
+const BLOCK_BITS: u64 = 512;
+
+/// A read-only trie over a buffer written by [Trie::write_to_with] with
+/// [LabelEncoding::Fixed](super::LabelEncoding::Fixed), such as a
+/// memory-mapped file, that is queried without copying its nodes, labels, or
+/// values onto the heap.
+///
+/// Only a rank directory of 1/64th the size of the bit vectors is allocated.
+/// The buffer must be aligned to 8 bytes, as a memory map is.
+///
+/// ```rust
+/// use trie_rs::map::{LabelEncoding, Trie, TrieView};
+///
+/// let trie = Trie::from_iter([("a", 0u32), ("app", 1), ("apple", 2)]);
+/// let mut bytes = Vec::new();
+/// trie.write_to_with(&mut bytes, LabelEncoding::Fixed).unwrap();
+/// // A Vec<u64> stands in for an aligned memory map.
+/// let mut words = vec![0u64; bytes.len() / 8];
+/// bytemuck::cast_slice_mut(&mut words).copy_from_slice(&bytes);
+///
+/// let view = TrieView::<u8, u32>::new(bytemuck::cast_slice(&words)).unwrap();
+/// assert_eq!(view.exact_match("app"), Some(&1));
+/// let results: Vec<(String, &u32)> = view.predictive_search("app").collect();
+/// assert_eq!(results, [("app".to_string(), &1), ("apple".to_string(), &2)]);
+/// ```
+#[derive(Debug, Clone)]
+pub struct TrieView<'a, Label, Value> {
+    louds: BitsView<'a>,
+    terminals: BitsView<'a>,
+    labels: &'a [Label],
+    values: &'a [Value],
+}
+
+impl<'a, Label: Ord + Pod, Value: Pod> TrieView<'a, Label, Value> {
+    /// Borrow a trie written by [Trie::write_to_with] with fixed-width labels.
+    ///
+    /// Returns an error of kind [io::ErrorKind::InvalidData] if `bytes` are
+    /// not in that format, are misaligned, do not hold `Label`s and `Value`s,
+    /// or hold varint labels as [Trie::write_to] writes by default, and
+    /// [io::ErrorKind::Unsupported] on big-endian targets.
+    /// The shape of the trie is checked but the order of its labels is
+    /// trusted.
+    pub fn new(bytes: &'a [u8]) -> io::Result<Self> {
+        if cfg!(target_endian = "big") {
+            return Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                "the binary format is little-endian",
+            ));
+        }
+        let mut bytes = Sections(bytes);
+        if bytes.take(8)? != MAGIC {
+            return Err(invalid_data("not a trie-rs binary"));
+        }
+        let header: &[u32] = bytes.cast(4)?;
+        if header[0] != VERSION {
+            return Err(invalid_data("unsupported version"));
+        }
+        if header[1] as usize != size_of::<Label>() || header[2] as usize != size_of::<Value>() {
+            return Err(invalid_data("label or value size does not match"));
+        }
+        if header[3] & VARINT_LABELS != 0 {
+            return Err(invalid_data(
+                "labels are varints; write them with LabelEncoding::Fixed to view in place",
+            ));
+        }
+        let nodes = bytes.cast::<u64>(1)?[0];
+        if nodes == 0 || nodes > u64::MAX / 4 {
+            return Err(invalid_data("invalid number of nodes"));
+        }
+        let louds = BitsView::new(bytes.cast(words(2 * nodes + 1)?)?, 2 * nodes + 1);
+        if !louds.is_louds() {
+            return Err(invalid_data("malformed LOUDS bits"));
+        }
+        let terminals = BitsView::new(bytes.cast(words(nodes)?)?, nodes);
+        if terminals.get(0) {
+            return Err(invalid_data("root is terminal"));
+        }
+        let labels = bytes.cast(len(nodes - 1)?)?;
+        let values = bytes.cast(len(terminals.rank1(nodes))?)?;
+        Ok(Self {
+            louds,
+            terminals,
+            labels,
+            values,
+        })
+    }
+
+    /// Return the number of entries.
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    /// Return true if there are no entries.
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// Return `Some(&Value)` if query is an exact match.
+    pub fn exact_match(&self, query: impl AsRef<[Label]>) -> Option<&'a Value> {
+        self.value(self.prefix_node(query.as_ref())?)
+    }
+
+    /// Return true if `query` is a prefix of a longer entry.
+    pub fn is_prefix(&self, query: impl AsRef<[Label]>) -> bool {
+        self.prefix_node(query.as_ref())
+            .map_or(false, |node| !self.children(node).is_empty())
+    }
+
+    /// Return all entries and their values that start with `query` in
+    /// lexicographic order.
+    pub fn predictive_search<C, M>(
+        &self,
+        query: impl AsRef<[Label]>,
+    ) -> std::vec::IntoIter<(C, &'a Value)>
+    where
+        C: TryFromIterator<Label, M>,
+    {
+        let query = query.as_ref();
+        let mut results = Vec::new();
+        let mut stack = match self.prefix_node(query) {
+            Some(node) => vec![(query.len(), node)],
+            None => vec![],
+        };
+        let mut key = query.to_vec();
+        while let Some((depth, node)) = stack.pop() {
+            if depth > query.len() {
+                key.truncate(depth - 1);
+                key.push(self.labels[node as usize - 2]);
+            }
+            if let Some(value) = self.value(node) {
+                results.push((collect(&key), value));
+            }
+            stack.extend(self.children(node).rev().map(|child| (depth + 1, child)));
+        }
+        results.into_iter()
+    }
+
+    /// Return the entries that are prefixes of `query` and their values,
+    /// shortest first.
+    pub fn common_prefix_search<C, M>(
+        &self,
+        query: impl AsRef<[Label]>,
+    ) -> std::vec::IntoIter<(C, &'a Value)>
+    where
+        C: TryFromIterator<Label, M>,
+    {
+        let query = query.as_ref();
+        let mut results = Vec::new();
+        let mut node = 1;
+        for (i, label) in query.iter().enumerate() {
+            match self.child(node, label) {
+                Some(child) => node = child,
+                None => break,
+            }
+            if let Some(value) = self.value(node) {
+                results.push((collect(&query[..=i]), value));
+            }
+        }
+        results.into_iter()
+    }
+
+    /// Return the node `query` leads to from the root.
+    fn prefix_node(&self, query: &[Label]) -> Option<u64> {
+        query
+            .iter()
+            .try_fold(1, |node, label| self.child(node, label))
+    }
+
+    /// Return the child of `node` labeled `label`.
+    fn child(&self, node: u64, label: &Label) -> Option<u64> {
+        let children = self.children(node);
+        let labels = &self.labels[children.start as usize - 2..children.end as usize - 2];
+        labels
+            .binary_search(label)
+            .ok()
+            .map(|i| children.start + i as u64)
+    }
+
+    /// Return the node numbers of the children of `node`.
+    fn children(&self, node: u64) -> Range<u64> {
+        // The block of ones after the node-th zero are its children.
+        let start = self.louds.select0(node) + 1;
+        let end = self.louds.select0(node + 1);
+        let first = self.louds.rank1(start) + 1;
+        first..first + (end - start)
+    }
+
+    fn is_terminal(&self, node: u64) -> bool {
+        self.terminals.get(node - 1)
+    }
+
+    fn value(&self, node: u64) -> Option<&'a Value> {
+        self.is_terminal(node)
+            .then(|| &self.values[self.terminals.rank1(node) as usize - 1])
+    }
+}
+
+fn collect<Label: Clone, C: TryFromIterator<Label, M>, M>(key: &[Label]) -> C {
+    key.iter()
+        .cloned()
+        .try_collect()
+        .expect("Could not collect")
+}
+
+/// Return the number of `u64` words holding `bits`.
+fn words(bits: u64) -> io::Result<usize> {
+    len((bits + 63) / 64)
+}
+
+fn len(n: u64) -> io::Result<usize> {
+    usize::try_from(n).map_err(|_| invalid_data("section too large"))
+}
+
+/// The remaining sections of a buffer.
+struct Sections<'a>(&'a [u8]);
+
+impl<'a> Sections<'a> {
+    fn take(&mut self, n: usize) -> io::Result<&'a [u8]> {
+        if n > self.0.len() {
+            return Err(io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                "buffer ends early",
+            ));
+        }
+        let (section, rest) = self.0.split_at(n);
+        self.0 = rest;
+        Ok(section)
+    }
+
+    /// Take `n` items of `T` and skip to the next 8-byte boundary.
+    fn cast<T: Pod>(&mut self, n: usize) -> io::Result<&'a [T]> {
+        let bytes = n
+            .checked_mul(size_of::<T>())
+            .ok_or_else(|| invalid_data("section too large"))?;
+        let section = self.take(bytes)?;
+        self.take(((8 - bytes % 8) % 8).min(self.0.len()))?;
+        bytemuck::try_cast_slice(section).map_err(|_| invalid_data("buffer is misaligned"))
+    }
+}
+
+/// A borrowed bit vector with a rank directory.
+#[derive(Debug, Clone)]
+struct BitsView<'a> {
+    words: &'a [u64],
+    len: u64,
+    /// Number of ones before each block of [BLOCK_BITS].
+    ranks: Vec<u64>,
+}
+
+impl<'a> BitsView<'a> {
+    fn new(words: &'a [u64], len: u64) -> Self {
+        let per_block = (BLOCK_BITS / 64) as usize;
+        let mut ranks = vec![0];
+        for block in words.chunks(per_block) {
+            let ones: u64 = block.iter().map(|w| w.count_ones() as u64).sum();
+            ranks.push(ranks[ranks.len() - 1] + ones);
+        }
+        Self { words, len, ranks }
+    }
+
+    fn get(&self, i: u64) -> bool {
+        self.words[(i / 64) as usize] >> (i % 64) & 1 == 1
+    }
+
+    /// Return the number of ones in `[0, i)`.
+    fn rank1(&self, i: u64) -> u64 {
+        let block = i / BLOCK_BITS;
+        let mut rank = self.ranks[block as usize];
+        for w in block * BLOCK_BITS / 64..i / 64 {
+            rank += self.words[w as usize].count_ones() as u64;
+        }
+        if i % 64 > 0 {
+            let mask = (1 << (i % 64)) - 1;
+            rank += (self.words[(i / 64) as usize] & mask).count_ones() as u64;
+        }
+        rank
+    }
+
+    /// Return the position of the `k`th zero, counting from 1.
+    fn select0(&self, k: u64) -> u64 {
+        // Last block with fewer than `k` zeros before it.
+        let zeros_before = |block: usize| block as u64 * BLOCK_BITS - self.ranks[block];
+        let (mut lo, mut hi) = (0, self.ranks.len() - 1);
+        while lo + 1 < hi {
+            let mid = (lo + hi) / 2;
+            if zeros_before(mid) < k {
+                lo = mid;
+            } else {
+                hi = mid;
+            }
+        }
+        let mut remaining = k - zeros_before(lo);
+        let mut w = lo * (BLOCK_BITS / 64) as usize;
+        loop {
+            let zeros = (!self.words[w]).count_ones() as u64;
+            if zeros >= remaining {
+                break;
+            }
+            remaining -= zeros;
+            w += 1;
+        }
+        let mut word = !self.words[w];
+        for _ in 1..remaining {
+            word &= word - 1;
+        }
+        w as u64 * 64 + word.trailing_zeros() as u64
+    }
+
+    /// Return true if these bits are a valid LOUDS bit string.
+    fn is_louds(&self) -> bool {
+        let (mut zeros, mut ones) = (0, 0);
+        for i in 0..self.len {
+            if self.get(i) {
+                ones += 1;
+            } else {
+                zeros += 1;
+            }
+            if zeros > ones + 1 {
+                return false;
+            }
+        }
+        self.len >= 2 && self.get(0) && !self.get(1) && zeros == ones + 1
+    }
+}
+
+#[cfg(test)]
+mod view_tests {
+    use crate::map::{Trie, TrieBuilder, TrieView};
+    use std::io::ErrorKind;
+
+    fn build_trie() -> Trie<u8, u32> {
+        let mut builder = TrieBuilder::new();
+        builder.push("a", 0);
+        builder.push("app", 1);
+        builder.push("apple", 2);
+        builder.push("better", 3);
+        builder.push("application", 4);
+        builder.push("アップル🍎", 5);
+        builder.build()
+    }
+
+    /// Write `trie` into an 8-byte aligned buffer.
+    fn to_words<Label: Ord + crate::map::FixedBytes, Value: crate::map::FixedBytes>(
+        trie: &Trie<Label, Value>,
+    ) -> Vec<u64> {
+        let mut bytes = Vec::new();
+        trie.write_to_with(&mut bytes, crate::map::LabelEncoding::Fixed)
+            .unwrap();
+        let mut words = vec![0u64; bytes.len() / 8];
+        bytemuck::cast_slice_mut(&mut words).copy_from_slice(&bytes);
+        words
+    }
+
+    mod predictive_search_tests {
+        use crate::map::TrieView;
+
+        macro_rules! parameterized_tests {
+            ($($name:ident: $value:expr,)*) => {
+            $(
+                #[test]
+                fn $name() {
+                    let query = $value;
+                    let trie = super::build_trie();
+                    let words = super::to_words(&trie);
+                    let view = TrieView::<u8, u32>::new(bytemuck::cast_slice(&words)).unwrap();
+                    let results: Vec<(String, &u32)> = view.predictive_search(query).collect();
+                    let expected: Vec<(String, &u32)> = trie.predictive_search(query).collect();
+                    assert_eq!(results, expected);
+                    let results: Vec<(String, &u32)> = view.common_prefix_search(query).collect();
+                    let expected: Vec<(String, &u32)> = trie.common_prefix_search(query).collect();
+                    assert_eq!(results, expected);
+                    assert_eq!(view.exact_match(query), trie.exact_match(query));
+                    assert_eq!(view.is_prefix(query), trie.is_prefix(query));
+                }
+            )*
+            }
+        }
+
+        parameterized_tests! {
+            t1: "",
+            t2: "a",
+            t3: "app",
+            t4: "appl",
+            t5: "apple",
+            t6: "applesauce",
+            t7: "b",
+            t8: "アップ",
+            t9: "z",
+        }
+    }
+
+    #[test]
+    fn large() {
+        let keys: Vec<String> = (0..3000u32).map(|i| format!("{}", i * 7)).collect();
+        let trie = Trie::<u8, u32>::from_iter(keys.iter().zip(0..));
+        let words = to_words(&trie);
+        let view = TrieView::<u8, u32>::new(bytemuck::cast_slice(&words)).unwrap();
+        assert_eq!(view.len(), keys.len());
+        for (i, key) in keys.iter().enumerate() {
+            assert_eq!(view.exact_match(key), Some(&(i as u32)));
+        }
+        let results: Vec<(Vec<u8>, &u32)> = view.predictive_search("").collect();
+        let expected: Vec<(Vec<u8>, &u32)> = trie.iter().collect();
+        assert_eq!(results, expected);
+    }
+
+    #[test]
+    fn u32_labels() {
+        let trie = Trie::<u32, u8>::from_iter([(&[1, 2, 3][..], 0), (&[1, 2], 1)]);
+        let words = to_words(&trie);
+        let view = TrieView::<u32, u8>::new(bytemuck::cast_slice(&words)).unwrap();
+        assert_eq!(view.exact_match([1, 2]), Some(&1));
+    }
+
+    #[test]
+    fn empty() {
+        let trie: Trie<u8, u32> = TrieBuilder::new().build();
+        let words = to_words(&trie);
+        let view = TrieView::<u8, u32>::new(bytemuck::cast_slice(&words)).unwrap();
+        assert!(view.is_empty());
+        assert_eq!(view.exact_match("a"), None);
+        assert_eq!(view.predictive_search::<String, _>("").count(), 0);
+    }
+
+    #[test]
+    fn invalid_data() {
+        let words = to_words(&build_trie());
+        let bytes: &[u8] = bytemuck::cast_slice(&words);
+        let err = |bytes: &[u8]| TrieView::<u8, u32>::new(bytes).unwrap_err().kind();
+        assert_eq!(err(&bytes[..bytes.len() - 8]), ErrorKind::UnexpectedEof);
+        assert_eq!(
+            TrieView::<u8, u8>::new(bytes).unwrap_err().kind(),
+            ErrorKind::InvalidData
+        );
+        // The default encoding of write_to is varint labels.
+        let mut varint = Vec::new();
+        build_trie().write_to(&mut varint).unwrap();
+        let mut varint_words = vec![0u64; varint.len() / 8];
+        bytemuck::cast_slice_mut(&mut varint_words).copy_from_slice(&varint);
+        let varint_err = TrieView::<u8, u32>::new(bytemuck::cast_slice(&varint_words)).unwrap_err();
+        assert_eq!(varint_err.kind(), ErrorKind::InvalidData);
+        assert!(varint_err.to_string().contains("LabelEncoding::Fixed"));
+        let mut bad_louds = words.clone();
+        bad_louds[4] = 0;
+        assert_eq!(
+            err(bytemuck::cast_slice(&bad_louds)),
+            ErrorKind::InvalidData
+        );
+    }
+}
@@ -0,0 +1,437 @@
+//! Depth-first traversal of the trie's structure.
+use super::Trie;
+use louds_rs::LoudsNodeNum;
+
+/// Receives the nodes of a trie from [Trie::visit] in depth-first order.
+///
+/// Every method has an empty default implementation, so implement only what
+/// you need.
+pub trait Visitor<Label, Value> {
+    /// Called when a node is entered. The root has no label and a depth of
+    /// zero.
+    fn enter_node(&mut self, _label: Option<&Label>, _depth: usize) {}
+
+    /// Called after [Visitor::enter_node] if the node terminates an entry.
+    fn leaf(&mut self, _value: &Value) {}
+
+    /// Called after all of a node's children have been visited.
+    fn leave_node(&mut self) {}
+}
+
+/// Whether [Trie::predictive_search_visit] descends below a node.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Descent {
+    /// Visit the node's children.
+    Continue,
+    /// Skip the node's entire subtree.
+    Prune,
+}
+
+enum Step {
+    Enter(LoudsNodeNum, usize),
+    Leave,
+}
+
+impl<Label: Ord, Value> Trie<Label, Value> {
+    /// Walk every node of the trie depth-first in lexicographic order.
+    ///
+    /// The walk uses an explicit stack, so very deep tries will not overflow
+    /// the call stack.
+    ///
+    /// ```rust
+    /// use trie_rs::map::{Trie, Visitor};
+    ///
+    /// #[derive(Default)]
+    /// struct MaxDepth(usize);
+    ///
+    /// impl Visitor<u8, u8> for MaxDepth {
+    ///     fn enter_node(&mut self, _label: Option<&u8>, depth: usize) {
+    ///         self.0 = self.0.max(depth);
+    ///     }
+    /// }
+    ///
+    /// let trie = Trie::from_iter([("a", 0), ("app", 1), ("apple", 2)]);
+    /// let mut visitor = MaxDepth::default();
+    /// trie.visit(&mut visitor);
+    /// assert_eq!(visitor.0, 5);
+    /// ```
+    pub fn visit<V: Visitor<Label, Value>>(&self, visitor: &mut V) {
+        let mut stack = vec![Step::Enter(LoudsNodeNum(1), 0)];
+        while let Some(step) = stack.pop() {
+            match step {
+                Step::Enter(node_num, depth) => {
+                    let label = (depth > 0).then(|| self.label(node_num));
+                    visitor.enter_node(label, depth);
+                    if let Some(value) = self.value(node_num) {
+                        visitor.leaf(value);
+                    }
+                    stack.push(Step::Leave);
+                    let children: Vec<_> = self.children_node_nums(node_num).collect();
+                    stack.extend(
+                        children
+                            .into_iter()
+                            .rev()
+                            .map(|child| Step::Enter(child, depth + 1)),
+                    );
+                }
+                Step::Leave => visitor.leave_node(),
+            }
+        }
+    }
+
+    /// Call `visit` with the key and value, if any, of every node that starts
+    /// with `query` in lexicographic order, beginning with `query` itself.
+    /// Returning [Descent::Prune] skips the node's subtree.
+    ///
+    /// The key is only valid for the call as with [Trie::for_each].
+    ///
+    /// ```rust
+    /// use trie_rs::map::{Descent, Trie};
+    ///
+    /// let trie = Trie::from_iter([("a", 0), ("app", 1), ("apple", 2), ("application", 3)]);
+    /// let mut results = vec![];
+    /// trie.predictive_search_visit("ap", |key, value| {
+    ///     if let Some(value) = value {
+    ///         results.push((String::from_utf8(key.to_vec()).unwrap(), *value));
+    ///     }
+    ///     if key.len() < 5 { Descent::Continue } else { Descent::Prune }
+    /// });
+    /// assert_eq!(results, [("app".to_string(), 1), ("apple".to_string(), 2)]);
+    /// ```
+    pub fn predictive_search_visit(
+        &self,
+        query: impl AsRef<[Label]>,
+        mut visit: impl FnMut(&[Label], Option<&Value>) -> Descent,
+    ) where
+        Label: Clone,
+    {
+        let mut key = query.as_ref().to_vec();
+        let root = match self.prefix_node(&key) {
+            Some(node_num) => node_num,
+            None => return,
+        };
+        let query_len = key.len();
+        let mut stack = vec![(root, query_len)];
+        while let Some((node_num, depth)) = stack.pop() {
+            if depth > query_len {
+                key.truncate(depth - 1);
+                key.push(self.label(node_num).clone());
+            }
+            if visit(&key, self.value(node_num)) == Descent::Prune {
+                continue;
+            }
+            stack.extend(
+                self.children_node_nums(node_num)
+                    .rev()
+                    .map(|child| (child, depth + 1)),
+            );
+        }
+    }
+
+    /// Call `f` with the key and value of every entry that starts with
+    /// `query` in lexicographic order, building keys in `buf`.
+    ///
+    /// This yields the same entries as [Trie::predictive_search] without
+    /// allocating a key per entry: `buf` is cleared, filled with `query`, and
+    /// updated in place as the walk moves between siblings. Reuse one `buf`
+    /// across calls to avoid allocating at all once it has grown. The key is
+    /// only valid for the call as with [Trie::for_each].
+    ///
+    /// ```rust
+    /// use trie_rs::map::Trie;
+    ///
+    /// let trie = Trie::from_iter([("a", 0), ("app", 1), ("apple", 2), ("b", 3)]);
+    /// let mut buf = Vec::new();
+    /// let mut results = vec![];
+    /// trie.predictive_search_buffered("ap", &mut buf, |key, value| {
+    ///     results.push((String::from_utf8(key.to_vec()).unwrap(), *value));
+    /// });
+    /// assert_eq!(results, [("app".to_string(), 1), ("apple".to_string(), 2)]);
+    /// ```
+    pub fn predictive_search_buffered(
+        &self,
+        query: impl AsRef<[Label]>,
+        buf: &mut Vec<Label>,
+        mut f: impl FnMut(&[Label], &Value),
+    ) where
+        Label: Clone,
+    {
+        let query = query.as_ref();
+        buf.clear();
+        buf.extend_from_slice(query);
+        let root = match self.prefix_node(query) {
+            Some(node_num) => node_num,
+            None => return,
+        };
+        let mut stack = vec![(root, query.len())];
+        while let Some((node_num, depth)) = stack.pop() {
+            if depth > query.len() {
+                buf.truncate(depth - 1);
+                buf.push(self.label(node_num).clone());
+            }
+            if let Some(value) = self.value(node_num) {
+                f(buf, value);
+            }
+            stack.extend(
+                self.children_node_nums(node_num)
+                    .rev()
+                    .map(|child| (child, depth + 1)),
+            );
+        }
+    }
+
+    /// Call `f` with every entry's key and value in lexicographic order.
+    ///
+    /// The key is a view into a single buffer that is updated in place as the
+    /// walk descends, so no key is allocated. It is only valid for the call;
+    /// copy it with `to_vec()` to keep it.
+    ///
+    /// ```rust
+    /// use trie_rs::map::Trie;
+    ///
+    /// let trie = Trie::from_iter([("a", 0), ("app", 1), ("apple", 2)]);
+    /// let mut long_keys = 0;
+    /// trie.for_each(|key, _| if key.len() > 1 { long_keys += 1 });
+    /// assert_eq!(long_keys, 2);
+    /// ```
+    pub fn for_each(&self, mut f: impl FnMut(&[Label], &Value))
+    where
+        Label: Clone,
+    {
+        self.fold((), |(), key, value| f(key, value))
+    }
+
+    /// Fold every entry's key and value into an accumulator in lexicographic
+    /// order. The key is only valid for the call as with [Trie::for_each].
+    ///
+    /// ```rust
+    /// use trie_rs::map::Trie;
+    ///
+    /// let trie = Trie::from_iter([("a", 0), ("app", 1), ("apple", 2)]);
+    /// assert_eq!(trie.fold(0, |sum, _, value| sum + value), 3);
+    /// ```
+    pub fn fold<B>(&self, init: B, mut f: impl FnMut(B, &[Label], &Value) -> B) -> B
+    where
+        Label: Clone,
+    {
+        let mut acc = init;
+        let mut key = vec![];
+        let mut stack = vec![(LoudsNodeNum(1), 0)];
+        while let Some((node_num, depth)) = stack.pop() {
+            if depth > 0 {
+                key.truncate(depth - 1);
+                key.push(self.label(node_num).clone());
+            }
+            if let Some(value) = self.value(node_num) {
+                acc = f(acc, &key, value);
+            }
+            stack.extend(
+                self.children_node_nums(node_num)
+                    .rev()
+                    .map(|child| (child, depth + 1)),
+            );
+        }
+        acc
+    }
+
+    /// Fold the values of every entry that starts with `query` into an
+    /// accumulator in lexicographic order. Return `init` if there are none.
+    ///
+    /// No keys are built, so this is cheaper than folding over
+    /// [Trie::predictive_search].
+    ///
+    /// ```rust
+    /// use trie_rs::map::Trie;
+    ///
+    /// let trie = Trie::from_iter([("fruit/apple", 3), ("fruit/pear", 4), ("veg/kale", 5)]);
+    /// assert_eq!(trie.aggregate_prefix("fruit/", 0, |sum, hits| sum + hits), 7);
+    /// assert_eq!(trie.aggregate_prefix("meat/", 0, |sum, hits| sum + hits), 0);
+    /// ```
+    pub fn aggregate_prefix<A>(
+        &self,
+        query: impl AsRef<[Label]>,
+        init: A,
+        mut f: impl FnMut(A, &Value) -> A,
+    ) -> A {
+        let mut acc = init;
+        let mut stack = match self.prefix_node(query) {
+            Some(node_num) => vec![node_num],
+            None => return acc,
+        };
+        while let Some(node_num) = stack.pop() {
+            if let Some(value) = self.value(node_num) {
+                acc = f(acc, value);
+            }
+            stack.extend(self.children_node_nums(node_num).rev());
+        }
+        acc
+    }
+}
+
+#[cfg(test)]
+mod visit_tests {
+    use super::{Descent, Visitor};
+    use crate::map::{Trie, TrieBuilder};
+
+    #[derive(Default)]
+    struct Recorder(Vec<String>);
+
+    impl Visitor<u8, u8> for Recorder {
+        fn enter_node(&mut self, label: Option<&u8>, depth: usize) {
+            let label = label.map(|l| *l as char).unwrap_or('^');
+            self.0.push(format!("{}{}", label, depth));
+        }
+
+        fn leaf(&mut self, value: &u8) {
+            self.0.push(format!("={}", value));
+        }
+
+        fn leave_node(&mut self) {
+            self.0.push("<".to_string());
+        }
+    }
+
+    #[test]
+    fn visit_order() {
+        let trie = Trie::<u8, u8>::from_iter([("ab", 0), ("a", 1), ("b", 2)]);
+        let mut recorder = Recorder::default();
+        trie.visit(&mut recorder);
+        assert_eq!(
+            recorder.0,
+            ["^0", "a1", "=1", "b2", "=0", "<", "<", "b1", "=2", "<", "<"]
+        );
+    }
+
+    #[test]
+    fn visit_empty() {
+        let trie: Trie<u8, u8> = TrieBuilder::new().build();
+        let mut recorder = Recorder::default();
+        trie.visit(&mut recorder);
+        assert_eq!(recorder.0, ["^0", "<"]);
+    }
+
+    #[test]
+    fn visit_deep() {
+        let key = vec![0u8; 10_000];
+        let trie = Trie::<u8, u8>::from_iter([(key, 7)]);
+        struct Count(usize, usize);
+        impl Visitor<u8, u8> for Count {
+            fn enter_node(&mut self, _label: Option<&u8>, _depth: usize) {
+                self.0 += 1;
+            }
+            fn leave_node(&mut self) {
+                self.1 += 1;
+            }
+        }
+        let mut count = Count(0, 0);
+        trie.visit(&mut count);
+        assert_eq!((count.0, count.1), (10_001, 10_001));
+    }
+
+    #[test]
+    fn for_each_matches_iter() {
+        let trie = Trie::<u8, u8>::from_iter([("ab", 0), ("a", 1), ("b", 2), ("abc", 3)]);
+        let mut entries = vec![];
+        trie.for_each(|key, value| entries.push((key.to_vec(), *value)));
+        let expected: Vec<(Vec<u8>, u8)> = trie.iter().map(|(k, v)| (k, *v)).collect();
+        assert_eq!(entries, expected);
+    }
+
+    #[test]
+    fn predictive_search_buffered_matches_iter() {
+        let trie = Trie::<u8, u8>::from_iter([
+            ("a", 0),
+            ("app", 1),
+            ("apple", 2),
+            ("application", 3),
+            ("apply", 4),
+            ("b", 5),
+        ]);
+        let mut buf = vec![];
+        for query in ["", "a", "app", "appl", "apple", "applex", "b", "x"] {
+            let mut entries = vec![];
+            trie.predictive_search_buffered(query, &mut buf, |key, value| {
+                entries.push((key.to_vec(), *value))
+            });
+            let expected: Vec<(Vec<u8>, u8)> = trie
+                .predictive_search(query)
+                .map(|(k, v): (Vec<u8>, &u8)| (k, *v))
+                .collect();
+            assert_eq!(entries, expected, "query {:?}", query);
+        }
+    }
+
+    #[test]
+    fn fold() {
+        let trie = Trie::<u8, u8>::from_iter([("ab", 0), ("a", 1), ("b", 2), ("abc", 3)]);
+        assert_eq!(trie.fold(0, |sum, _, value| sum + value), 6);
+        assert_eq!(trie.fold(0, |sum, key, _| sum + key.len()), 7);
+        let empty: Trie<u8, u8> = TrieBuilder::new().build();
+        assert_eq!(empty.fold(0, |sum, _, value| sum + value), 0);
+    }
+
+    #[test]
+    fn aggregate_prefix() {
+        let trie = Trie::<u8, u8>::from_iter([("ab", 1), ("a", 2), ("b", 4), ("abc", 8)]);
+        assert_eq!(trie.aggregate_prefix("", 0, |sum, value| sum + value), 15);
+        assert_eq!(trie.aggregate_prefix("a", 0, |sum, value| sum + value), 11);
+        assert_eq!(trie.aggregate_prefix("ab", 0, |sum, value| sum + value), 9);
+        assert_eq!(
+            trie.aggregate_prefix("abcd", 0, |sum, value| sum + value),
+            0
+        );
+        assert_eq!(trie.aggregate_prefix("x", 42, |sum, value| sum + value), 42);
+        let values = trie.aggregate_prefix("a", vec![], |mut values, value| {
+            values.push(*value);
+            values
+        });
+        assert_eq!(values, [2, 1, 8]);
+    }
+
+    #[test]
+    fn predictive_search_visit_prunes_subtree() {
+        let trie =
+            Trie::<u8, u8>::from_iter([("a", 0), ("ab", 1), ("abc", 2), ("b", 3), ("bc", 4)]);
+        let mut visited = vec![];
+        trie.predictive_search_visit("", |key, value| {
+            visited.push((key.to_vec(), value.copied()));
+            if key == b"ab" {
+                Descent::Prune
+            } else {
+                Descent::Continue
+            }
+        });
+        assert_eq!(
+            visited,
+            [
+                (b"".to_vec(), None),
+                (b"a".to_vec(), Some(0)),
+                (b"ab".to_vec(), Some(1)),
+                (b"b".to_vec(), Some(3)),
+                (b"bc".to_vec(), Some(4)),
+            ]
+        );
+    }
+
+    #[test]
+    fn predictive_search_visit_query() {
+        let trie = Trie::<u8, u8>::from_iter([("a", 0), ("ab", 1), ("abc", 2), ("b", 3)]);
+        let mut keys = vec![];
+        trie.predictive_search_visit("ab", |key, _| {
+            keys.push(key.to_vec());
+            Descent::Continue
+        });
+        assert_eq!(keys, [b"ab".to_vec(), b"abc".to_vec()]);
+        let mut count = 0;
+        trie.predictive_search_visit("x", |_, _| {
+            count += 1;
+            Descent::Continue
+        });
+        assert_eq!(count, 0);
+        trie.predictive_search_visit("a", |_, _| {
+            count += 1;
+            Descent::Prune
+        });
+        assert_eq!(count, 1);
+    }
+}
@@ -0,0 +1,176 @@
+//! Unicode normalization. Requires the "unicode-normalization" crate feature.
+//!
+//! The same text may be encoded by different sequences of `char`s, e.g., "é"
+//! may be one precomposed `char` or an "e" followed by a combining accent. A
+//! plain `Trie<char>` treats these as different keys. A [NormalizingTrie]
+//! normalizes both its keys and its queries to one [Form], so they match.
+//!
+//! ```rust
+//! use trie_rs::normalize::{Form, NormalizingTrieBuilder};
+//!
+//! let mut builder = NormalizingTrieBuilder::new(Form::Nfc);
+//! builder.push("caf\u{e9}", 0); // precomposed "é"
+//! let trie = builder.build();
+//!
+//! assert_eq!(trie.exact_match("cafe\u{301}"), Some(&0)); // "e" + combining acute
+//! ```
+//!
+//! Keys and queries are always normalized with the same form. A trie built
+//! from already normalized keys with [crate::map::TrieBuilder] must be queried
+//! with keys normalized to that same form.
+use crate::iter::{PrefixIter, SearchIter};
+use crate::map;
+use crate::try_collect::TryFromIterator;
+use unicode_normalization::UnicodeNormalization;
+
+/// A Unicode normalization form.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Form {
+    /// Canonical composition, e.g., "e" with a combining accent becomes "é".
+    Nfc,
+    /// Compatibility composition, which also folds compatibility characters,
+    /// e.g., the ligature "ﬁ" becomes "fi" and half-width "ｱ" becomes "ア".
+    Nfkc,
+}
+
+impl Form {
+    /// Return `s` normalized to this form.
+    pub fn normalize(self, s: &str) -> Vec<char> {
+        match self {
+            Form::Nfc => s.nfc().collect(),
+            Form::Nfkc => s.nfkc().collect(),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+/// A trie of `char`s whose keys and queries are normalized.
+pub struct NormalizingTrie<Value> {
+    trie: map::Trie<char, Value>,
+    form: Form,
+}
+
+#[derive(Debug, Clone)]
+/// A trie builder for [NormalizingTrie].
+pub struct NormalizingTrieBuilder<Value> {
+    builder: map::TrieBuilder<char, Value>,
+    form: Form,
+}
+
+impl<Value> NormalizingTrieBuilder<Value> {
+    /// Return a [NormalizingTrieBuilder] that normalizes keys to `form`.
+    pub fn new(form: Form) -> Self {
+        Self {
+            builder: map::TrieBuilder::new(),
+            form,
+        }
+    }
+
+    /// Add an entry and value.
+    pub fn push(&mut self, entry: &str, value: Value) {
+        self.builder.insert(self.form.normalize(entry), value);
+    }
+
+    /// Build a [NormalizingTrie].
+    pub fn build(self) -> NormalizingTrie<Value> {
+        NormalizingTrie {
+            trie: self.builder.build(),
+            form: self.form,
+        }
+    }
+}
+
+impl<Value> NormalizingTrie<Value> {
+    /// Return `Some(&Value)` if `query` is an exact match once normalized.
+    pub fn exact_match(&self, query: &str) -> Option<&Value> {
+        self.trie.exact_match(self.form.normalize(query))
+    }
+
+    /// Return the stored key and its value if `query` is an exact match once
+    /// normalized. The key is returned normalized.
+    pub fn get_key_value(&self, query: &str) -> Option<(String, &Value)> {
+        self.trie.get_key_value(self.form.normalize(query))
+    }
+
+    /// Return true if `query` is a prefix once normalized.
+    pub fn is_prefix(&self, query: &str) -> bool {
+        self.trie.is_prefix(self.form.normalize(query))
+    }
+
+    /// Return all entries and their values that match `query` once
+    /// normalized. The keys are returned normalized.
+    pub fn predictive_search<C, M>(&self, query: &str) -> SearchIter<'_, char, Value, C, M>
+    where
+        C: TryFromIterator<char, M> + Clone,
+    {
+        self.trie.predictive_search(self.form.normalize(query))
+    }
+
+    /// Return the common prefixes of `query` once normalized. The keys are
+    /// returned normalized.
+    pub fn common_prefix_search<C, M>(&self, query: &str) -> PrefixIter<'_, char, Value, C, M>
+    where
+        C: TryFromIterator<char, M>,
+    {
+        self.trie.common_prefix_search(self.form.normalize(query))
+    }
+
+    /// Return the normalization form of keys and queries.
+    pub fn form(&self) -> Form {
+        self.form
+    }
+
+    /// Return the underlying trie of normalized keys.
+    pub fn trie(&self) -> &map::Trie<char, Value> {
+        &self.trie
+    }
+}
+
+#[cfg(test)]
+mod search_tests {
+    use super::*;
+
+    fn build_trie(form: Form) -> NormalizingTrie<u8> {
+        let mut builder = NormalizingTrieBuilder::new(form);
+        builder.push("caf\u{e9}", 0);
+        builder.push("すし", 1);
+        builder.push("\u{fb01}sh", 2);
+        builder.push("ｱｯﾌﾟﾙ", 3);
+        builder.build()
+    }
+
+    #[test]
+    fn nfc() {
+        let trie = build_trie(Form::Nfc);
+        assert_eq!(trie.exact_match("cafe\u{301}"), Some(&0));
+        assert_eq!(trie.exact_match("caf\u{e9}"), Some(&0));
+        // "す" + combining voiced mark composes to "ず", not "す".
+        assert_eq!(trie.exact_match("す\u{3099}し"), None);
+        assert_eq!(trie.exact_match("fish"), None);
+        assert_eq!(trie.exact_match("\u{fb01}sh"), Some(&2));
+    }
+
+    #[test]
+    fn nfkc() {
+        let trie = build_trie(Form::Nfkc);
+        assert_eq!(trie.exact_match("cafe\u{301}"), Some(&0));
+        assert_eq!(trie.exact_match("fish"), Some(&2));
+        assert_eq!(trie.exact_match("アップル"), Some(&3));
+        assert!(trie.is_prefix("ｱｯ"));
+        assert_eq!(
+            trie.get_key_value("\u{fb01}sh"),
+            Some(("fish".to_string(), &2))
+        );
+    }
+
+    #[test]
+    fn search() {
+        let trie = build_trie(Form::Nfc);
+        let results: Vec<(String, &u8)> = trie.predictive_search("cafe").collect();
+        assert_eq!(results, []);
+        let results: Vec<(String, &u8)> = trie.predictive_search("caf").collect();
+        assert_eq!(results, [("caf\u{e9}".to_string(), &0)]);
+        let results: Vec<(String, &u8)> = trie.common_prefix_search("cafe\u{301}s").collect();
+        assert_eq!(results, [("caf\u{e9}".to_string(), &0)]);
+    }
+}
@@ -0,0 +1,287 @@
+//! Phonetic search
+//!
+//! A [PhoneticTrie] stores each key under its phonetic code, e.g. its
+//! [soundex] code, and keeps the original keys so that a query returns every
+//! key that "sounds like" it.
+//!
+//! ```rust
+//! use trie_rs::phonetic::PhoneticTrieBuilder;
+//!
+//! let mut builder = PhoneticTrieBuilder::new();
+//! builder.push("Robert");
+//! builder.push("Rupert");
+//! builder.push("Rubin");
+//! let trie = builder.build();
+//!
+//! let results: Vec<&[u8]> = trie.phonetic_search("Robbert").collect();
+//! assert_eq!(results, [&b"Robert"[..], &b"Rupert"[..]]);
+//! ```
+//!
+//! Any encoder of type `Fn(&[Label]) -> Vec<Label>` may be supplied with
+//! [PhoneticTrieBuilder::with_encoder], which is how one would plug in
+//! Metaphone for instance.
+use crate::map;
+use std::collections::BTreeMap;
+use std::fmt;
+
+/// The default encoder of a [PhoneticTrieBuilder].
+pub type Encoder<Label> = fn(&[Label]) -> Vec<Label>;
+
+#[derive(Clone)]
+/// A trie that finds keys by their phonetic code.
+pub struct PhoneticTrie<Label, F = Encoder<Label>> {
+    trie: map::Trie<Label, Vec<Vec<Label>>>,
+    encoder: F,
+}
+
+#[derive(Clone)]
+/// A trie builder for [PhoneticTrie].
+pub struct PhoneticTrieBuilder<Label, F = Encoder<Label>> {
+    entries: BTreeMap<Vec<Label>, Vec<Vec<Label>>>,
+    encoder: F,
+}
+
+impl PhoneticTrieBuilder<u8> {
+    /// Return a [PhoneticTrieBuilder] that encodes keys with [soundex].
+    pub fn new() -> Self {
+        Self::with_encoder(soundex)
+    }
+}
+
+impl Default for PhoneticTrieBuilder<u8> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<Label: Ord, F> PhoneticTrieBuilder<Label, F>
+where
+    F: Fn(&[Label]) -> Vec<Label>,
+{
+    /// Return a [PhoneticTrieBuilder] that encodes keys with `encoder`.
+    pub fn with_encoder(encoder: F) -> Self {
+        Self {
+            entries: BTreeMap::new(),
+            encoder,
+        }
+    }
+
+    /// Add a cloneable entry. See [PhoneticTrieBuilder::insert].
+    pub fn push<Arr: AsRef<[Label]>>(&mut self, entry: Arr) -> bool
+    where
+        Label: Clone,
+    {
+        self.insert(entry.as_ref().to_vec())
+    }
+
+    /// Add an entry and return true, or return false and drop it if its
+    /// phonetic code is empty, e.g., a key with no ASCII letter under
+    /// [soundex]. A dropped entry can never be found.
+    ///
+    /// ```rust
+    /// use trie_rs::phonetic::PhoneticTrieBuilder;
+    ///
+    /// let mut builder = PhoneticTrieBuilder::new();
+    /// assert!(builder.insert(*b"Robert"));
+    /// assert!(!builder.insert(*b"123"));
+    /// ```
+    pub fn insert<Arr: IntoIterator<Item = Label>>(&mut self, entry: Arr) -> bool {
+        let entry: Vec<Label> = entry.into_iter().collect();
+        let code = (self.encoder)(&entry);
+        if code.is_empty() {
+            return false;
+        }
+        let originals = self.entries.entry(code).or_default();
+        if let Err(i) = originals.binary_search(&entry) {
+            originals.insert(i, entry);
+        }
+        true
+    }
+
+    /// Build a [PhoneticTrie].
+    pub fn build(self) -> PhoneticTrie<Label, F> {
+        let mut builder = map::TrieBuilder::new();
+        for (code, originals) in self.entries {
+            builder.insert(code, originals);
+        }
+        PhoneticTrie {
+            trie: builder.build(),
+            encoder: self.encoder,
+        }
+    }
+}
+
+impl<Label: Ord, F> PhoneticTrie<Label, F>
+where
+    F: Fn(&[Label]) -> Vec<Label>,
+{
+    /// Return the original keys whose phonetic code matches that of `query`.
+    /// The keys are sorted.
+    pub fn phonetic_search(&self, query: impl AsRef<[Label]>) -> impl Iterator<Item = &[Label]> {
+        let code = (self.encoder)(query.as_ref());
+        self.trie
+            .exact_match(code)
+            .into_iter()
+            .flat_map(|originals| originals.iter().map(Vec::as_slice))
+    }
+
+    /// Return the phonetic code of `query`.
+    pub fn encode(&self, query: impl AsRef<[Label]>) -> Vec<Label> {
+        (self.encoder)(query.as_ref())
+    }
+
+    /// Return the underlying trie which maps phonetic codes to original keys.
+    pub fn codes(&self) -> &map::Trie<Label, Vec<Vec<Label>>> {
+        &self.trie
+    }
+}
+
+impl<Label: fmt::Debug, F> fmt::Debug for PhoneticTrie<Label, F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PhoneticTrie")
+            .field("trie", &self.trie)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<Label: fmt::Debug, F> fmt::Debug for PhoneticTrieBuilder<Label, F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PhoneticTrieBuilder")
+            .field("entries", &self.entries)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Return the American Soundex code of an ASCII `word`, e.g., "Robert" is
+/// "R163".
+///
+/// Non-alphabetic bytes are ignored. Returns an empty code if `word` has no
+/// ASCII letters.
+///
+/// ```rust
+/// use trie_rs::phonetic::soundex;
+///
+/// assert_eq!(soundex(b"Robert"), b"R163");
+/// assert_eq!(soundex(b"Ashcraft"), b"A261");
+/// assert_eq!(soundex(b"Lee"), b"L000");
+/// ```
+pub fn soundex(word: &[u8]) -> Vec<u8> {
+    let mut letters = word
+        .iter()
+        .filter(|c| c.is_ascii_alphabetic())
+        .map(|c| c.to_ascii_uppercase());
+    let first = match letters.next() {
+        Some(c) => c,
+        None => return vec![],
+    };
+    let mut code = vec![first];
+    let mut last = soundex_digit(first);
+    for c in letters {
+        if code.len() == 4 {
+            break;
+        }
+        // 'H' and 'W' do not separate letters with the same code.
+        if let Some(digit) = soundex_digit(c) {
+            if digit != b'0' && Some(digit) != last {
+                code.push(digit);
+            }
+            last = Some(digit);
+        }
+    }
+    code.resize(4, b'0');
+    code
+}
+
+/// Return the Soundex digit of an uppercase ASCII letter: `b'0'` for vowels
+/// and `None` for 'H' and 'W'.
+fn soundex_digit(c: u8) -> Option<u8> {
+    match c {
+        b'B' | b'F' | b'P' | b'V' => Some(b'1'),
+        b'C' | b'G' | b'J' | b'K' | b'Q' | b'S' | b'X' | b'Z' => Some(b'2'),
+        b'D' | b'T' => Some(b'3'),
+        b'L' => Some(b'4'),
+        b'M' | b'N' => Some(b'5'),
+        b'R' => Some(b'6'),
+        b'H' | b'W' => None,
+        _ => Some(b'0'),
+    }
+}
+
+#[cfg(test)]
+mod search_tests {
+    use super::*;
+
+    fn build_trie() -> PhoneticTrie<u8> {
+        let mut builder = PhoneticTrieBuilder::new();
+        builder.push("Robert");
+        builder.push("Rupert");
+        builder.push("Rubin");
+        builder.push("Ashcraft");
+        builder.push("Ashcroft");
+        builder.push("Tymczak");
+        builder.push("Pfister");
+        builder.push("Robert");
+        builder.build()
+    }
+
+    mod soundex_tests {
+        macro_rules! parameterized_tests {
+            ($($name:ident: $value:expr,)*) => {
+            $(
+                #[test]
+                fn $name() {
+                    let (word, expected_code) = $value;
+                    assert_eq!(super::soundex(word.as_bytes()), expected_code.as_bytes());
+                }
+            )*
+            }
+        }
+
+        parameterized_tests! {
+            t1: ("Robert", "R163"),
+            t2: ("Rupert", "R163"),
+            t3: ("Rubin", "R150"),
+            t4: ("Ashcraft", "A261"),
+            t5: ("Tymczak", "T522"),
+            t6: ("Pfister", "P236"),
+            t7: ("honeyman", "H555"),
+            t8: ("A", "A000"),
+            t9: ("", ""),
+            t10: ("123", ""),
+        }
+    }
+
+    #[test]
+    fn phonetic_search() {
+        let trie = build_trie();
+        let results: Vec<&[u8]> = trie.phonetic_search("rupurt").collect();
+        assert_eq!(results, [&b"Robert"[..], &b"Rupert"[..]]);
+        let results: Vec<&[u8]> = trie.phonetic_search("Ashcrofft").collect();
+        assert_eq!(results, [&b"Ashcraft"[..], &b"Ashcroft"[..]]);
+        assert_eq!(trie.phonetic_search("Smith").next(), None);
+        assert_eq!(trie.phonetic_search("").next(), None);
+    }
+
+    #[test]
+    fn empty_code() {
+        let mut builder = PhoneticTrieBuilder::new();
+        assert!(builder.push("Robert"));
+        assert!(!builder.push("123"));
+        assert!(!builder.push(""));
+        let trie = builder.build();
+        assert_eq!(trie.phonetic_search("123").count(), 0);
+        assert_eq!(trie.codes().iter::<Vec<u8>, _>().count(), 1);
+    }
+
+    #[test]
+    fn custom_encoder() {
+        let mut builder = PhoneticTrieBuilder::with_encoder(|word: &[char]| {
+            word.iter().map(|c| c.to_ascii_lowercase()).collect()
+        });
+        builder.insert("Apple".chars());
+        builder.insert("APPLE".chars());
+        let trie = builder.build();
+        let query: Vec<char> = "apple".chars().collect();
+        assert_eq!(trie.phonetic_search(query).count(), 2);
+    }
+}
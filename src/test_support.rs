@@ -0,0 +1,174 @@
+//! Fixtures for property-testing code built on tries. Requires the "testing"
+//! crate feature.
+//!
+//! [random_trie] makes a trie with Unicode keys and varied values along with
+//! a [BTreeMap] holding the same entries as a reference model.
+//! [assert_matches_model] checks a trie against such a model.
+//!
+//! ```rust
+//! use trie_rs::test_support::{assert_matches_model, random_trie};
+//!
+//! let (trie, model) = random_trie(42, 100);
+//! assert_eq!(model.len(), 100);
+//! assert_matches_model(&trie, &model);
+//! ```
+//!
+//! The same seed always gives the same trie, so a failing case can be
+//! reproduced from its seed.
+use crate::map::{Trie, TrieBuilder};
+use std::collections::BTreeMap;
+
+/// Characters that keys are made of: ASCII, accented Latin, kana, kanji, and
+/// emoji, which encode to one to four bytes in UTF-8.
+const ALPHABET: [char; 10] = ['a', 'b', 'c', 'é', 'ü', 'ア', 'ッ', 'プ', '日', '🍎'];
+
+/// Longest key in characters.
+const MAX_KEY_LEN: u64 = 8;
+
+/// Number of distinct keys there are of 1 to [MAX_KEY_LEN] characters from
+/// [ALPHABET].
+const MAX_ENTRIES: u64 = {
+    let mut total = 0;
+    let mut keys = 1;
+    let mut len = 0;
+    while len < MAX_KEY_LEN {
+        keys *= ALPHABET.len() as u64;
+        total += keys;
+        len += 1;
+    }
+    total
+};
+
+/// A small, deterministic pseudorandom number generator (SplitMix64).
+struct SplitMix64(u64);
+
+impl SplitMix64 {
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// Return a number in `0..n`.
+    fn below(&mut self, n: u64) -> u64 {
+        self.next() % n
+    }
+}
+
+/// Return `n` distinct random entries made from `seed`.
+///
+/// Keys are 1 to 8 characters drawn from a small alphabet, so many share
+/// prefixes. Values are random `u32`s.
+///
+/// # Panics
+///
+/// If `n` is more than the number of distinct keys there are.
+pub fn random_entries(seed: u64, n: usize) -> BTreeMap<String, u32> {
+    assert!(
+        n as u64 <= MAX_ENTRIES,
+        "cannot make {} distinct keys, only {}",
+        n,
+        MAX_ENTRIES
+    );
+    let mut rng = SplitMix64(seed);
+    let mut entries = BTreeMap::new();
+    while entries.len() < n {
+        let len = 1 + rng.below(MAX_KEY_LEN);
+        let key: String = (0..len)
+            .map(|_| ALPHABET[rng.below(ALPHABET.len() as u64) as usize])
+            .collect();
+        entries.insert(key, rng.next() as u32);
+    }
+    entries
+}
+
+/// Return a trie of `n` distinct random entries made from `seed` and a model
+/// of the same entries. See [random_entries].
+pub fn random_trie(seed: u64, n: usize) -> (Trie<u8, u32>, BTreeMap<String, u32>) {
+    let model = random_entries(seed, n);
+    let mut builder = TrieBuilder::with_capacity(n);
+    for (key, value) in &model {
+        builder.push(key, *value);
+    }
+    (builder.build(), model)
+}
+
+/// Panic unless `trie` holds exactly the entries of `model`.
+///
+/// Every key of the model must be an exact match with its value, iteration
+/// must yield the model's entries in order, and every proper prefix of a key
+/// must complete to the model's entries that start with it.
+pub fn assert_matches_model(trie: &Trie<u8, u32>, model: &BTreeMap<String, u32>) {
+    for (key, value) in model {
+        assert_eq!(trie.exact_match(key), Some(value), "exact_match({:?})", key);
+    }
+    let entries: Vec<(String, &u32)> = trie.iter().collect();
+    let expected: Vec<(String, &u32)> = model.iter().map(|(k, v)| (k.clone(), v)).collect();
+    assert_eq!(entries, expected, "iter()");
+    for key in model.keys() {
+        for (end, _) in key.char_indices().skip(1) {
+            let prefix = &key[..end];
+            let results: Vec<(String, &u32)> = trie.predictive_search(prefix).collect();
+            let expected: Vec<(String, &u32)> = model
+                .range(prefix.to_string()..)
+                .take_while(|(k, _)| k.starts_with(prefix))
+                .map(|(k, v)| (k.clone(), v))
+                .collect();
+            assert_eq!(results, expected, "predictive_search({:?})", prefix);
+            assert_eq!(trie.exact_match(prefix), model.get(prefix));
+        }
+    }
+}
+
+#[cfg(test)]
+mod test_support_tests {
+    use super::{assert_matches_model, random_entries, random_trie};
+
+    #[test]
+    fn deterministic() {
+        assert_eq!(random_entries(7, 50), random_entries(7, 50));
+        assert_ne!(random_entries(7, 50), random_entries(8, 50));
+    }
+
+    #[test]
+    fn unicode_keys() {
+        let model = random_entries(1, 200);
+        assert_eq!(model.len(), 200);
+        assert!(model.keys().any(|key| key.len() > key.chars().count()));
+        assert!(model
+            .keys()
+            .all(|key| (1..=8).contains(&key.chars().count())));
+    }
+
+    #[test]
+    fn matches_model() {
+        for seed in 0..10 {
+            let (trie, model) = random_trie(seed, 300);
+            assert_eq!(trie.check(), Ok(()));
+            assert_matches_model(&trie, &model);
+        }
+        let (trie, model) = random_trie(0, 0);
+        assert_matches_model(&trie, &model);
+    }
+
+    #[test]
+    fn max_entries() {
+        assert_eq!(super::MAX_ENTRIES, 111_111_110);
+    }
+
+    #[test]
+    #[should_panic(expected = "cannot make")]
+    fn too_many_entries() {
+        random_entries(0, super::MAX_ENTRIES as usize + 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "exact_match")]
+    fn detects_mismatch() {
+        let (trie, mut model) = random_trie(3, 20);
+        model.insert("zzz".to_string(), 0);
+        assert_matches_model(&trie, &model);
+    }
+}
@@ -16,6 +16,37 @@ impl<Label: Ord> TrieBuilder<Label> {
         Self(map::TrieBuilder::new())
     }
 
+    /// Return a [TrieBuilder] that expects about `keys` entries. See
+    /// [map::TrieBuilder::with_capacity].
+    pub fn with_capacity(keys: usize) -> Self {
+        Self(map::TrieBuilder::with_capacity(keys))
+    }
+
+    /// Return a [TrieBuilder] holding every entry of `trie`. See
+    /// [map::TrieBuilder::from_trie].
+    pub fn from_trie(trie: &Trie<Label>) -> Self
+    where
+        Label: Clone,
+    {
+        Self(map::TrieBuilder::from_trie(&trie.0))
+    }
+
+    /// Keep the order in which entries are pushed. See
+    /// [map::TrieBuilder::with_insertion_order].
+    pub fn with_insertion_order(self) -> Self {
+        Self(self.0.with_insertion_order())
+    }
+
+    /// Set what happens when an entry is pushed again. See
+    /// [map::TrieBuilder::on_duplicate].
+    ///
+    /// # Errors
+    /// With [map::DuplicatePolicy::Error], [TrieBuilder::build] panics on
+    /// duplicates; use [TrieBuilder::try_build] to get an error instead.
+    pub fn on_duplicate(self, policy: map::DuplicatePolicy) -> Self {
+        Self(self.0.on_duplicate(policy))
+    }
+
     /// Add a cloneable entry.
     pub fn push<Arr: AsRef<[Label]>>(&mut self, entry: Arr)
     where
@@ -29,10 +60,64 @@ impl<Label: Ord> TrieBuilder<Label> {
         self.0.insert(entry, ());
     }
 
+    /// Move every entry of `other` into this builder.
+    pub fn append(&mut self, other: TrieBuilder<Label>) {
+        self.0.append(other.0)
+    }
+
+    /// Add a batch of entries that are sorted among themselves. See
+    /// [map::TrieBuilder::push_sorted_batch].
+    pub fn push_sorted_batch(&mut self, batch: impl IntoIterator<Item = Vec<Label>>) {
+        self.0
+            .push_sorted_batch(batch.into_iter().map(|entry| (entry, ())))
+    }
+
+    /// Remove an entry and return true if it was present.
+    pub fn remove(&mut self, entry: impl AsRef<[Label]>) -> bool {
+        self.0.remove(entry).is_some()
+    }
+
+    /// Return true if no entry has been added.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Remove all entries so the builder may be reused.
+    pub fn clear(&mut self) {
+        self.0.clear()
+    }
+
+    /// Build a [Trie], or return an error if duplicate entries were pushed
+    /// under [map::DuplicatePolicy::Error].
+    pub fn try_build(self) -> Result<Trie<Label>, map::DuplicateKeyError> {
+        self.0.try_build().map(Trie)
+    }
+
     /// Build a [Trie].
+    ///
+    /// # Panics
+    /// If duplicate entries were pushed under [map::DuplicatePolicy::Error].
     pub fn build(self) -> Trie<Label> {
         Trie(self.0.build())
     }
+
+    /// Build a [Trie] directly from keys that are already strictly sorted with
+    /// no duplicates. See [map::TrieBuilder::build_from_sorted_unchecked] for
+    /// what happens if they are not.
+    pub fn build_from_sorted_unchecked(keys: impl IntoIterator<Item = Vec<Label>>) -> Trie<Label> {
+        Trie(map::TrieBuilder::build_from_sorted_unchecked(
+            keys.into_iter().map(|key| (key, ())),
+        ))
+    }
+}
+
+#[cfg(feature = "unicode-segmentation")]
+impl TrieBuilder<String> {
+    /// Add an entry whose labels are the grapheme clusters of `s`. See
+    /// [map::TrieBuilder::push_graphemes].
+    pub fn push_graphemes(&mut self, s: &str) {
+        self.0.push_graphemes(s, ());
+    }
 }
 
 impl<Label: Ord> Default for TrieBuilder<Label> {
@@ -1,12 +1,22 @@
-use crate::inc_search::IncSearch;
-use crate::iter::{Keys, KeysExt, PostfixIter, PrefixIter, SearchIter};
-use crate::map;
-use crate::try_collect::TryFromIterator;
+use crate::inc_search::{CommonPrefixMatcher, FuzzyIncSearch, IncSearch, Position};
+use crate::iter::{
+    AnnotatedKeys, BfNodes, BoundedSearchIter, DfNodes, Keys, KeysExt, OrderedSearchIter,
+    PostfixIter, PrefixIter, SearchCursor, SearchIter, SearchOrder, TryCollectIter, TryKeys,
+    WithDepth,
+};
+use crate::map::{self, FixedBytes, Glob, Visitor};
+use crate::try_collect::{Collect, TryFromIterator};
+use std::borrow::Borrow;
+use std::io::{self, Read, Write};
 use std::iter::FromIterator;
+use std::str::Utf8Error;
 
 #[cfg(feature = "mem_dbg")]
 use mem_dbg::MemDbg;
 
+/// Keys of a fallible search.
+type TryKeysOf<I, C, M> = TryKeys<TryCollectIter<I, C, M>>;
+
 #[derive(Debug, Clone)]
 #[cfg_attr(feature = "mem_dbg", derive(mem_dbg::MemDbg, mem_dbg::MemSize))]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
@@ -30,10 +40,44 @@ impl<Label: Ord> Trie<Label> {
     /// assert!(trie.exact_match("application"));
     /// assert!(trie.exact_match("app"));
     /// assert!(!trie.exact_match("appla"));
+    /// assert!(!trie.exact_match(""));
     ///
     /// ```
     pub fn exact_match(&self, query: impl AsRef<[Label]>) -> bool {
-        self.0.exact_match(query).is_some()
+        // Only the terminal bit is needed; skip the rank to find the `()`.
+        self.0.exact_match_node(query).is_some()
+    }
+
+    /// Return whether each query is an exact match, in order. See
+    /// [map::Trie::exact_match_many].
+    pub fn exact_match_many<'a, Q>(
+        &'a self,
+        queries: impl IntoIterator<Item = Q> + 'a,
+    ) -> impl Iterator<Item = bool> + 'a
+    where
+        Q: AsRef<[Label]> + 'a,
+    {
+        self.0
+            .exact_match_many(queries)
+            .map(|value| value.is_some())
+    }
+
+    /// Return the sequence number of the push of `key`. See
+    /// [map::Trie::insertion_index].
+    pub fn insertion_index(&self, key: impl AsRef<[Label]>) -> Option<u64> {
+        self.0.insertion_index(key)
+    }
+
+    /// Return true if every key is an exact match. See
+    /// [map::Trie::contains_all].
+    pub fn contains_all<Q: AsRef<[Label]>>(&self, keys: impl IntoIterator<Item = Q>) -> bool {
+        self.0.contains_all(keys)
+    }
+
+    /// Return true if any key is an exact match. See
+    /// [map::Trie::contains_any].
+    pub fn contains_any<Q: AsRef<[Label]>>(&self, keys: impl IntoIterator<Item = Q>) -> bool {
+        self.0.contains_any(keys)
     }
 
     /// Return the common prefixes of `query`.
@@ -66,7 +110,46 @@ impl<Label: Ord> Trie<Label> {
         self.0.common_prefix_search(query).keys()
     }
 
-    /// Return all entries that match `query`.
+    /// Return the common prefixes of `query` and the number of labels of
+    /// `query` consumed by the longest one. See
+    /// [map::Trie::common_prefix_search_with_rest].
+    pub fn common_prefix_search_with_rest<C, M>(
+        &self,
+        query: impl AsRef<[Label]>,
+    ) -> (Keys<PrefixIter<'_, Label, (), C, M>>, usize)
+    where
+        C: TryFromIterator<Label, M>,
+        Label: Clone,
+    {
+        let (iter, consumed) = self.0.common_prefix_search_with_rest(query);
+        (iter.keys(), consumed)
+    }
+
+    /// Return the common prefixes of `query` with whether each one is also a
+    /// prefix of longer entries. See [map::Trie::common_prefix_search_annotated].
+    ///
+    /// ```rust
+    /// use trie_rs::Trie;
+    ///
+    /// let trie = Trie::from_iter(["a", "app", "application"]);
+    /// let results: Vec<(String, bool)> = trie.common_prefix_search_annotated("application").collect();
+    /// assert_eq!(results, [("a".into(), true), ("app".into(), true), ("application".into(), false)]);
+    /// ```
+    pub fn common_prefix_search_annotated<C, M>(
+        &self,
+        query: impl AsRef<[Label]>,
+    ) -> AnnotatedKeys<'_, Label, C, M>
+    where
+        C: TryFromIterator<Label, M>,
+        Label: Clone,
+    {
+        self.0
+            .common_prefix_search_annotated(query)
+            .map(|(key, _, is_further_prefix)| (key, is_further_prefix))
+    }
+
+    /// Return all entries that match `query` in ascending lexicographic order.
+    /// See [map::Trie::predictive_search].
     pub fn predictive_search<C, M>(
         &self,
         query: impl AsRef<[Label]>,
@@ -78,6 +161,142 @@ impl<Label: Ord> Trie<Label> {
         self.0.predictive_search(query).keys()
     }
 
+    /// Return all entries that match `query` in `order`. See
+    /// [map::Trie::predictive_search_ordered].
+    pub fn predictive_search_ordered<C, M>(
+        &self,
+        query: impl AsRef<[Label]>,
+        order: SearchOrder,
+    ) -> Keys<OrderedSearchIter<'_, Label, (), C, M>>
+    where
+        C: TryFromIterator<Label, M> + Clone,
+        Label: Clone,
+    {
+        self.0.predictive_search_ordered(query, order).keys()
+    }
+
+    /// Return all entries that match `query` with the number of labels in
+    /// each; values are `&()`. See [crate::iter::SearchIter::with_depth].
+    pub fn predictive_search_with_depth<C, M>(
+        &self,
+        query: impl AsRef<[Label]>,
+    ) -> WithDepth<'_, Label, (), C, M>
+    where
+        C: TryFromIterator<Label, M> + Clone,
+        Label: Clone,
+    {
+        self.0.predictive_search(query).with_depth()
+    }
+
+    /// Return the entries grouped by their first label. See
+    /// [map::Trie::group_by_first].
+    pub fn group_by_first<C, M>(
+        &self,
+    ) -> impl Iterator<Item = (&Label, Keys<SearchIter<'_, Label, (), C, M>>)>
+    where
+        C: TryFromIterator<Label, M> + Clone,
+        Label: Clone,
+    {
+        self.0
+            .group_by_first()
+            .map(|(label, group)| (label, group.keys()))
+    }
+
+    /// Return all entries that match `query` except `query` itself. See
+    /// [map::Trie::predictive_search_strict].
+    pub fn predictive_search_strict<C, M>(
+        &self,
+        query: impl AsRef<[Label]>,
+    ) -> Keys<BoundedSearchIter<'_, Label, (), C, M>>
+    where
+        C: TryFromIterator<Label, M>,
+        Label: Clone,
+    {
+        self.0.predictive_search_strict(query).keys()
+    }
+
+    /// Return all entries that match `query`, or an error for each key that
+    /// cannot be collected into `C`. See [map::Trie::try_predictive_search].
+    pub fn try_predictive_search<C, M>(
+        &self,
+        query: impl AsRef<[Label]>,
+    ) -> TryKeysOf<SearchIter<'_, Label, (), Vec<Label>, Collect>, C, M>
+    where
+        C: TryFromIterator<Label, M>,
+        Label: Clone,
+    {
+        TryKeys::new(self.0.try_predictive_search(query))
+    }
+
+    /// Return the common prefixes of `query`, or an error for each key that
+    /// cannot be collected into `C`.
+    pub fn try_common_prefix_search<C, M>(
+        &self,
+        query: impl AsRef<[Label]>,
+    ) -> TryKeysOf<PrefixIter<'_, Label, (), Vec<Label>, Collect>, C, M>
+    where
+        C: TryFromIterator<Label, M>,
+        Label: Clone,
+    {
+        TryKeys::new(self.0.try_common_prefix_search(query))
+    }
+
+    /// Return the postfixes of all entries that match `query`, or an error
+    /// for each postfix that cannot be collected into `C`.
+    pub fn try_postfix_search<C, M>(
+        &self,
+        query: impl AsRef<[Label]>,
+    ) -> TryKeysOf<PostfixIter<'_, Label, (), Vec<Label>, Collect>, C, M>
+    where
+        C: TryFromIterator<Label, M>,
+        Label: Clone,
+    {
+        TryKeys::new(self.0.try_postfix_search(query))
+    }
+
+    /// Return all entries that match `query`, lending each key as a slice of
+    /// one reusable buffer. See [map::Trie::predictive_search_cursor].
+    pub fn predictive_search_cursor(
+        &self,
+        query: impl AsRef<[Label]>,
+    ) -> SearchCursor<'_, Label, ()>
+    where
+        Label: Clone,
+    {
+        self.0.predictive_search_cursor(query)
+    }
+
+    /// Return all entries with exactly `len` labels.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use trie_rs::Trie;
+    ///
+    /// let trie = Trie::from_iter(["a", "app", "apple", "better", "application"]);
+    /// let results: Vec<String> = trie.keys_of_len(3).collect();
+    /// assert_eq!(results, vec!["app"]);
+    /// ```
+    pub fn keys_of_len<C, M>(&self, len: usize) -> Keys<BoundedSearchIter<'_, Label, (), C, M>>
+    where
+        C: TryFromIterator<Label, M>,
+        Label: Clone,
+    {
+        self.0.keys_of_len(len).keys()
+    }
+
+    /// Return all entries with from `min` to `max` labels, inclusive.
+    pub fn keys_in_len_range<C, M>(
+        &self,
+        min: usize,
+        max: usize,
+    ) -> Keys<BoundedSearchIter<'_, Label, (), C, M>>
+    where
+        C: TryFromIterator<Label, M>,
+        Label: Clone,
+    {
+        self.0.keys_in_len_range(min, max).keys()
+    }
+
     /// Return the postfixes of all entries that match `query`.
     ///
     /// # Arguments
@@ -136,12 +355,42 @@ impl<Label: Ord> Trie<Label> {
         self.postfix_search([])
     }
 
+    /// Return every node but the root in breadth-first order. See
+    /// [map::Trie::bf_nodes].
+    pub fn bf_nodes(&self) -> BfNodes<'_, Label, ()> {
+        self.0.bf_nodes()
+    }
+
+    /// Return every node but the root in depth-first order. See
+    /// [map::Trie::df_nodes].
+    pub fn df_nodes(&self) -> DfNodes<'_, Label, ()> {
+        self.0.df_nodes()
+    }
+
     /// Create an incremental search. Useful for interactive applications. See
     /// [crate::inc_search] for details.
     pub fn inc_search(&self) -> IncSearch<'_, Label, ()> {
         IncSearch::new(&self.0)
     }
 
+    /// Create an incremental search starting at `position`. See
+    /// [map::Trie::inc_search_from].
+    pub fn inc_search_from(&self, position: Position) -> IncSearch<'_, Label, ()> {
+        IncSearch::resume(&self.0, position)
+    }
+
+    /// Create an incremental search that tolerates up to `max_distance`
+    /// edits. See [FuzzyIncSearch].
+    pub fn fuzzy_inc_search(&self, max_distance: usize) -> FuzzyIncSearch<'_, Label, ()> {
+        FuzzyIncSearch::new(&self.0, max_distance)
+    }
+
+    /// Create a common prefix search that is fed one label at a time. See
+    /// [map::Trie::common_prefix_matcher].
+    pub fn common_prefix_matcher(&self) -> CommonPrefixMatcher<'_, Label, ()> {
+        CommonPrefixMatcher::new(&self.0)
+    }
+
     /// Return true if `query` is a prefix.
     ///
     /// Note: A prefix may be an exact match or not, and an exact match may be a
@@ -150,6 +399,178 @@ impl<Label: Ord> Trie<Label> {
         self.0.is_prefix(query)
     }
 
+    /// Return the number of labels in `query` if it is an exact match. See
+    /// [map::Trie::exact_match_len].
+    pub fn exact_match_len(&self, query: impl AsRef<[Label]>) -> Option<usize> {
+        self.0.exact_match_len(query)
+    }
+
+    /// Add the entries of `other` to this trie. See
+    /// [map::Trie::extend_from].
+    pub fn extend_from(&mut self, other: &Self)
+    where
+        Label: Clone,
+    {
+        self.0.extend_from(&other.0, |_, _| {});
+    }
+
+    /// Return the number of labels in `key` if it is an entry or a prefix of
+    /// one. See [map::Trie::depth_of].
+    pub fn depth_of(&self, key: impl AsRef<[Label]>) -> Option<usize> {
+        self.0.depth_of(key)
+    }
+
+    /// Return true if `key` is an entry and no longer entry extends it. See
+    /// [map::Trie::is_leaf].
+    pub fn is_leaf(&self, key: impl AsRef<[Label]>) -> bool {
+        self.0.is_leaf(key)
+    }
+
+    /// Return the number of entries in the subtree rooted at `node`. See
+    /// [map::Trie::subtree_count].
+    pub fn subtree_count(&self, node: Position) -> usize {
+        self.0.subtree_count(node)
+    }
+
+    /// Return the deepest node on the paths to both entries `a` and `b`. See
+    /// [map::Trie::lcp_node].
+    pub fn lcp_node(&self, a: impl AsRef<[Label]>, b: impl AsRef<[Label]>) -> Option<Position> {
+        self.0.lcp_node(a, b)
+    }
+
+    /// Return the length of the longest common prefix of entries `a` and
+    /// `b`. See [map::Trie::common_prefix_len].
+    pub fn common_prefix_len(
+        &self,
+        a: impl AsRef<[Label]>,
+        b: impl AsRef<[Label]>,
+    ) -> Option<usize> {
+        self.0.common_prefix_len(a, b)
+    }
+
+    /// Return the stored key if `query` is an exact match. See
+    /// [map::Trie::get_key_value].
+    pub fn get_key<C, M>(&self, query: impl AsRef<[Label]>) -> Option<C>
+    where
+        C: TryFromIterator<Label, M>,
+        Label: Clone,
+    {
+        self.0.get_key_value(query).map(|(key, _)| key)
+    }
+
+    /// Return true if query is an exact match, comparing a borrowed form of
+    /// each label. See [map::Trie::exact_match_borrowed].
+    pub fn exact_match_borrowed<Q>(&self, query: &[&Q]) -> bool
+    where
+        Label: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.0.exact_match_borrowed(query).is_some()
+    }
+
+    /// Return true if `query` is a prefix, comparing a borrowed form of each
+    /// label. See [map::Trie::is_prefix_borrowed].
+    pub fn is_prefix_borrowed<Q>(&self, query: &[&Q]) -> bool
+    where
+        Label: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.0.is_prefix_borrowed(query)
+    }
+
+    /// Return true if any entry is a prefix of `query`. See
+    /// [map::Trie::has_common_prefix].
+    pub fn has_common_prefix(&self, query: impl AsRef<[Label]>) -> bool {
+        self.0.has_common_prefix(query)
+    }
+
+    /// Return the labels that follow `key` in some entry. See
+    /// [map::Trie::children_labels_at].
+    pub fn children_labels_at(&self, key: impl AsRef<[Label]>) -> impl Iterator<Item = &Label> {
+        self.0.children_labels_at(key)
+    }
+
+    /// Return each label that follows `prefix` with whether it completes an
+    /// entry and how many labels follow it. See [map::Trie::children_summary].
+    pub fn children_summary(
+        &self,
+        prefix: impl AsRef<[Label]>,
+    ) -> impl Iterator<Item = (&Label, bool, usize)> {
+        self.0.children_summary(prefix)
+    }
+
+    /// Return true if any entry starts with `prefix`. See
+    /// [map::Trie::has_completion].
+    pub fn has_completion(&self, prefix: impl AsRef<[Label]>) -> bool {
+        self.0.has_completion(prefix)
+    }
+
+    /// Return true if no entry is a prefix of another entry.
+    pub fn is_prefix_free(&self) -> bool {
+        self.0.is_prefix_free()
+    }
+
+    /// Return a trie of only the entries that start with `prefix`. See
+    /// [map::Trie::retain_prefix].
+    pub fn retain_prefix(&self, prefix: impl AsRef<[Label]>) -> Self
+    where
+        Label: Clone,
+    {
+        Trie(self.0.retain_prefix(prefix))
+    }
+
+    /// Return a trie of the entries in exactly one of `self` and `other`.
+    ///
+    /// ```rust
+    /// use trie_rs::Trie;
+    ///
+    /// let a = Trie::from_iter(["a", "app", "apple"]);
+    /// let b = Trie::from_iter(["app", "better"]);
+    /// assert_eq!(a.symmetric_difference(&b), Trie::from_iter(["a", "apple", "better"]));
+    /// ```
+    pub fn symmetric_difference(&self, other: &Self) -> Self
+    where
+        Label: Clone,
+    {
+        let mut builder = super::TrieBuilder::new();
+        for (this, that) in [(self, other), (other, self)] {
+            for key in this.iter::<Vec<Label>, _>() {
+                if !that.exact_match(&key) {
+                    builder.insert(key);
+                }
+            }
+        }
+        builder.build()
+    }
+
+    /// Return the number of entries that start with `prefix`. See
+    /// [map::Trie::prefix_count].
+    pub fn prefix_count(&self, prefix: impl AsRef<[Label]>) -> usize {
+        self.0.prefix_count(prefix)
+    }
+
+    /// Return the span of each entry found anywhere in `text`. See
+    /// [map::Trie::find_in].
+    pub fn find_in<'a>(
+        &'a self,
+        text: &'a [Label],
+        non_overlapping: bool,
+    ) -> impl Iterator<Item = std::ops::Range<usize>> + 'a {
+        self.0
+            .find_in(text, non_overlapping)
+            .map(|m| m.start..m.end)
+    }
+
+    /// Consume the trie and return its keys in lexicographic order. See
+    /// [map::Trie::into_keys].
+    pub fn into_keys<C, M>(self) -> std::vec::IntoIter<C>
+    where
+        C: TryFromIterator<Label, M>,
+        Label: Clone,
+    {
+        self.0.into_keys()
+    }
+
     /// Return the longest shared prefix of `query`.
     pub fn longest_prefix<C, M>(&self, query: impl AsRef<[Label]>) -> Option<C>
     where
@@ -158,8 +579,193 @@ impl<Label: Ord> Trie<Label> {
     {
         self.0.longest_prefix(query)
     }
+
+    /// Return the longest entry that is a prefix of `query`. See
+    /// [map::Trie::longest_common_prefix].
+    pub fn longest_common_prefix<C, M>(&self, query: impl AsRef<[Label]>) -> Option<C>
+    where
+        C: TryFromIterator<Label, M>,
+        Label: Clone,
+    {
+        self.0.longest_common_prefix(query).map(|(key, _)| key)
+    }
+
+    /// Return each entry that is a proper prefix of `key`, from shortest to
+    /// longest, if `key` itself is an entry. See [map::Trie::prefixes_of].
+    pub fn prefixes_of<C, M>(&self, key: impl AsRef<[Label]>) -> Keys<std::vec::IntoIter<(C, &())>>
+    where
+        C: TryFromIterator<Label, M>,
+        Label: Clone,
+    {
+        self.0.prefixes_of(key).keys()
+    }
+
+    /// Return the smallest key, or `None` if the trie is empty. See
+    /// [map::Trie::first_key].
+    pub fn first_key<C, M>(&self) -> Option<C>
+    where
+        C: TryFromIterator<Label, M>,
+        Label: Clone,
+    {
+        self.0.first_key().map(|(key, _)| key)
+    }
+
+    /// Return the largest key, or `None` if the trie is empty. See
+    /// [map::Trie::last_key].
+    pub fn last_key<C, M>(&self) -> Option<C>
+    where
+        C: TryFromIterator<Label, M>,
+        Label: Clone,
+    {
+        self.0.last_key().map(|(key, _)| key)
+    }
+
+    /// Return every entry that matches `pattern` in lexicographic order. See
+    /// [map::Trie::pattern_search].
+    pub fn pattern_search<C, M>(&self, pattern: &[Glob<Label>]) -> Vec<C>
+    where
+        C: TryFromIterator<Label, M>,
+        Label: Clone,
+    {
+        self.0
+            .pattern_search(pattern)
+            .into_iter()
+            .map(|(key, _)| key)
+            .collect()
+    }
+
+    /// Return true if any entry matches `pattern`. See
+    /// [map::Trie::matches_pattern].
+    pub fn matches_pattern(&self, pattern: &[Glob<Label>]) -> bool {
+        self.0.matches_pattern(pattern)
+    }
+
+    /// Return the key closest to `query` by edit distance and that distance.
+    /// See [map::Trie::nearest].
+    pub fn nearest<C, M>(&self, query: impl AsRef<[Label]>) -> Option<(C, usize)>
+    where
+        C: TryFromIterator<Label, M>,
+        Label: Clone,
+    {
+        self.0
+            .nearest(query)
+            .map(|(key, _, distance)| (key, distance))
+    }
+
+    /// Return the `k` keys closest to `query` by edit distance and their
+    /// distances. See [map::Trie::knn].
+    pub fn knn<C, M>(&self, query: impl AsRef<[Label]>, k: usize) -> Vec<(C, usize)>
+    where
+        C: TryFromIterator<Label, M>,
+        Label: Clone,
+    {
+        self.0
+            .knn(query, k)
+            .into_iter()
+            .map(|(key, _, distance)| (key, distance))
+            .collect()
+    }
+
+    /// Return up to `limit` keys that start with a prefix within
+    /// `max_distance` edits of `query`. See [map::Trie::fuzzy_predictive_search].
+    pub fn fuzzy_predictive_search<C, M>(
+        &self,
+        query: impl AsRef<[Label]>,
+        max_distance: usize,
+        limit: usize,
+    ) -> Vec<C>
+    where
+        C: TryFromIterator<Label, M>,
+        Label: Clone,
+    {
+        self.0
+            .fuzzy_predictive_search(query, max_distance, limit)
+            .into_iter()
+            .map(|(key, _)| key)
+            .collect()
+    }
+
+    /// Return the approximate number of bytes this trie has allocated on the
+    /// heap. See [map::Trie::heap_size_bytes].
+    pub fn heap_size_bytes(&self) -> usize {
+        self.0.heap_size_bytes()
+    }
+
+    /// Release any excess capacity held by this trie back to the allocator.
+    pub fn shrink_to_fit(&mut self) {
+        self.0.shrink_to_fit()
+    }
+
+    /// Return the structure of this trie. See [map::Trie::stats].
+    pub fn stats(&self) -> map::Stats {
+        self.0.stats()
+    }
+
+    /// Verify the trie's structural invariants. See [map::Trie::check].
+    pub fn check(&self) -> Result<(), map::InvariantError> {
+        self.0.check()
+    }
+
+    /// Call `visit` with every node's key that starts with `query` and whether
+    /// it is an exact match. See [map::Trie::predictive_search_visit].
+    pub fn predictive_search_visit(
+        &self,
+        query: impl AsRef<[Label]>,
+        mut visit: impl FnMut(&[Label], bool) -> map::Descent,
+    ) where
+        Label: Clone,
+    {
+        self.0
+            .predictive_search_visit(query, |key, value| visit(key, value.is_some()))
+    }
+
+    /// Call `f` with every key that starts with `query`, building keys in
+    /// `buf`. See [map::Trie::predictive_search_buffered].
+    pub fn predictive_search_buffered(
+        &self,
+        query: impl AsRef<[Label]>,
+        buf: &mut Vec<Label>,
+        mut f: impl FnMut(&[Label]),
+    ) where
+        Label: Clone,
+    {
+        self.0
+            .predictive_search_buffered(query, buf, |key, _| f(key))
+    }
+
+    /// Call `f` with every key in lexicographic order without allocating. See
+    /// [map::Trie::for_each].
+    pub fn for_each(&self, mut f: impl FnMut(&[Label]))
+    where
+        Label: Clone,
+    {
+        self.0.for_each(|key, _| f(key))
+    }
+
+    /// Fold every key into an accumulator in lexicographic order. See
+    /// [map::Trie::fold].
+    pub fn fold<B>(&self, init: B, mut f: impl FnMut(B, &[Label]) -> B) -> B
+    where
+        Label: Clone,
+    {
+        self.0.fold(init, |acc, key, _| f(acc, key))
+    }
+
+    /// Walk every node of the trie depth-first. See [map::Trie::visit].
+    pub fn visit<V: Visitor<Label, ()>>(&self, visitor: &mut V) {
+        self.0.visit(visitor)
+    }
 }
 
+/// Collect bare keys into a trie.
+///
+/// ```rust
+/// use trie_rs::Trie;
+///
+/// let words = vec!["a".to_string(), "app".to_string()];
+/// let trie: Trie<u8> = words.into_iter().collect();
+/// assert!(trie.exact_match("app"));
+/// ```
 impl<Label, C> FromIterator<C> for Trie<Label>
 where
     C: AsRef<[Label]>,
@@ -178,6 +784,132 @@ where
     }
 }
 
+/// Collect a trie from a parallel iterator. See [map::Trie]'s
+/// `FromParallelIterator`.
+#[cfg(feature = "rayon")]
+impl<Label, C> rayon::iter::FromParallelIterator<C> for Trie<Label>
+where
+    C: AsRef<[Label]> + Send,
+    Label: Ord + Clone + Send,
+{
+    fn from_par_iter<I>(par_iter: I) -> Self
+    where
+        I: rayon::iter::IntoParallelIterator<Item = C>,
+    {
+        use rayon::iter::ParallelIterator;
+        Trie(par_iter.into_par_iter().map(|key| (key, ())).collect())
+    }
+}
+
+/// Two tries are equal if they contain the same entries.
+impl<Label: Ord> PartialEq for Trie<Label> {
+    fn eq(&self, other: &Self) -> bool {
+        self.0 == other.0
+    }
+}
+
+impl<Label: Ord> Eq for Trie<Label> {}
+
+impl<Label: Ord + FixedBytes> Trie<Label> {
+    /// Write this trie to `w` in a versioned binary format. See
+    /// [map::Trie::write_to].
+    pub fn write_to(&self, w: &mut impl Write) -> io::Result<()> {
+        self.0.write_to(w)
+    }
+
+    /// Read a trie written by [Trie::write_to] from `r`. See
+    /// [map::Trie::read_from].
+    pub fn read_from(r: &mut impl Read) -> io::Result<Self> {
+        map::Trie::read_from(r).map(Trie)
+    }
+}
+
+impl Trie<u8> {
+    /// Return all entries that match `query` decoded as UTF-8. See
+    /// [map::Trie::predictive_search_str].
+    pub fn predictive_search_str(
+        &self,
+        query: &str,
+    ) -> impl Iterator<Item = Result<String, Utf8Error>> + '_ {
+        self.0
+            .predictive_search_str(query)
+            .map(|result| result.map(|(key, _)| key))
+    }
+
+    /// Return the common prefixes of `query` decoded as UTF-8.
+    pub fn common_prefix_search_str(
+        &self,
+        query: &str,
+    ) -> impl Iterator<Item = Result<String, Utf8Error>> + '_ {
+        self.0
+            .common_prefix_search_str(query)
+            .map(|result| result.map(|(key, _)| key))
+    }
+
+    /// Return the postfixes of all entries that match `query` decoded as UTF-8.
+    pub fn postfix_search_str(
+        &self,
+        query: &str,
+    ) -> impl Iterator<Item = Result<String, Utf8Error>> + '_ {
+        self.0
+            .postfix_search_str(query)
+            .map(|result| result.map(|(key, _)| key))
+    }
+}
+
+impl Trie<char> {
+    /// Return true if the `char`s of `query` are an exact match.
+    pub fn exact_match_str(&self, query: &str) -> bool {
+        self.0.exact_match_str(query).is_some()
+    }
+
+    /// Return true if the `char`s of `query` are a prefix.
+    pub fn is_prefix_str(&self, query: &str) -> bool {
+        self.0.is_prefix_str(query)
+    }
+
+    /// Return all entries that start with the `char`s of `query`.
+    pub fn predictive_search_str(
+        &self,
+        query: &str,
+    ) -> Keys<SearchIter<'_, char, (), String, Collect>> {
+        self.0.predictive_search_str(query).keys()
+    }
+
+    /// Return the entries that are prefixes of the `char`s of `query`.
+    pub fn common_prefix_search_str(
+        &self,
+        query: &str,
+    ) -> Keys<PrefixIter<'_, char, (), String, Collect>> {
+        self.0.common_prefix_search_str(query).keys()
+    }
+
+    /// Return the postfixes of all entries that start with the `char`s of
+    /// `query`.
+    pub fn postfix_search_str(
+        &self,
+        query: &str,
+    ) -> Keys<PostfixIter<'_, char, (), String, Collect>> {
+        self.0.postfix_search_str(query).keys()
+    }
+}
+
+#[cfg(feature = "unicode-segmentation")]
+impl Trie<String> {
+    /// Return true if the grapheme clusters of `query` are an exact match.
+    pub fn exact_match_graphemes(&self, query: &str) -> bool {
+        self.0.exact_match_graphemes(query).is_some()
+    }
+
+    /// Return all entries that start with the grapheme clusters of `query`.
+    pub fn predictive_search_graphemes(
+        &self,
+        query: &str,
+    ) -> Keys<SearchIter<'_, String, (), String, crate::try_collect::Collect>> {
+        self.0.predictive_search_graphemes(query).keys()
+    }
+}
+
 #[cfg(test)]
 mod search_tests {
     use crate::{Trie, TrieBuilder};
@@ -194,6 +926,36 @@ mod search_tests {
         builder.build()
     }
 
+    #[test]
+    fn group_by_first() {
+        let trie = build_trie();
+        let groups: Vec<(u8, Vec<String>)> = trie
+            .group_by_first()
+            .map(|(label, group)| (*label, group.collect()))
+            .collect();
+        assert_eq!(
+            groups[0],
+            (
+                b'a',
+                vec![
+                    "a".into(),
+                    "app".into(),
+                    "apple".into(),
+                    "application".into()
+                ]
+            )
+        );
+        assert_eq!(groups[1], (b'b', vec!["better".into()]));
+        assert_eq!(groups.len(), 3);
+    }
+
+    #[test]
+    fn find_in() {
+        let trie = build_trie();
+        let spans: Vec<_> = trie.find_in(b"an applet", true).collect();
+        assert_eq!(spans, [0..1, 3..8]);
+    }
+
     #[test]
     fn trie_from_iter() {
         let trie = Trie::<u8>::from_iter(["a", "app", "apple", "better", "application"]);
@@ -207,18 +969,62 @@ mod search_tests {
         assert!(trie.exact_match("application"));
     }
 
+    #[test]
+    fn str_searches() {
+        let trie = build_trie();
+        let results: Result<Vec<String>, _> = trie.predictive_search_str("app").collect();
+        assert_eq!(results.unwrap(), ["app", "apple", "application"]);
+        let results: Result<Vec<String>, _> = trie.common_prefix_search_str("apple").collect();
+        assert_eq!(results.unwrap(), ["a", "app", "apple"]);
+        let results: Result<Vec<String>, _> = trie.postfix_search_str("アップ").collect();
+        assert_eq!(results.unwrap(), ["ル🍎"]);
+    }
+
+    #[test]
+    fn collect_owned_keys() {
+        let trie: Trie<u8> = vec!["app".to_string(), "a".to_string(), "app".to_string()]
+            .into_iter()
+            .collect();
+        let keys: Vec<String> = trie.iter().collect();
+        assert_eq!(keys, ["a", "app"]);
+        let trie: Trie<char> = [vec!['b'], vec!['a', 'b']].into_iter().collect();
+        assert!(trie.exact_match(['a', 'b']));
+    }
+
     #[test]
     fn clone() {
         let trie = build_trie();
         let _c: Trie<u8> = trie.clone();
     }
 
+    #[test]
+    fn eq() {
+        let trie = build_trie();
+        assert_eq!(trie, trie.clone());
+        let reordered =
+            Trie::<u8>::from_iter(["アップル🍎", "application", "better", "apple", "app", "a"]);
+        assert_eq!(trie, reordered);
+        assert_ne!(trie, Trie::<u8>::from_iter(["a", "app"]));
+    }
+
+    #[test]
+    fn nearest() {
+        let trie = build_trie();
+        assert_eq!(trie.nearest("aple"), Some(("apple".to_string(), 1)));
+        assert_eq!(trie.nearest("betta"), Some(("better".to_string(), 2)));
+        let results: Vec<(String, usize)> = trie.knn("ap", 2);
+        assert_eq!(results, [("a".to_string(), 1), ("app".to_string(), 1)]);
+        let results: Vec<String> = trie.fuzzy_predictive_search("bet", 1, 10);
+        assert_eq!(results, ["better"]);
+    }
+
     #[rustfmt::skip]
+    #[cfg(not(any(feature = "counts", feature = "rand")))]
     #[test]
     fn print_debug() {
         let trie: Trie<u8> = ["a"].into_iter().collect();
         assert_eq!(format!("{:?}", trie),
-"Trie(Trie { louds: Louds { lbs: Fid { byte_vec: [160], bit_len: 5, chunks: Chunks { chunks: [Chunk { value: 2, blocks: Blocks { blocks: [Block { value: 1, length: 1 }, Block { value: 1, length: 1 }, Block { value: 2, length: 1 }, Block { value: 2, length: 1 }], blocks_cnt: 4 } }, Chunk { value: 2, blocks: Blocks { blocks: [Block { value: 0, length: 1 }], blocks_cnt: 1 } }], chunks_cnt: 2 }, table: PopcountTable { bit_length: 1, table: [0, 1] } } }, trie_labels: [TrieLabel { label: 97, value: Some(()) }] })"
+"Trie(Trie { louds: Louds { lbs: Fid { byte_vec: [160], bit_len: 5, chunks: Chunks { chunks: [Chunk { value: 2, blocks: Blocks { blocks: [Block { value: 1, length: 1 }, Block { value: 1, length: 1 }, Block { value: 2, length: 1 }, Block { value: 2, length: 1 }], blocks_cnt: 4 } }, Chunk { value: 2, blocks: Blocks { blocks: [Block { value: 0, length: 1 }], blocks_cnt: 1 } }], chunks_cnt: 2 }, table: PopcountTable { bit_length: 1, table: [0, 1] } } }, labels: [97], terminals: Fid { byte_vec: [64], bit_len: 2, chunks: Chunks { chunks: [Chunk { value: 0, blocks: Blocks { blocks: [Block { value: 0, length: 1 }], blocks_cnt: 1 } }, Chunk { value: 1, blocks: Blocks { blocks: [Block { value: 1, length: 1 }], blocks_cnt: 1 } }], chunks_cnt: 2 }, table: PopcountTable { bit_length: 1, table: [0, 1] } }, values: [()], insertion_order: None })"
         );
     }
 
@@ -230,10 +1036,114 @@ mod search_tests {
         builder.push("a");
         builder.push("app");
         assert_eq!(format!("{:?}", builder),
-"TrieBuilder(TrieBuilder { naive_trie: Root(NaiveTrieRoot { children: [IntermOrLeaf(NaiveTrieIntermOrLeaf { children: [IntermOrLeaf(NaiveTrieIntermOrLeaf { children: [IntermOrLeaf(NaiveTrieIntermOrLeaf { children: [], label: 112, value: Some(()) })], label: 112, value: None })], label: 97, value: Some(()) })] }) })"
+"TrieBuilder(TrieBuilder { naive_trie: Root(NaiveTrieRoot { children: [IntermOrLeaf(NaiveTrieIntermOrLeaf { children: [IntermOrLeaf(NaiveTrieIntermOrLeaf { children: [IntermOrLeaf(NaiveTrieIntermOrLeaf { children: [], label: 112, value: Some(()), order: 0 })], label: 112, value: None, order: 0 })], label: 97, value: Some(()), order: 0 })] }), capacity: 0, insertion_order: None, on_duplicate: Overwrite, duplicates: 0 })"
         );
     }
 
+    #[test]
+    fn try_searches() {
+        let mut builder = TrieBuilder::new();
+        builder.push("app");
+        builder.push(b"ap\xff");
+        let trie = builder.build();
+        let results: Vec<Result<String, _>> = trie.try_predictive_search("ap").collect();
+        assert!(results[1].is_err());
+        assert_eq!(results[0].as_ref().unwrap(), "app");
+        let results: Result<Vec<String>, _> = trie.try_common_prefix_search("app").collect();
+        assert_eq!(results.unwrap(), ["app"]);
+        let results: Result<Vec<String>, _> = trie.try_postfix_search("a").collect();
+        assert!(results.is_err());
+    }
+
+    #[test]
+    fn prefixes_of() {
+        let trie = build_trie();
+        let results: Vec<String> = trie.prefixes_of("apple").collect();
+        assert_eq!(results, ["a", "app"]);
+    }
+
+    #[test]
+    fn exact_match_many() {
+        let trie = build_trie();
+        let queries = ["a", "ap", "app", "apple", "", "applesauce", "アップル🍎"];
+        let results: Vec<bool> = trie.exact_match_many(queries).collect();
+        let expected: Vec<bool> = queries.iter().map(|q| trie.exact_match(q)).collect();
+        assert_eq!(results, expected);
+        assert_eq!(results, [true, false, true, true, false, false, true]);
+    }
+
+    #[test]
+    fn char_str_queries() {
+        let mut builder = TrieBuilder::new();
+        for key in ["a", "app", "apple", "application", "アップル🍎"] {
+            builder.insert(key.chars());
+        }
+        let trie = builder.build();
+        assert!(trie.exact_match_str("アップル🍎"));
+        assert!(!trie.exact_match_str("アップ"));
+        assert!(trie.is_prefix_str("アップ"));
+        let results: Vec<String> = trie.predictive_search_str("appl").collect();
+        assert_eq!(results, ["apple", "application"]);
+        let results: Vec<String> = trie.common_prefix_search_str("apple").collect();
+        assert_eq!(results, ["a", "app", "apple"]);
+        let results: Vec<String> = trie.postfix_search_str("appl").collect();
+        assert_eq!(results, ["e", "ication"]);
+    }
+
+    #[test]
+    fn contains_all_any() {
+        let trie = build_trie();
+        assert!(trie.contains_all(["a", "apple", "better"]));
+        assert!(!trie.contains_all(["a", "ap"]));
+        assert!(trie.contains_any(["ap", "application"]));
+        assert!(!trie.contains_any(["ap", "b"]));
+    }
+
+    mod symmetric_difference_tests {
+        use crate::Trie;
+
+        macro_rules! parameterized_tests {
+            ($($name:ident: $value:expr,)*) => {
+            $(
+                #[test]
+                fn $name() {
+                    let (a, b, expected): (&[&str], &[&str], &[&str]) = $value;
+                    let (a, b) = (Trie::<u8>::from_iter(a), Trie::<u8>::from_iter(b));
+                    let expected = Trie::<u8>::from_iter(expected);
+                    assert_eq!(a.symmetric_difference(&b), expected);
+                    assert_eq!(b.symmetric_difference(&a), expected);
+                }
+            )*
+            }
+        }
+
+        parameterized_tests! {
+            t1: (&["a", "app", "apple"], &["app", "better"], &["a", "apple", "better"]),
+            t2: (&["a", "app", "apple"], &["a", "app", "apple"], &[]),
+            t3: (&["a", "app"], &["apple", "better"], &["a", "app", "apple", "better"]),
+            t4: (&["a", "app"], &[], &["a", "app"]),
+            t5: (&[], &[], &[]),
+        }
+    }
+
+    #[test]
+    fn one_node_per_label() {
+        let keys = ["a", "app", "apple", "better", "application"];
+        let trie = Trie::<u8>::from_iter(keys);
+        // a, ap, app, appl, apple, appli..application, b..better
+        let stats = trie.stats();
+        assert_eq!(stats.nodes, 5 + 7 + 6);
+        assert_eq!(stats.terminals, keys.len());
+        for key in keys {
+            assert!(trie.exact_match(key));
+        }
+        for key in ["", "ap", "appl", "b", "betters"] {
+            assert!(!trie.exact_match(key));
+        }
+        let results: Vec<String> = trie.predictive_search("app").collect();
+        assert_eq!(results, ["app", "apple", "application"]);
+    }
+
     #[test]
     fn use_empty_queries() {
         let trie = build_trie();
@@ -243,6 +1153,17 @@ mod search_tests {
         let _ = trie.common_prefix_search::<String, _>("").next();
     }
 
+    #[test]
+    fn empty_query_semantics() {
+        let trie = build_trie();
+        let all: Vec<String> = trie.iter().collect();
+        let results: Vec<String> = trie.predictive_search("").collect();
+        assert_eq!(results, all);
+        let results: Vec<String> = trie.postfix_search("").collect();
+        assert_eq!(results, all);
+        assert_eq!(trie.common_prefix_search::<String, _>("").count(), 0);
+    }
+
     #[cfg(feature = "mem_dbg")]
     #[test]
     /// ```sh
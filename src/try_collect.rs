@@ -25,6 +25,17 @@
 //! [std::iter::FromIterator]. And `try_collect()` is `collect()` fallible
 //! cousin as well.
 //!
+//! # Which collections can fail?
+//!
+//! Any type that implements [std::iter::FromIterator], e.g., `Vec<Label>`,
+//! `VecDeque<Label>`, or `String` from `char`s, is collected without fail;
+//! its error type is `()`. Only `String` from `u8`s can fail, when a key is
+//! not valid UTF-8.
+//!
+//! Searches such as [crate::map::Trie::predictive_search] panic if a key
+//! cannot be collected. Their `try_*` variants, e.g.,
+//! [crate::map::Trie::try_predictive_search], yield a `Result` instead.
+//!
 //! # Technical Note
 //!
 //! `TryFromIterator<A, M>` accepts a generic type `M` marker parameter. In